@@ -0,0 +1,51 @@
+use wasm_bindgen::prelude::*;
+
+use crate::render::escape_xml;
+use crate::{LayoutEngine, WordCloud, WordPosition};
+
+// 根 <svg> 元素的无障碍标签
+const SVG_ARIA_LABEL: &str = "Word cloud";
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置 SVG 的整体描述，以 <desc> 元素输出，传入 None 表示不输出
+    #[wasm_bindgen]
+    pub fn set_alt_text(&mut self, alt_text: Option<String>) {
+        self.options.alt_text = alt_text;
+    }
+
+    // 设置整体描述后生成布局。单词的 aria_label 随输入给出，未给出时 SVG 中按文字和权重生成
+    #[wasm_bindgen]
+    pub fn generate_layout_with_word_accessibility_attributes(
+        &mut self,
+        words_json: String,
+        alt_text: Option<String>,
+    ) -> String {
+        self.set_alt_text(alt_text);
+        self.generate_layout(words_json)
+    }
+}
+
+impl LayoutEngine {
+    // 根 <svg> 元素上的 role 和 aria-label 属性
+    pub(crate) fn svg_root_accessibility(&self) -> String {
+        format!(r#" role="img" aria-label="{}""#, SVG_ARIA_LABEL)
+    }
+
+    // 紧跟根元素的 <desc>，没有设置 alt_text 时为空
+    pub(crate) fn svg_description(&self) -> String {
+        match &self.options.alt_text {
+            Some(alt_text) => format!("<desc>{}</desc>", escape_xml(alt_text)),
+            None => String::new(),
+        }
+    }
+
+    // 单词的 aria-label 属性，未指定时为 "文字, weight 权重"
+    pub(crate) fn svg_aria_label(&self, word: &WordPosition) -> String {
+        let label = match &word.aria_label {
+            Some(label) => label.clone(),
+            None => format!("{}, weight {:.0}", word.text, word.weight),
+        };
+        format!(r#" aria-label="{}""#, escape_xml(&label))
+    }
+}
@@ -0,0 +1,57 @@
+use wasm_bindgen::prelude::*;
+
+use crate::env::log;
+use crate::rng::RngStream;
+use crate::WordCloud;
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置单词动画：mode 为 "sequential" 或 "parallel"，
+    // 持续时间在 [duration_min_ms, duration_max_ms] 内按种子随机采样
+    #[wasm_bindgen]
+    pub fn set_animation(
+        &mut self,
+        mode: String,
+        stagger_ms: f64,
+        duration_min_ms: f64,
+        duration_max_ms: f64,
+    ) {
+        self.options.animation_mode = mode;
+        self.options.animation_stagger_ms = stagger_ms.max(0.0);
+        self.options.animation_duration_min_ms = duration_min_ms.max(0.0);
+        self.options.animation_duration_max_ms = duration_max_ms.max(0.0);
+    }
+
+    // 生成布局，并为每个单词附带 animation_start_time 和 animation_duration（毫秒），
+    // 供 WebGL 渲染器按顶点设置动画时间。sequential 模式下第 i 个放置的单词
+    // 在 stagger_ms * i 时开始，parallel 模式下全部从 0 开始
+    #[wasm_bindgen]
+    pub fn generate_layout_with_word_size_animation(&mut self, words_json: String) -> String {
+        self.generate_layout(words_json);
+
+        let sequential = match self.options.animation_mode.as_str() {
+            "sequential" => true,
+            "parallel" => false,
+            other => {
+                log(&format!("未知的动画模式: {}，使用 sequential", other));
+                true
+            }
+        };
+        let min = self.options.animation_duration_min_ms;
+        let max = self.options.animation_duration_max_ms.max(min);
+
+        let mut words = self.emit_layout(&self.placed);
+        for (index, word) in words.iter_mut().enumerate() {
+            let start = if sequential {
+                self.options.animation_stagger_ms * index as f64
+            } else {
+                0.0
+            };
+            // 按单词 key 派生随机数，同一单词的持续时间不受其他单词影响
+            let random = self.rng(RngStream::Animation, Some(word.key())).next_f64();
+            word.animation_start_time = Some(self.round_output(start));
+            word.animation_duration = Some(self.round_output(min + (max - min) * random));
+        }
+        serde_json::to_string(&words).unwrap_or_else(|_| "[]".to_string())
+    }
+}
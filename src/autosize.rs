@@ -0,0 +1,68 @@
+use wasm_bindgen::prelude::*;
+
+use crate::env::log;
+use crate::{LayoutContext, LayoutEngine, WordCloud, WordItem};
+
+// 黄金比例，画布宽高比为 1.618 : 1
+const GOLDEN_RATIO: f64 = 1.618_033_988_75;
+// 未指定目标面积时，单词总面积占画布面积的比例
+const AUTO_SIZE_FILL: f64 = 0.5;
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 按黄金比例的横向矩形自动确定画布尺寸，面积为 target_area_px，然后进行布局。
+    // target_area_px 为 0 时按单词包围盒的总面积估算；返回 [width, height]
+    #[wasm_bindgen]
+    pub fn auto_size_canvas(&mut self, words_json: String, target_area_px: u64) -> Vec<u32> {
+        let area = if target_area_px > 0 {
+            target_area_px as f64
+        } else {
+            match self.estimate_words_area(&words_json) {
+                Some(area) => area / AUTO_SIZE_FILL,
+                None => return vec![self.options.width, self.options.height],
+            }
+        };
+
+        let width = (area * GOLDEN_RATIO).sqrt().round().max(1.0) as u32;
+        let height = (area / width as f64).round().max(1.0) as u32;
+        log(&format!("自动画布尺寸: {}x{}", width, height));
+
+        self.set_size(width, height);
+        self.generate_layout(words_json);
+        vec![width, height]
+    }
+}
+
+impl LayoutEngine {
+    // 按当前字号设置估算所有单词包围盒的总面积
+    fn estimate_words_area(&self, words_json: &str) -> Option<f64> {
+        let mut words: Vec<WordItem> = match serde_json::from_str(words_json) {
+            Ok(words) => words,
+            Err(e) => {
+                log(&format!("解析词语JSON失败: {}", e));
+                return None;
+            }
+        };
+        for word in &mut words {
+            word.normalize_compound();
+        }
+
+        let weights = || words.iter().flat_map(|w| w.sizing_weights());
+        let context = LayoutContext {
+            min_weight: weights().fold(f64::INFINITY, f64::min),
+            max_weight: weights().fold(f64::NEG_INFINITY, f64::max),
+            center_x: 0.0,
+            center_y: 0.0,
+            palette: Vec::new(),
+        };
+
+        let area = words
+            .iter()
+            .map(|word| {
+                let measured = self.measure_word(word, &context, 1.0);
+                measured.width * measured.height
+            })
+            .sum::<f64>();
+        (area > 0.0).then_some(area)
+    }
+}
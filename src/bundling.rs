@@ -0,0 +1,115 @@
+// 连线的边捆绑：简化的力导向近似。
+// 每条连线表示为三次贝塞尔曲线，两个控制点先被拉向所在连通分组的重心，
+// 再在若干轮迭代中互相吸引方向相近、距离较近的连线的控制点，形成成束的曲线
+
+// 控制点初始时从端点向分组重心移动的比例
+const BUNDLE_STRENGTH: f64 = 0.6;
+// 力导向迭代的轮数和每轮移动的比例
+const BUNDLE_ITERATIONS: usize = 10;
+const BUNDLE_STEP: f64 = 0.3;
+
+type Vec2 = (f64, f64);
+
+// 参与捆绑的连线，group 为所在连通分组的编号
+pub(crate) struct Segment {
+    pub(crate) from: Vec2,
+    pub(crate) to: Vec2,
+    pub(crate) group: usize,
+}
+
+fn lerp(a: Vec2, b: Vec2, t: f64) -> Vec2 {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+fn distance(a: Vec2, b: Vec2) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+// 两条连线的相容度：方向越接近平行、中点越近，相容度越高，取值 0 到 1
+fn compatibility(a: &Segment, b: &Segment, scale: f64) -> f64 {
+    let (ax, ay) = (a.to.0 - a.from.0, a.to.1 - a.from.1);
+    let (bx, by) = (b.to.0 - b.from.0, b.to.1 - b.from.1);
+    let lengths = ax.hypot(ay) * bx.hypot(by);
+    if lengths == 0.0 {
+        return 0.0;
+    }
+    let angle = ((ax * bx + ay * by) / lengths).abs();
+    let spread = distance(lerp(a.from, a.to, 0.5), lerp(b.from, b.to, 0.5));
+    angle * scale / (scale + spread)
+}
+
+// 计算每条连线的两个贝塞尔控制点，分别靠近 from 和 to
+pub(crate) fn bundle(segments: &[Segment]) -> Vec<[Vec2; 2]> {
+    if segments.is_empty() {
+        return Vec::new();
+    }
+
+    // 各分组的重心
+    let groups = segments.iter().map(|s| s.group).max().unwrap_or(0) + 1;
+    let mut sums = vec![(0.0, 0.0, 0usize); groups];
+    for segment in segments {
+        let sum = &mut sums[segment.group];
+        sum.0 += segment.from.0 + segment.to.0;
+        sum.1 += segment.from.1 + segment.to.1;
+        sum.2 += 2;
+    }
+    let centroid = |group: usize| {
+        let (x, y, n) = sums[group];
+        (x / n as f64, y / n as f64)
+    };
+
+    let mut controls: Vec<[Vec2; 2]> = segments
+        .iter()
+        .map(|s| {
+            let center = centroid(s.group);
+            [
+                lerp(s.from, center, BUNDLE_STRENGTH),
+                lerp(s.to, center, BUNDLE_STRENGTH),
+            ]
+        })
+        .collect();
+
+    // 连线平均长度作为距离尺度
+    let scale =
+        segments.iter().map(|s| distance(s.from, s.to)).sum::<f64>() / segments.len() as f64;
+    let scale = scale.max(1.0);
+
+    for _ in 0..BUNDLE_ITERATIONS {
+        let previous = controls.clone();
+        for (i, a) in segments.iter().enumerate() {
+            let mut force = [(0.0, 0.0); 2];
+            let mut total = 0.0;
+            for (j, b) in segments.iter().enumerate() {
+                if i == j || a.group != b.group {
+                    continue;
+                }
+                let weight = compatibility(a, b, scale);
+                if weight <= 0.0 {
+                    continue;
+                }
+                // 方向相反的连线，控制点按相反顺序对应
+                let reversed = (a.to.0 - a.from.0) * (b.to.0 - b.from.0)
+                    + (a.to.1 - a.from.1) * (b.to.1 - b.from.1)
+                    < 0.0;
+                let other = if reversed {
+                    [previous[j][1], previous[j][0]]
+                } else {
+                    previous[j]
+                };
+                for k in 0..2 {
+                    force[k].0 += weight * (other[k].0 - previous[i][k].0);
+                    force[k].1 += weight * (other[k].1 - previous[i][k].1);
+                }
+                total += weight;
+            }
+            if total > 0.0 {
+                for k in 0..2 {
+                    controls[i][k].0 += BUNDLE_STEP * force[k].0 / total;
+                    controls[i][k].1 += BUNDLE_STEP * force[k].1 / total;
+                }
+            }
+        }
+    }
+
+    controls
+}
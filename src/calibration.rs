@@ -0,0 +1,562 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::env::log;
+use crate::{LayoutEngine, WordCloud};
+
+// 启发式估算中每个字符的宽度系数（相对字号）
+pub(crate) const DEFAULT_ADVANCE: f64 = 0.6;
+
+// 除 ASCII 可打印字符外额外测量的常用标点
+const EXTRA_PUNCTUATION: &str = "–—‘’“”…·•€£¥©®°";
+// 代表性的拉丁扩展字符和中日韩字符，其平均宽度用于同类中未测量的字符
+const LATIN_EXTENDED_SAMPLE: &str = "ÀÉÎÕÜÇÑàéîõüçñßøåæœł";
+const CJK_SAMPLE: &str = "中文字体测量汉字日本語한국어";
+// 实测宽度缓存的最大条目数，超出后清空重新积累
+const MEASURE_CACHE_LIMIT: usize = 10_000;
+// export_measurements 的格式版本
+const MEASUREMENTS_VERSION: u32 = 1;
+
+// 默认校准的常见拉丁字母字距调整对
+pub(crate) const DEFAULT_KERNING_PAIRS: [&str; 40] = [
+    "AV", "VA", "AW", "WA", "AY", "YA", "AT", "TA", "AC", "AO", "AQ", "AU", "LT", "LV", "LW", "LY",
+    "FA", "PA", "RT", "RV", "RW", "RY", "To", "Ta", "Te", "Tr", "Ty", "Va", "Ve", "Vo", "Wa", "We",
+    "Wo", "Ya", "Ye", "Yo", "av", "va", "ff", "rn",
+];
+
+// 按字体校准的字宽表，宽度均已除以测量时的字号
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct GlyphTable {
+    // 校准时使用的字体，字体改变后表格不再生效
+    font: String,
+    advances: HashMap<char, f64>,
+    latin_extended_advance: f64,
+    cjk_advance: f64,
+    // 字距调整对的修正量：整对实测宽度减去两个字符宽度之和，只记录非零的修正
+    #[serde(default)]
+    kerning: HashMap<String, f64>,
+}
+
+// 导出的实测宽度：按完整字体（含字号）分组，每组为 文本 -> 宽度
+#[derive(Serialize, Deserialize)]
+struct MeasurementSnapshot {
+    version: u32,
+    fonts: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+impl GlyphTable {
+    // 单个字符的宽度系数：已测量的字符使用实测值，其余按字符类别或默认系数估算
+    fn advance(&self, c: char) -> f64 {
+        if let Some(&advance) = self.advances.get(&c) {
+            return advance;
+        }
+        match c as u32 {
+            0x00C0..=0x024F | 0x1E00..=0x1EFF => self.latin_extended_advance,
+            0x1100..=0x11FF
+            | 0x2E80..=0x9FFF
+            | 0xAC00..=0xD7AF
+            | 0xF900..=0xFAFF
+            | 0xFF00..=0xFFEF => self.cjk_advance,
+            _ => DEFAULT_ADVANCE,
+        }
+    }
+
+    // 相邻两个字符的字距修正量，未校准的字符对为 0
+    fn kerning(&self, a: char, b: char) -> f64 {
+        if self.kerning.is_empty() {
+            return 0.0;
+        }
+        self.kerning
+            .get(&[a, b].iter().collect::<String>())
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 用 canvas 按当前字体测量常用字符的宽度，之后的宽度估算改为逐字符累加
+    #[wasm_bindgen]
+    pub fn calibrate_font(&mut self, ctx: &CanvasRenderingContext2d, sample_size: f64) -> bool {
+        let sample_size = if sample_size > 0.0 {
+            sample_size
+        } else {
+            100.0
+        };
+        ctx.save();
+        ctx.set_font(&format!(
+            "{} {}px {}",
+            self.options.font_weight, sample_size, self.options.font_family
+        ));
+
+        let characters = (' '..='~')
+            .chain(EXTRA_PUNCTUATION.chars())
+            .chain(LATIN_EXTENDED_SAMPLE.chars())
+            .chain(CJK_SAMPLE.chars());
+        let mut advances = HashMap::new();
+        for c in characters {
+            match ctx.measure_text(&c.to_string()) {
+                Ok(metrics) => {
+                    advances.insert(c, metrics.width() / sample_size);
+                }
+                Err(_) => {
+                    ctx.restore();
+                    log("字宽测量失败，保留原有估算方式");
+                    return false;
+                }
+            }
+        }
+
+        // 字距调整对按整对测量，与两个字符各自宽度之和的差即为修正量
+        let mut kerning = HashMap::new();
+        for pair in &self.options.kerning_pairs {
+            let mut chars = pair.chars();
+            let (a, b) = match (chars.next(), chars.next(), chars.next()) {
+                (Some(a), Some(b), None) => (a, b),
+                _ => continue,
+            };
+            let (Some(&advance_a), Some(&advance_b)) = (advances.get(&a), advances.get(&b)) else {
+                continue;
+            };
+            if let Ok(metrics) = ctx.measure_text(pair) {
+                let delta = metrics.width() / sample_size - advance_a - advance_b;
+                if delta != 0.0 {
+                    kerning.insert(pair.clone(), delta);
+                }
+            }
+        }
+        ctx.restore();
+
+        let mean = |sample: &str| {
+            let values: Vec<f64> = sample
+                .chars()
+                .filter_map(|c| advances.get(&c))
+                .copied()
+                .collect();
+            values.iter().sum::<f64>() / values.len().max(1) as f64
+        };
+        self.glyph_table = Some(GlyphTable {
+            font: self.calibration_font(),
+            latin_extended_advance: mean(LATIN_EXTENDED_SAMPLE),
+            cjk_advance: mean(CJK_SAMPLE),
+            advances,
+            kerning,
+        });
+        self.clear_footprint_cache();
+        true
+    }
+
+    // 之后的宽度估算直接用该 canvas 上下文的 measureText 实测整个单词，
+    // 比逐字符的字宽表和固定系数都准确；结果按字体、字号和文本缓存
+    #[wasm_bindgen]
+    pub fn measure_with_context(&mut self, ctx: &CanvasRenderingContext2d) {
+        self.js_host.measure_context = Some(ctx.clone());
+        self.sync_host();
+        self.measure_cache.borrow_mut().clear();
+        self.clear_footprint_cache();
+    }
+
+    // 只在本次布局中用 ctx 实测单词宽度，之后恢复原来的测量方式
+    #[wasm_bindgen]
+    pub fn generate_layout_with_ctx(
+        &mut self,
+        ctx: &CanvasRenderingContext2d,
+        words_json: String,
+    ) -> String {
+        let previous = self.js_host.measure_context.replace(ctx.clone());
+        self.sync_host();
+        self.clear_footprint_cache();
+        let layout = self.generate_layout(words_json);
+        self.js_host.measure_context = previous;
+        self.sync_host();
+        self.clear_footprint_cache();
+        layout
+    }
+
+    // 不再使用 canvas 实测，退回字宽表或固定系数的估算
+    #[wasm_bindgen]
+    pub fn clear_measure_context(&mut self) {
+        self.js_host.measure_context = None;
+        self.sync_host();
+        self.measure_cache.borrow_mut().clear();
+        self.clear_footprint_cache();
+    }
+
+    // 导出实测宽度缓存，可保存到 localStorage 等处，在下次加载时用 import_measurements 复用
+    #[wasm_bindgen]
+    pub fn export_measurements(&self) -> String {
+        let mut fonts: BTreeMap<String, BTreeMap<String, f64>> = BTreeMap::new();
+        for ((font, text), &width) in self.measure_cache.borrow().iter() {
+            fonts
+                .entry(font.clone())
+                .or_default()
+                .insert(text.clone(), width);
+        }
+        let snapshot = MeasurementSnapshot {
+            version: MEASUREMENTS_VERSION,
+            fonts,
+        };
+        serde_json::to_string(&snapshot).unwrap_or_else(|_| "null".to_string())
+    }
+
+    // 导入 export_measurements 导出的实测宽度。命中缓存的单词不再调用 measureText，
+    // 没有 canvas 上下文时也会使用；字体与当前配置不同的条目保留但不会被用到。
+    // measure_with_context 会清空缓存，需在其之后导入
+    #[wasm_bindgen]
+    pub fn import_measurements(&mut self, data: String) -> bool {
+        let snapshot = match serde_json::from_str::<MeasurementSnapshot>(&data) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                log(&format!("解析实测宽度失败: {}", e));
+                return false;
+            }
+        };
+        if snapshot.version != MEASUREMENTS_VERSION {
+            log(&format!("不支持的实测宽度格式版本: {}", snapshot.version));
+            return false;
+        }
+
+        let mut cache = self.measure_cache.borrow_mut();
+        'fonts: for (font, widths) in snapshot.fonts {
+            for (text, width) in widths {
+                if cache.len() >= MEASURE_CACHE_LIMIT {
+                    break 'fonts;
+                }
+                cache.insert((font.clone(), text), width);
+            }
+        }
+        drop(cache);
+        self.clear_footprint_cache();
+        true
+    }
+
+    // 设置校准时测量的字距调整对，每项为两个字符，例如 "AV"；
+    // 在下一次 calibrate_font 时生效
+    #[wasm_bindgen]
+    pub fn set_kerning_pairs(&mut self, pairs: Vec<String>) {
+        self.options.kerning_pairs = pairs
+            .into_iter()
+            .filter(|pair| pair.chars().count() == 2)
+            .collect();
+    }
+
+    // 导出字宽表 JSON，可在 worker 或原生环境中通过 import_calibration 复用
+    #[wasm_bindgen]
+    pub fn export_calibration(&self) -> String {
+        match &self.glyph_table {
+            Some(table) => serde_json::to_string(table).unwrap_or_else(|_| "null".to_string()),
+            None => "null".to_string(),
+        }
+    }
+
+    // 导入 export_calibration 导出的字宽表
+    #[wasm_bindgen]
+    pub fn import_calibration(&mut self, calibration_json: String) -> bool {
+        match serde_json::from_str::<GlyphTable>(&calibration_json) {
+            Ok(table) => {
+                self.glyph_table = Some(table);
+                self.clear_footprint_cache();
+                true
+            }
+            Err(e) => {
+                log(&format!("解析字宽表失败: {}", e));
+                false
+            }
+        }
+    }
+
+    // 清除字宽表，恢复固定系数的估算
+    #[wasm_bindgen]
+    pub fn clear_calibration(&mut self) {
+        self.glyph_table = None;
+        self.clear_footprint_cache();
+    }
+}
+
+impl LayoutEngine {
+    fn calibration_font(&self) -> String {
+        format!("{} {}", self.options.font_weight, self.options.font_family)
+    }
+
+    // 与当前字体匹配的字宽表
+    fn active_glyph_table(&self) -> Option<&GlyphTable> {
+        self.glyph_table
+            .as_ref()
+            .filter(|table| table.font == self.calibration_font())
+    }
+
+    // 用 canvas 按实际字号实测文本宽度（不含字间距），缓存和导入的数据中都没有、
+    // 又没有设置上下文或测量失败时为 None。
+    // 字体在不同字号下的微调不一定线性，因此每个字号单独设置字体并测量
+    pub(crate) fn measured_width(&self, text: &str, size: f64) -> Option<f64> {
+        let font = format!(
+            "{} {}px {}",
+            self.options.font_weight, size, self.options.font_family
+        );
+        let key = (font, text.to_string());
+        if let Some(&width) = self.measure_cache.borrow().get(&key) {
+            return Some(width);
+        }
+        let width = self.host.measure_text(&key.0, text)?;
+        let mut cache = self.measure_cache.borrow_mut();
+        if cache.len() >= MEASURE_CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(key, width);
+        Some(width)
+    }
+
+    // 文本宽度相对字号的系数（不含字间距）：有字宽表时逐字符累加，否则按固定系数估算
+    pub(crate) fn text_advance(&self, text: &str) -> f64 {
+        match self.active_glyph_table() {
+            Some(table) => {
+                let advance: f64 = text.chars().map(|c| table.advance(c)).sum();
+                let kerning: f64 = text
+                    .chars()
+                    .zip(text.chars().skip(1))
+                    .map(|(a, b)| table.kerning(a, b))
+                    .sum();
+                advance + kerning
+            }
+            None => DEFAULT_ADVANCE * text.len() as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_cloud;
+
+    // 合成的字宽表：系数都是 1/8 的倍数，逐字符累加没有舍入误差
+    const TABLE: &str = r#"{
+        "font": "normal sans-serif",
+        "advances": {"a": 0.5, "b": 0.625, "c": 0.75, "W": 1.125},
+        "latin_extended_advance": 0.5,
+        "cjk_advance": 1.0,
+        "kerning": {"Wa": -0.125}
+    }"#;
+
+    #[test]
+    fn imported_table_reproduces_synthetic_widths() {
+        let mut cloud = test_cloud();
+        assert_eq!(cloud.estimate_word_width("cab", 20.0), 20.0 * 0.6 * 3.0);
+
+        assert!(cloud.import_calibration(TABLE.to_string()));
+        assert_eq!(
+            cloud.estimate_word_width("cab", 20.0),
+            20.0 * (0.75 + 0.5 + 0.625)
+        );
+        assert_eq!(
+            cloud.estimate_word_width("Wab", 16.0),
+            16.0 * (1.125 + 0.5 + 0.625 - 0.125)
+        );
+        // 未测量的字符按类别估算
+        assert_eq!(cloud.estimate_word_width("中é", 10.0), 10.0 * (1.0 + 0.5));
+
+        // 导出后在另一个实例中导入，得到相同的宽度
+        let mut other = test_cloud();
+        assert!(other.import_calibration(cloud.export_calibration()));
+        assert_eq!(
+            other.estimate_word_width("Wab", 16.0),
+            cloud.estimate_word_width("Wab", 16.0)
+        );
+    }
+
+    #[test]
+    fn calibrated_pair_narrows_only_matching_adjacent_characters() {
+        let mut cloud = test_cloud();
+        assert!(cloud.import_calibration(
+            r#"{
+                "font": "normal sans-serif",
+                "advances": {"A": 0.75, "V": 0.625, "X": 0.5},
+                "latin_extended_advance": 0.5,
+                "cjk_advance": 1.0,
+                "kerning": {"AV": -0.125}
+            }"#
+            .to_string()
+        ));
+        let naive = 16.0 * (0.5 + 0.75 + 0.625 + 0.5);
+        assert_eq!(
+            cloud.estimate_word_width("XAVX", 16.0),
+            naive - 16.0 * 0.125
+        );
+        // 未校准的 "VA" 和不相邻的 A、V 都不修正
+        assert_eq!(cloud.estimate_word_width("XVAX", 16.0), naive);
+        assert_eq!(
+            cloud.estimate_word_width("AXV", 16.0),
+            16.0 * (0.75 + 0.5 + 0.625)
+        );
+    }
+
+    #[test]
+    fn table_only_applies_to_the_calibrated_font() {
+        let mut cloud = test_cloud();
+        assert!(cloud.import_calibration(TABLE.to_string()));
+        cloud.options.font_family = "serif".to_string();
+        assert_eq!(cloud.estimate_word_width("cab", 20.0), 20.0 * 0.6 * 3.0);
+    }
+
+    #[test]
+    fn imported_measurements_are_used_without_a_context() {
+        let mut cloud = test_cloud();
+        // 未知字段被忽略；serif 的条目与当前字体不符，保留但不使用
+        assert!(cloud.import_measurements(
+            r#"{
+                "version": 1,
+                "source": "localStorage",
+                "fonts": {
+                    "normal 20px sans-serif": {"cab": 47.0},
+                    "normal 20px serif": {"cab": 51.0}
+                }
+            }"#
+            .to_string()
+        ));
+        assert_eq!(cloud.estimate_word_width("cab", 20.0), 47.0);
+        assert_eq!(cloud.estimate_word_width("cab", 10.0), 10.0 * 0.6 * 3.0);
+
+        let exported: serde_json::Value =
+            serde_json::from_str(&cloud.export_measurements()).unwrap();
+        assert_eq!(exported["version"], 1);
+        assert_eq!(exported["fonts"]["normal 20px serif"]["cab"], 51.0);
+
+        cloud.options.font_family = "serif".to_string();
+        assert_eq!(cloud.estimate_word_width("cab", 20.0), 51.0);
+
+        // 不认识的格式版本整体拒绝
+        let mut other = test_cloud();
+        assert!(!other.import_measurements(r#"{"version": 2, "fonts": {}}"#.to_string()));
+        assert!(!other.import_measurements("not json".to_string()));
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use js_sys::{Function, Reflect};
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+    use web_sys::CanvasRenderingContext2d;
+
+    use crate::test_cloud;
+
+    // 按合成字宽返回 measureText 结果的假 canvas 上下文，宽度按 64 像素的样本字号给出
+    fn synthetic_context() -> CanvasRenderingContext2d {
+        Function::new_no_args(
+            r#"
+            const advances = { a: 0.5, b: 0.625, c: 0.75 };
+            return {
+                font: "",
+                save() {},
+                restore() {},
+                measureText(text) {
+                    let width = 0;
+                    for (const c of text) width += (advances[c] ?? 0.5) * 64;
+                    return { width };
+                },
+            };
+            "#,
+        )
+        .call0(&JsValue::NULL)
+        .unwrap()
+        .unchecked_into()
+    }
+
+    #[wasm_bindgen_test]
+    fn calibration_reproduces_synthetic_metrics() {
+        let mut cloud = test_cloud();
+        assert!(cloud.calibrate_font(&synthetic_context(), 64.0));
+        assert_eq!(
+            cloud.estimate_word_width("cab", 20.0),
+            20.0 * (0.75 + 0.5 + 0.625)
+        );
+        assert_eq!(cloud.estimate_word_width("abba", 8.0), 8.0 * 2.25);
+    }
+
+    // 整对测量时 "AV" 和 "VA" 都收紧 8 像素（64 像素字号下为 0.125）的假上下文
+    fn kerning_context() -> CanvasRenderingContext2d {
+        Function::new_no_args(
+            r#"
+            const advances = { A: 0.75, V: 0.625 };
+            return {
+                font: "",
+                save() {},
+                restore() {},
+                measureText(text) {
+                    let width = 0;
+                    for (const c of text) width += (advances[c] ?? 0.5) * 64;
+                    if (text === "AV" || text === "VA") width -= 8;
+                    return { width };
+                },
+            };
+            "#,
+        )
+        .call0(&JsValue::NULL)
+        .unwrap()
+        .unchecked_into()
+    }
+
+    #[wasm_bindgen_test]
+    fn calibrated_kerning_pair_applies_its_measured_delta() {
+        let mut cloud = test_cloud();
+        cloud.set_kerning_pairs(vec!["AV".to_string()]);
+        assert!(cloud.calibrate_font(&kerning_context(), 64.0));
+        let naive = 16.0 * (0.5 + 0.75 + 0.625 + 0.5);
+        assert_eq!(
+            cloud.estimate_word_width("xAVx", 16.0),
+            naive - 16.0 * 0.125
+        );
+        // "VA" 虽然在测量时也收紧，但不在校准列表中，不修正
+        assert_eq!(cloud.estimate_word_width("xVAx", 16.0), naive);
+    }
+
+    // 记录 measureText 调用次数的假上下文；fail 为 true 时每次调用都抛出异常
+    fn counting_context(fail: bool) -> (CanvasRenderingContext2d, JsValue) {
+        let ctx = Function::new_with_args(
+            "fail",
+            r#"
+            return {
+                font: "",
+                calls: 0,
+                save() {},
+                restore() {},
+                measureText(text) {
+                    this.calls += 1;
+                    if (fail) throw new Error("measureText called for " + text);
+                    return { width: text.length * 11 };
+                },
+            };
+            "#,
+        )
+        .call1(&JsValue::NULL, &JsValue::from_bool(fail))
+        .unwrap();
+        (ctx.clone().unchecked_into(), ctx)
+    }
+
+    fn calls(ctx: &JsValue) -> f64 {
+        Reflect::get(ctx, &"calls".into())
+            .unwrap()
+            .as_f64()
+            .unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn imported_measurements_replace_measuring_on_a_fresh_instance() {
+        let words = crate::weighted_words(8);
+        let mut cloud = test_cloud();
+        let (ctx, counter) = counting_context(false);
+        let layout = cloud.generate_layout_with_ctx(&ctx, words.clone());
+        assert!(calls(&counter) > 0.0);
+        let exported = cloud.export_measurements();
+
+        // 新实例的上下文一旦被调用就抛出异常，布局只能依赖导入的数据
+        let mut fresh = test_cloud();
+        let (ctx, counter) = counting_context(true);
+        fresh.measure_with_context(&ctx);
+        assert!(fresh.import_measurements(exported));
+        assert_eq!(fresh.generate_layout(words), layout);
+        assert_eq!(calls(&counter), 0.0);
+    }
+}
@@ -0,0 +1,172 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::env::log;
+use crate::{LayoutContext, LayoutEngine, WordCloud, WordItem};
+
+// 没有历史数据时使用的装箱效率：单词实际能占用的空闲面积比例
+const DEFAULT_PACKING_EFFICIENCY: f64 = 0.6;
+// 历史数据修正后的装箱效率下限
+const MIN_PACKING_EFFICIENCY: f64 = 0.1;
+// 历史尝试面积达到画布面积的多少倍时完全采用历史比例
+const HISTORY_FULL_WEIGHT: f64 = 2.0;
+
+// 本实例历次放置尝试的面积（按网格取整后的包围盒面积）
+#[derive(Clone, Default)]
+pub(crate) struct PackingHistory {
+    attempted_area: f64,
+    placed_area: f64,
+}
+
+// 容量估算结果
+#[derive(Serialize)]
+struct CapacityEstimate {
+    // 估计能放下的单词数量
+    estimated: usize,
+    total: usize,
+    // 这批单词按网格取整后的总面积
+    batch_area: f64,
+    remaining_area: f64,
+    packing_efficiency: f64,
+    // 空闲面积乘以装箱效率后实际可用的面积
+    usable_area: f64,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 在不修改网格的前提下估计一批单词中有多少能放入当前布局：
+    // 按放置顺序累加单词面积，直到超过剩余面积乘以装箱效率
+    #[wasm_bindgen]
+    pub fn estimate_capacity(&self, words_json: String) -> JsValue {
+        match self.capacity_estimate(&words_json) {
+            Some(estimate) => serde_wasm_bindgen::to_value(&estimate).unwrap_or(JsValue::NULL),
+            None => JsValue::NULL,
+        }
+    }
+
+    // 网格中未被占用的面积（平方像素）
+    #[wasm_bindgen]
+    pub fn remaining_area(&self) -> f64 {
+        self.engine.remaining_area()
+    }
+}
+
+impl LayoutEngine {
+    pub(crate) fn remaining_area(&self) -> f64 {
+        let (columns, rows) = self.grid_dimensions();
+        let free = columns * rows - self.occupied_grid_cells();
+        (free * self.grid_size * self.grid_size) as f64
+    }
+
+    // 构建 estimate_capacity 返回的估算结果，JSON 无法解析时返回 None
+    fn capacity_estimate(&self, words_json: &str) -> Option<CapacityEstimate> {
+        let mut words: Vec<WordItem> = match serde_json::from_str(words_json) {
+            Ok(words) => words,
+            Err(e) => {
+                log(&format!("解析词语JSON失败: {}", e));
+                return None;
+            }
+        };
+        for word in &mut words {
+            word.normalize_compound();
+        }
+        words.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+
+        // 已有布局时沿用其权重范围，新单词的字号与追加放置时一致
+        let context = self.layout_context.clone().unwrap_or_else(|| {
+            let weights = || words.iter().flat_map(|w| w.sizing_weights());
+            LayoutContext {
+                min_weight: weights().fold(f64::INFINITY, f64::min),
+                max_weight: weights().fold(f64::NEG_INFINITY, f64::max),
+                center_x: 0.0,
+                center_y: 0.0,
+                palette: Vec::new(),
+            }
+        });
+
+        let remaining_area = self.remaining_area();
+        let packing_efficiency = self.packing_efficiency();
+        let usable_area = remaining_area * packing_efficiency;
+
+        let mut batch_area = 0.0;
+        let mut estimated = 0;
+        for word in &words {
+            let measured = self.measure_word(word, &context, 1.0);
+            batch_area += self.padded_area(measured.width, measured.height);
+            if batch_area <= usable_area {
+                estimated += 1;
+            }
+        }
+
+        Some(CapacityEstimate {
+            estimated,
+            total: words.len(),
+            batch_area,
+            remaining_area,
+            packing_efficiency,
+            usable_area,
+        })
+    }
+
+    // 包围盒按网格取整后的面积，即单词在网格中实际占用的面积
+    fn padded_area(&self, width: f64, height: f64) -> f64 {
+        let cell = self.grid_size as f64;
+        (width / cell).ceil() * cell * (height / cell).ceil() * cell
+    }
+
+    // 记录一次放置尝试的面积
+    pub(crate) fn record_packing(&mut self, width: f64, height: f64, placed: bool) {
+        let area = self.padded_area(width, height);
+        self.packing_history.attempted_area += area;
+        if placed {
+            self.packing_history.placed_area += area;
+        }
+    }
+
+    // 装箱效率：以默认值为先验，随历史尝试面积的增加逐渐过渡到实际放置面积与尝试面积之比
+    fn packing_efficiency(&self) -> f64 {
+        let history = &self.packing_history;
+        if history.attempted_area <= 0.0 {
+            return DEFAULT_PACKING_EFFICIENCY;
+        }
+        let canvas_area = (self.options.width as f64 * self.options.height as f64).max(1.0);
+        let weight = (history.attempted_area / (canvas_area * HISTORY_FULL_WEIGHT)).min(1.0);
+        let observed = history.placed_area / history.attempted_area;
+        (DEFAULT_PACKING_EFFICIENCY * (1.0 - weight) + observed * weight)
+            .clamp(MIN_PACKING_EFFICIENCY, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cloud, weighted_words};
+
+    #[test]
+    fn small_batch_is_estimated_to_fit_entirely() {
+        let cloud = test_cloud();
+        let free = cloud.remaining_area();
+        assert!(free >= 400.0 * 300.0);
+        let estimate = cloud.capacity_estimate(&weighted_words(5)).unwrap();
+        assert_eq!((estimate.estimated, estimate.total), (5, 5));
+        // 估算不修改网格
+        assert_eq!(cloud.remaining_area(), free);
+    }
+
+    #[test]
+    fn oversized_batch_estimate_tracks_the_actual_outcome() {
+        let mut cloud = test_cloud();
+        let free = cloud.remaining_area();
+        let words = weighted_words(300);
+        let estimate = cloud.capacity_estimate(&words).unwrap();
+        assert!(estimate.estimated < estimate.total / 4);
+
+        cloud.generate_layout(words.clone());
+        let placed = cloud.placed.len();
+        assert!(estimate.estimated <= placed * 2 && placed <= estimate.estimated * 2);
+
+        // 历史中大量失败的尝试使装箱效率低于默认值
+        assert!(cloud.remaining_area() < free);
+        let refined = cloud.capacity_estimate(&words).unwrap();
+        assert!(refined.packing_efficiency < estimate.packing_efficiency);
+    }
+}
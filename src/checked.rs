@@ -0,0 +1,60 @@
+use js_sys::{Error, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::{parse_words_json, WordCloud, WordItem};
+
+// 错误类型：输入无法解析、输入为空、配置无效、画布尺寸为零
+pub(crate) const PARSE_ERROR: &str = "parse";
+const EMPTY_INPUT_ERROR: &str = "empty_input";
+const INVALID_OPTIONS_ERROR: &str = "invalid_options";
+const INVALID_CANVAS_ERROR: &str = "invalid_canvas";
+
+// 带 kind 字段的 JS Error，在 JS 侧会被抛出
+pub(crate) fn layout_error(kind: &str, message: &str) -> JsValue {
+    let error = Error::new(message);
+    let _ = Reflect::set(&error, &JsValue::from_str("kind"), &JsValue::from_str(kind));
+    error.into()
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 与 generate_layout 相同，但输入无法解析、为空、配置无效或画布尺寸为零时返回
+    // 带 kind 和 message 的错误，而不是空数组。空数组只表示输入有效但没有单词放得下
+    #[wasm_bindgen]
+    pub fn generate_layout_checked(&mut self, words_json: String) -> Result<String, JsValue> {
+        let words = parse_words_json(&words_json).map_err(|e| layout_error(PARSE_ERROR, &e))?;
+        self.validate_layout_input(&words)?;
+
+        self.layout_words(words);
+        Ok(self.layout_json())
+    }
+}
+
+impl WordCloud {
+    // 布局前检查输入和配置，JSON 和 JsValue 两种带错误的接口共用
+    pub(crate) fn validate_layout_input(&self, words: &[WordItem]) -> Result<(), JsValue> {
+        if words.is_empty() {
+            return Err(layout_error(EMPTY_INPUT_ERROR, "no words to lay out"));
+        }
+        if self.options.width == 0 || self.options.height == 0 {
+            return Err(layout_error(
+                INVALID_CANVAS_ERROR,
+                &format!(
+                    "canvas must have a non-zero size, got {}x{}",
+                    self.options.width, self.options.height
+                ),
+            ));
+        }
+        let (min_size, max_size) = (self.options.min_size, self.options.max_size);
+        if !(min_size > 0.0 && max_size.is_finite()) || min_size > max_size {
+            return Err(layout_error(
+                INVALID_OPTIONS_ERROR,
+                &format!(
+                    "font sizes must satisfy 0 < min_size <= max_size, got min_size {} and max_size {}",
+                    min_size, max_size
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
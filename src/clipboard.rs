@@ -0,0 +1,67 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+
+use crate::WordCloud;
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 读取剪贴板文本并按词频生成布局，返回的 Promise 以布局 JSON 兑现
+    // 调用时同步复制一份实例，读到文本后在副本上布局：等待剪贴板期间不借用实例，
+    // 其他方法仍可调用，布局结果也不会写回实例
+    // 剪贴板不可用或没有权限时以 "Clipboard access denied" 拒绝
+    #[wasm_bindgen]
+    pub fn generate_layout_from_clipboard(&self) -> js_sys::Promise {
+        future_to_promise(layout_from_clipboard(self.clone()))
+    }
+
+    // 读取剪贴板文本，返回的 Promise 以文本兑现，再交给 generate_layout_from_text 生成布局。
+    // 不借用实例，等待剪贴板期间实例上的其他方法仍可调用
+    // 剪贴板不可用或没有权限时以 "Clipboard access denied" 拒绝
+    #[wasm_bindgen]
+    pub async fn read_clipboard_text() -> Result<String, JsValue> {
+        let denied = || JsValue::from_str("Clipboard access denied");
+
+        // 非安全上下文中 navigator.clipboard 为 undefined
+        let clipboard = web_sys::window()
+            .map(|window| window.navigator().clipboard())
+            .filter(|clipboard| !clipboard.is_undefined())
+            .ok_or_else(denied)?;
+
+        JsFuture::from(clipboard.read_text())
+            .await
+            .map_err(|_| denied())?
+            .as_string()
+            .ok_or_else(denied)
+    }
+}
+
+// 先等剪贴板，再在按值传入的实例副本上同步生成布局
+async fn layout_from_clipboard(mut cloud: WordCloud) -> Result<JsValue, JsValue> {
+    let text = WordCloud::read_clipboard_text().await?;
+    Ok(JsValue::from_str(&cloud.generate_layout_from_text(text)))
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use wasm_bindgen_futures::JsFuture;
+    use wasm_bindgen_test::*;
+
+    use crate::test_cloud;
+
+    #[wasm_bindgen_test]
+    async fn clipboard_layout_rejects_without_a_clipboard_and_leaves_the_instance_usable() {
+        let mut cloud = test_cloud();
+        let promise = cloud.generate_layout_from_clipboard();
+
+        // 等待剪贴板期间实例照常可用
+        let layout = cloud.generate_layout_from_text("alpha beta alpha".to_string());
+        assert!(layout.contains("alpha"));
+
+        // Node 中没有 window，和没有权限一样拒绝
+        let error = JsFuture::from(promise).await.unwrap_err();
+        assert_eq!(
+            error.as_string().as_deref(),
+            Some("Clipboard access denied")
+        );
+    }
+}
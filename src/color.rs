@@ -0,0 +1,498 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{LayoutEngine, WordCloud};
+
+// 色觉缺陷模拟矩阵（作用于线性 RGB）
+// 红色盲、绿色盲使用 Viénot 1999，蓝色盲使用 Machado 2009 (severity = 1.0)
+const PROTANOPIA: [[f64; 3]; 3] = [
+    [0.11238, 0.88762, 0.0],
+    [0.11238, 0.88762, 0.0],
+    [0.00401, -0.00401, 1.0],
+];
+const DEUTERANOPIA: [[f64; 3]; 3] = [
+    [0.29275, 0.70725, 0.0],
+    [0.29275, 0.70725, 0.0],
+    [-0.02234, 0.02234, 1.0],
+];
+const TRITANOPIA: [[f64; 3]; 3] = [
+    [1.255528, -0.076749, -0.178779],
+    [-0.078411, 0.930809, 0.147602],
+    [0.004733, 0.691367, 0.303900],
+];
+
+// 提高对比度时向黑色或白色混合的步数
+const CONTRAST_STEPS: usize = 20;
+
+// sRGB 颜色，分量范围 [0, 1]
+#[derive(Clone, Copy)]
+pub(crate) struct Rgb {
+    pub(crate) r: f64,
+    pub(crate) g: f64,
+    pub(crate) b: f64,
+}
+
+impl Rgb {
+    // 由 HSL 构造颜色：色相为角度，饱和度和亮度范围 [0, 1]
+    pub(crate) fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Rgb {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = lightness - chroma / 2.0;
+        Rgb {
+            r: r + m,
+            g: g + m,
+            b: b + m,
+        }
+    }
+
+    // 解析 #rgb 或 #rrggbb 形式的颜色
+    pub(crate) fn parse(color: &str) -> Option<Rgb> {
+        let hex = color.trim().strip_prefix('#')?;
+        let digits: Vec<u8> = match hex.len() {
+            3 => hex
+                .chars()
+                .map(|c| c.to_digit(16).map(|d| (d * 17) as u8))
+                .collect::<Option<_>>()?,
+            6 => (0..3)
+                .map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok())
+                .collect::<Option<_>>()?,
+            _ => return None,
+        };
+        Some(Rgb {
+            r: digits[0] as f64 / 255.0,
+            g: digits[1] as f64 / 255.0,
+            b: digits[2] as f64 / 255.0,
+        })
+    }
+
+    fn linear(self) -> [f64; 3] {
+        let to_linear = |c: f64| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        [to_linear(self.r), to_linear(self.g), to_linear(self.b)]
+    }
+
+    fn from_linear(linear: [f64; 3]) -> Rgb {
+        let to_srgb = |c: f64| {
+            let c = c.clamp(0.0, 1.0);
+            if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        };
+        Rgb {
+            r: to_srgb(linear[0]),
+            g: to_srgb(linear[1]),
+            b: to_srgb(linear[2]),
+        }
+    }
+
+    // WCAG 相对亮度
+    pub(crate) fn luminance(self) -> f64 {
+        let [r, g, b] = self.linear();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    // CIE L*a*b*
+    fn lab(self) -> [f64; 3] {
+        let [r, g, b] = self.linear();
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+        let f = |t: f64| {
+            if t > 0.008856 {
+                t.cbrt()
+            } else {
+                7.787 * t + 16.0 / 116.0
+            }
+        };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+    }
+
+    pub(crate) fn to_hex(self) -> String {
+        let channel = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!(
+            "#{:02x}{:02x}{:02x}",
+            channel(self.r),
+            channel(self.g),
+            channel(self.b)
+        )
+    }
+
+    // 在两个颜色之间线性插值，t = 0 为 self，t = 1 为 other
+    pub(crate) fn mix(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        Rgb {
+            r: self.r + (other.r - self.r) * t,
+            g: self.g + (other.g - self.g) * t,
+            b: self.b + (other.b - self.b) * t,
+        }
+    }
+
+    // 应用色觉缺陷模拟矩阵
+    fn simulate(self, matrix: &[[f64; 3]; 3]) -> Rgb {
+        let c = self.linear();
+        let row = |m: &[f64; 3]| m[0] * c[0] + m[1] * c[1] + m[2] * c[2];
+        Rgb::from_linear([row(&matrix[0]), row(&matrix[1]), row(&matrix[2])])
+    }
+}
+
+// 把颜色向白色混合 amount（0 到 1），无法解析的颜色原样返回
+pub(crate) fn tint_color(color: &str, amount: f64) -> String {
+    let white = Rgb {
+        r: 1.0,
+        g: 1.0,
+        b: 1.0,
+    };
+    match Rgb::parse(color) {
+        Some(rgb) => rgb.mix(white, amount).to_hex(),
+        None => color.to_string(),
+    }
+}
+
+// 把颜色向同亮度的灰色混合 amount（0 到 1）以降低饱和度，无法解析的颜色原样返回
+pub(crate) fn desaturate_color(color: &str, amount: f64) -> String {
+    match Rgb::parse(color) {
+        Some(rgb) => {
+            let luma = 0.299 * rgb.r + 0.587 * rgb.g + 0.114 * rgb.b;
+            let gray = Rgb {
+                r: luma,
+                g: luma,
+                b: luma,
+            };
+            rgb.mix(gray, amount).to_hex()
+        }
+        None => color.to_string(),
+    }
+}
+
+// 与背景的对比度低于 min_contrast 时，逐步向黑色或白色（与背景对比更强的一方）混合直到满足要求；
+// 无法解析的颜色原样返回
+pub(crate) fn ensure_contrast(color: &str, background: &str, min_contrast: f64) -> String {
+    let (rgb, background) = match (Rgb::parse(color), Rgb::parse(background)) {
+        (Some(rgb), Some(background)) => (rgb, background),
+        _ => return color.to_string(),
+    };
+    if contrast_ratio(rgb, background) >= min_contrast {
+        return color.to_string();
+    }
+    let black = Rgb {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+    };
+    let white = Rgb {
+        r: 1.0,
+        g: 1.0,
+        b: 1.0,
+    };
+    let target = if contrast_ratio(black, background) >= contrast_ratio(white, background) {
+        black
+    } else {
+        white
+    };
+    let mut adjusted = rgb;
+    for step in 1..=CONTRAST_STEPS {
+        // 按十六进制舍入后的颜色计算，与输出一致
+        let mixed = rgb.mix(target, step as f64 / CONTRAST_STEPS as f64);
+        adjusted = Rgb::parse(&mixed.to_hex()).unwrap_or(mixed);
+        if contrast_ratio(adjusted, background) >= min_contrast {
+            break;
+        }
+    }
+    adjusted.to_hex()
+}
+
+// WCAG 对比度
+pub(crate) fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (a.luminance(), b.luminance());
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+// CIE76 色差
+fn delta_e(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (a.lab(), b.lab());
+    ((la[0] - lb[0]).powi(2) + (la[1] - lb[1]).powi(2) + (la[2] - lb[2]).powi(2)).sqrt()
+}
+
+// 正常视觉及三种色觉缺陷下的色差
+#[derive(Serialize)]
+struct SimulatedDistance {
+    normal: f64,
+    protanopia: f64,
+    deuteranopia: f64,
+    tritanopia: f64,
+}
+
+impl SimulatedDistance {
+    fn between(a: Rgb, b: Rgb) -> SimulatedDistance {
+        SimulatedDistance {
+            normal: delta_e(a, b),
+            protanopia: delta_e(a.simulate(&PROTANOPIA), b.simulate(&PROTANOPIA)),
+            deuteranopia: delta_e(a.simulate(&DEUTERANOPIA), b.simulate(&DEUTERANOPIA)),
+            tritanopia: delta_e(a.simulate(&TRITANOPIA), b.simulate(&TRITANOPIA)),
+        }
+    }
+
+    fn min(&self) -> f64 {
+        self.normal
+            .min(self.protanopia)
+            .min(self.deuteranopia)
+            .min(self.tritanopia)
+    }
+}
+
+// 两个颜色在最坏视觉条件下的色差
+pub(crate) fn worst_case_distance(a: Rgb, b: Rgb) -> f64 {
+    SimulatedDistance::between(a, b).min()
+}
+
+#[derive(Serialize)]
+struct PaletteColorReport {
+    color: String,
+    contrast_with_background: f64,
+    flagged: bool,
+}
+
+#[derive(Serialize)]
+struct PalettePairReport {
+    a: String,
+    b: String,
+    contrast_ratio: f64,
+    distance: SimulatedDistance,
+    flagged: bool,
+}
+
+#[derive(Serialize)]
+pub(crate) struct PaletteReport {
+    background: String,
+    min_contrast: f64,
+    min_distance: f64,
+    colors: Vec<PaletteColorReport>,
+    pairs: Vec<PalettePairReport>,
+    invalid: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置调色板，未指定颜色的单词按调色板依次着色
+    #[wasm_bindgen]
+    pub fn set_palette(&mut self, colors: Vec<String>) {
+        self.options.palette = colors;
+    }
+
+    // 设置调色板模式："cycle" 按顺序循环，"cvd_safe" 重新排列以拉开色觉缺陷下的色差，
+    // "random" 按单词 key 随机选取颜色
+    #[wasm_bindgen]
+    pub fn set_palette_mode(&mut self, mode: String) {
+        self.options.palette_mode = mode;
+    }
+
+    // 设置调色板校验阈值：与背景的最小对比度，以及颜色间的最小色差
+    #[wasm_bindgen]
+    pub fn set_palette_thresholds(&mut self, min_contrast: f64, min_distance: f64) {
+        self.options.palette_min_contrast = min_contrast;
+        self.options.palette_min_distance = min_distance;
+    }
+
+    // 设置是否在着色时把与背景色对比度不足的调色板颜色调整到 palette_min_contrast；
+    // 只在设置了背景色时生效，单词自带的颜色不受影响
+    #[wasm_bindgen]
+    pub fn set_palette_contrast_adjustment(&mut self, enabled: bool) {
+        self.options.adjust_palette_contrast = enabled;
+    }
+
+    // 校验调色板：给出每对颜色的对比度和模拟色觉缺陷下的色差，并标记低于阈值的项
+    #[wasm_bindgen]
+    pub fn validate_palette(&self, colors: Vec<String>, background: String) -> JsValue {
+        let report = self.palette_report(colors, background);
+        serde_wasm_bindgen::to_value(&report).unwrap_or(JsValue::NULL)
+    }
+}
+
+impl LayoutEngine {
+    // 构建 validate_palette 返回的校验报告
+    pub(crate) fn palette_report(&self, colors: Vec<String>, background: String) -> PaletteReport {
+        let min_contrast = self.options.palette_min_contrast;
+        let min_distance = self.options.palette_min_distance;
+        let background_rgb = Rgb::parse(&background);
+
+        let mut invalid = Vec::new();
+        let mut parsed: Vec<(String, Rgb)> = Vec::new();
+        for color in colors {
+            match Rgb::parse(&color) {
+                Some(rgb) => parsed.push((color, rgb)),
+                None => invalid.push(color),
+            }
+        }
+        if background_rgb.is_none() {
+            invalid.push(background.clone());
+        }
+
+        let color_reports = parsed
+            .iter()
+            .map(|(color, rgb)| {
+                let contrast = background_rgb.map_or(0.0, |bg| contrast_ratio(*rgb, bg));
+                PaletteColorReport {
+                    color: color.clone(),
+                    contrast_with_background: contrast,
+                    flagged: contrast < min_contrast,
+                }
+            })
+            .collect();
+
+        let mut pairs = Vec::new();
+        for (i, (a, a_rgb)) in parsed.iter().enumerate() {
+            for (b, b_rgb) in &parsed[i + 1..] {
+                let distance = SimulatedDistance::between(*a_rgb, *b_rgb);
+                pairs.push(PalettePairReport {
+                    a: a.clone(),
+                    b: b.clone(),
+                    contrast_ratio: contrast_ratio(*a_rgb, *b_rgb),
+                    flagged: distance.min() < min_distance,
+                    distance,
+                });
+            }
+        }
+
+        PaletteReport {
+            background,
+            min_contrast,
+            min_distance,
+            colors: color_reports,
+            pairs,
+            invalid,
+        }
+    }
+
+    // 本次布局实际使用的调色板顺序
+    pub(crate) fn effective_palette(&self) -> Vec<String> {
+        self.order_palette(&self.options.palette)
+    }
+
+    // 按调色板模式排列颜色：cvd_safe 模式下重新排序，其余模式保持原顺序
+    pub(crate) fn order_palette(&self, palette: &[String]) -> Vec<String> {
+        if self.options.palette_mode != "cvd_safe" || palette.len() < 3 {
+            return palette.to_vec();
+        }
+
+        // 贪心最远点排序：每次选取与已选颜色最坏色差最大的颜色，
+        // 使任意前缀以及相邻颜色在色觉缺陷下都尽量可区分
+        let mut remaining: Vec<(String, Option<Rgb>)> = palette
+            .iter()
+            .map(|color| (color.clone(), Rgb::parse(color)))
+            .collect();
+        let mut ordered: Vec<(String, Option<Rgb>)> = vec![remaining.remove(0)];
+
+        while !remaining.is_empty() {
+            let score = |candidate: &Option<Rgb>| -> f64 {
+                ordered
+                    .iter()
+                    .map(|(_, chosen)| match (candidate, chosen) {
+                        (Some(a), Some(b)) => worst_case_distance(*a, *b),
+                        _ => 0.0,
+                    })
+                    .fold(f64::INFINITY, f64::min)
+            };
+            let best = remaining
+                .iter()
+                .enumerate()
+                .map(|(index, (_, rgb))| (index, score(rgb)))
+                .fold((0, f64::NEG_INFINITY), |best, current| {
+                    if current.1 > best.1 {
+                        current
+                    } else {
+                        best
+                    }
+                })
+                .0;
+            ordered.push(remaining.remove(best));
+        }
+
+        ordered.into_iter().map(|(color, _)| color).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_cloud, weighted_words};
+
+    #[test]
+    fn red_green_pair_is_flagged() {
+        let cloud = test_cloud();
+        let report = cloud.palette_report(
+            vec![
+                "#d62728".to_string(),
+                "#2ca02c".to_string(),
+                "#1f77b4".to_string(),
+            ],
+            "#ffffff".to_string(),
+        );
+        let pair = |a: &str, b: &str| {
+            report
+                .pairs
+                .iter()
+                .find(|pair| pair.a == a && pair.b == b)
+                .unwrap()
+        };
+        let red_green = pair("#d62728", "#2ca02c");
+        assert!(red_green.flagged);
+        assert!(red_green.distance.normal > 50.0);
+        assert!(red_green.distance.deuteranopia < 10.0);
+        assert!(!pair("#d62728", "#1f77b4").flagged);
+        assert!(report.invalid.is_empty());
+    }
+
+    // 已放置单词所用颜色两两之间的最小最坏色差
+    fn min_assigned_distance(cloud: &WordCloud) -> f64 {
+        let colors: Vec<Rgb> = cloud
+            .placed
+            .iter()
+            .map(|word| Rgb::parse(word.color.as_ref().unwrap()).unwrap())
+            .collect();
+        let mut min = f64::INFINITY;
+        for (i, a) in colors.iter().enumerate() {
+            for b in &colors[i + 1..] {
+                min = min.min(worst_case_distance(*a, *b));
+            }
+        }
+        min
+    }
+
+    #[test]
+    fn cvd_safe_mode_spreads_assigned_colors() {
+        let palette = vec![
+            "#d62728".to_string(),
+            "#2ca02c".to_string(),
+            "#ff7f0e".to_string(),
+            "#1f77b4".to_string(),
+        ];
+        let distance = |mode: &str| {
+            let mut cloud = test_cloud();
+            cloud.set_palette(palette.clone());
+            cloud.set_palette_mode(mode.to_string());
+            cloud.generate_layout(weighted_words(3));
+            assert_eq!(cloud.placed.len(), 3);
+            min_assigned_distance(&cloud)
+        };
+        let cycled = distance("cycle");
+        let safe = distance("cvd_safe");
+        assert!(safe > cycled);
+    }
+}
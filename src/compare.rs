@@ -0,0 +1,326 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::color::Rgb;
+use crate::compound::PartPosition;
+use crate::env::log;
+use crate::geometry::Obb;
+use crate::{LayoutEngine, WordCloud, WordPosition};
+
+// 在新旧布局中都存在的单词：从旧位置移动到新位置
+#[derive(Serialize)]
+struct TransitionMove {
+    word: String,
+    from: WordPosition,
+    to: WordPosition,
+}
+
+// 差异图中新增单词与消失单词的颜色
+const DIFF_ADDED_COLOR: &str = "#27AE60";
+const DIFF_REMOVED_COLOR: &str = "#E74C3C";
+// 消失单词的不透明度
+const DIFF_REMOVED_OPACITY: f64 = 0.5;
+
+// 密度差异图中每个网格单元在每个方向上的采样点数
+const DENSITY_SAMPLES: usize = 4;
+
+// 两份布局的密度差异：values 按行优先排列，为新布局减去旧布局的覆盖比例
+#[derive(Serialize)]
+struct DensityDiff {
+    cols: usize,
+    rows: usize,
+    cell_px: u32,
+    min: f64,
+    max: f64,
+    values: Vec<f64>,
+    // 按 heatmap 颜色着色后的 RGBA 像素（每个网格单元一个像素），仅在请求时给出
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rgba: Option<Vec<u8>>,
+}
+
+// 动画过渡计划
+#[derive(Serialize)]
+struct TransitionPlan {
+    #[serde(rename = "move")]
+    moves: Vec<TransitionMove>,
+    enter: Vec<WordPosition>,
+    exit: Vec<WordPosition>,
+}
+
+// 解析布局 JSON，失败时返回空布局
+pub(crate) fn parse_layout(layout_json: &str) -> Vec<WordPosition> {
+    serde_json::from_str(layout_json).unwrap_or_else(|e| {
+        log(&format!("解析布局JSON失败: {}", e));
+        Vec::new()
+    })
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 根据旧布局和新词表计算过渡计划：按 key 匹配的单词移动，新单词淡入，消失的单词淡出
+    #[wasm_bindgen]
+    pub fn compute_transition_plan(
+        &mut self,
+        old_layout_json: String,
+        new_words_json: String,
+    ) -> String {
+        let mut old_layout: Vec<WordPosition> = parse_layout(&old_layout_json)
+            .into_iter()
+            .map(|word| self.import_position(word))
+            .collect();
+        self.generate_layout(new_words_json);
+
+        let mut moves = Vec::new();
+        let mut enter = Vec::new();
+        for to in &self.placed {
+            match old_layout.iter().position(|old| old.key() == to.key()) {
+                Some(index) => {
+                    let from = old_layout.remove(index);
+                    moves.push(TransitionMove {
+                        word: to.key().to_string(),
+                        from: self.emit_position(&from),
+                        to: self.emit_position(to),
+                    });
+                }
+                None => enter.push(self.emit_position(to)),
+            }
+        }
+
+        let plan = TransitionPlan {
+            moves,
+            enter,
+            exit: self.emit_layout(&old_layout),
+        };
+        serde_json::to_string(&plan).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    // 根据旧布局和新词表生成差异 SVG：按 key 匹配，两边都有的单词保持原色，
+    // 只在新布局中的单词为绿色，只在旧布局中的单词以半透明红色画在旧位置上
+    #[wasm_bindgen]
+    pub fn generate_diff_svg(&mut self, old_layout_json: String, new_words_json: String) -> String {
+        let mut old_layout: Vec<WordPosition> = parse_layout(&old_layout_json)
+            .into_iter()
+            .map(|word| self.import_position(word))
+            .collect();
+        self.generate_layout(new_words_json);
+
+        let mut words = Vec::new();
+        for word in &self.placed {
+            match old_layout.iter().position(|old| old.key() == word.key()) {
+                Some(index) => {
+                    old_layout.remove(index);
+                    words.push(word.clone());
+                }
+                None => words.push(recolor(word, DIFF_ADDED_COLOR)),
+            }
+        }
+
+        let (width, height) = (self.options.width, self.options.height);
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+            width, height, width, height
+        );
+        svg.push_str(&self.svg_defs());
+
+        // 消失的单词位于最下层，避免遮挡新布局
+        svg.push_str(&format!(r#"<g opacity="{}">"#, DIFF_REMOVED_OPACITY));
+        for (index, word) in old_layout.iter().enumerate() {
+            svg.push_str(&self.svg_word(
+                self.placed.len() + index,
+                &recolor(word, DIFF_REMOVED_COLOR),
+            ));
+        }
+        svg.push_str("</g>");
+
+        for (index, word) in words.iter().enumerate() {
+            svg.push_str(&self.svg_word(index, word));
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    // 两份布局的密度差异网格：按 cell_px 大小的网格统计各单词包围盒的覆盖比例，
+    // 返回 B 减 A 的有符号网格及其尺寸和最小、最大值。两份布局都按当前画布尺寸栅格化。
+    // colormap 为 true 时同时给出 RGBA 像素：减少的区域为 heatmap_cold_color，
+    // 增加的区域为 heatmap_hot_color，不透明度与差值的绝对值成正比
+    #[wasm_bindgen]
+    pub fn export_density_diff(
+        &self,
+        layout_a_json: String,
+        layout_b_json: String,
+        cell_px: u32,
+        colormap: bool,
+    ) -> JsValue {
+        let diff = self.density_diff(&layout_a_json, &layout_b_json, cell_px, colormap);
+        serde_wasm_bindgen::to_value(&diff).unwrap_or(JsValue::NULL)
+    }
+}
+
+impl LayoutEngine {
+    // 构建 export_density_diff 返回的差异网格
+    fn density_diff(
+        &self,
+        layout_a_json: &str,
+        layout_b_json: &str,
+        cell_px: u32,
+        colormap: bool,
+    ) -> DensityDiff {
+        let cell_px = cell_px.max(1);
+        let cell = cell_px as f64;
+        let cols = (self.options.width as f64 / cell).ceil() as usize;
+        let rows = (self.options.height as f64 / cell).ceil() as usize;
+
+        let a = self.layout_density(&parse_layout(layout_a_json), cell, cols, rows);
+        let b = self.layout_density(&parse_layout(layout_b_json), cell, cols, rows);
+        let values: Vec<f64> = b.iter().zip(&a).map(|(b, a)| b - a).collect();
+        let min = values.iter().copied().fold(0.0, f64::min);
+        let max = values.iter().copied().fold(0.0, f64::max);
+
+        let rgba = colormap.then(|| {
+            let black = Rgb {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+            };
+            let cold = Rgb::parse(&self.options.heatmap_cold_color).unwrap_or(black);
+            let hot = Rgb::parse(&self.options.heatmap_hot_color).unwrap_or(black);
+            let scale = max.max(-min);
+            let channel = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+            values
+                .iter()
+                .flat_map(|&value| {
+                    let t = if scale > 0.0 { value / scale } else { 0.0 };
+                    let color = if t < 0.0 { cold } else { hot };
+                    [
+                        channel(color.r),
+                        channel(color.g),
+                        channel(color.b),
+                        channel(t.abs()),
+                    ]
+                })
+                .collect()
+        });
+
+        DensityDiff {
+            cols,
+            rows,
+            cell_px,
+            min,
+            max,
+            values,
+            rgba,
+        }
+    }
+
+    // 将布局中各单词的旋转包围盒栅格化为 cols x rows 的覆盖比例网格，
+    // 每个单元按 DENSITY_SAMPLES x DENSITY_SAMPLES 个采样点计算被覆盖的比例
+    fn layout_density(
+        &self,
+        layout: &[WordPosition],
+        cell: f64,
+        cols: usize,
+        rows: usize,
+    ) -> Vec<f64> {
+        let mut density = vec![0.0; cols * rows];
+        let step = cell / DENSITY_SAMPLES as f64;
+        let sample_weight = 1.0 / (DENSITY_SAMPLES * DENSITY_SAMPLES) as f64;
+        for word in layout {
+            let word = self.import_position(word.clone());
+            let (width, height) = self.word_box(&word);
+            let obb = Obb::new(word.x, word.y, width, height, word.rotate);
+            let (min_x, min_y, max_x, max_y) = obb.bounds();
+            let col_range = (min_x / cell).floor().max(0.0) as usize
+                ..((max_x / cell).ceil().max(0.0) as usize).min(cols);
+            for row in (min_y / cell).floor().max(0.0) as usize
+                ..((max_y / cell).ceil().max(0.0) as usize).min(rows)
+            {
+                for col in col_range.clone() {
+                    let mut covered = 0.0;
+                    for i in 0..DENSITY_SAMPLES {
+                        for j in 0..DENSITY_SAMPLES {
+                            let x = col as f64 * cell + (i as f64 + 0.5) * step;
+                            let y = row as f64 * cell + (j as f64 + 0.5) * step;
+                            if obb.contains(x, y) {
+                                covered += sample_weight;
+                            }
+                        }
+                    }
+                    density[row * cols + col] += covered;
+                }
+            }
+        }
+        density
+    }
+}
+
+// 把单词及其各部分统一改成指定颜色，并去掉渐变
+fn recolor(word: &WordPosition, color: &str) -> WordPosition {
+    WordPosition {
+        color: Some(color.to_string()),
+        gradient_angle: None,
+        parts: word.parts.as_ref().map(|parts| {
+            parts
+                .iter()
+                .map(|part| PartPosition {
+                    color: None,
+                    ..part.clone()
+                })
+                .collect()
+        }),
+        ..word.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_cloud;
+
+    fn layout(x: f64, y: f64) -> String {
+        serde_json::json!([
+            {"text": "moving", "weight": 5, "x": x, "y": y, "rotate": 0, "size": 40},
+            {"text": "fixed", "weight": 1, "x": 320, "y": 60, "rotate": 0, "size": 12}
+        ])
+        .to_string()
+    }
+
+    #[test]
+    fn identical_layouts_produce_an_all_zero_diff() {
+        let cloud = test_cloud();
+        let diff = cloud.density_diff(&layout(100.0, 100.0), &layout(100.0, 100.0), 20, true);
+        assert_eq!((diff.cols, diff.rows), (20, 15));
+        assert!(diff.values.iter().all(|&value| value == 0.0));
+        assert_eq!((diff.min, diff.max), (0.0, 0.0));
+        // 没有差异时所有像素完全透明
+        let rgba = diff.rgba.unwrap();
+        assert_eq!(rgba.len(), 20 * 15 * 4);
+        assert!(rgba.chunks(4).all(|pixel| pixel[3] == 0));
+    }
+
+    #[test]
+    fn moving_a_large_word_leaves_matching_positive_and_negative_blobs() {
+        let cloud = test_cloud();
+        // 向右移动 10 个单元、向下移动 5 个单元
+        let diff = cloud.density_diff(&layout(100.0, 100.0), &layout(300.0, 200.0), 20, false);
+        let cell = |col: usize, row: usize| diff.values[row * diff.cols + col];
+
+        assert!(cell(5, 5) < 0.0);
+        assert!(cell(15, 10) > 0.0);
+        assert!((diff.max + diff.min).abs() < 1e-9);
+        for row in 0..diff.rows {
+            for col in 0..diff.cols {
+                let value = cell(col, row);
+                if value < 0.0 {
+                    // 旧位置的每个单元在新位置都有大小相同的正值
+                    assert!((cell(col + 10, row + 5) + value).abs() < 1e-9);
+                } else if value > 0.0 {
+                    assert!(col >= 10 && row >= 5);
+                    assert!((cell(col - 10, row - 5) + value).abs() < 1e-9);
+                }
+            }
+        }
+        let positive: f64 = diff.values.iter().filter(|&&v| v > 0.0).sum();
+        let negative: f64 = diff.values.iter().filter(|&&v| v < 0.0).sum();
+        assert!(positive > 1.0);
+        assert!((positive + negative).abs() < 1e-9);
+    }
+}
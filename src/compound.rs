@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{LayoutContext, LayoutEngine, WordItem};
+
+// 基线到单词包围盒中心的距离占字号的比例（近似值）
+const BASELINE_RATIO: f64 = 0.3;
+
+// 默认分隔符
+const DEFAULT_SEPARATOR: &str = " ";
+
+// 组合词中的一个部分，按自身权重计算字号
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CompoundPart {
+    text: String,
+    weight: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+}
+
+// 组合词各部分相对锚点的位置（旋转前的局部坐标）
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PartPosition {
+    pub(crate) text: String,
+    pub(crate) size: f64,
+    pub(crate) dx: f64,
+    pub(crate) dy: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) color: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub(crate) separator: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl WordItem {
+    // 组合词：用分隔符拼接出完整文本，未指定权重时取各部分的最大权重
+    pub(crate) fn normalize_compound(&mut self) {
+        let parts = match &self.parts {
+            Some(parts) if !parts.is_empty() => parts,
+            _ => return,
+        };
+
+        let separator = self.separator.as_deref().unwrap_or(DEFAULT_SEPARATOR);
+        self.text = parts
+            .iter()
+            .map(|part| part.text.as_str())
+            .collect::<Vec<_>>()
+            .join(separator);
+        if self.weight == 0.0 {
+            self.weight = parts.iter().map(|part| part.weight).fold(0.0, f64::max);
+        }
+    }
+
+    // 参与字号计算的权重：组合词为各部分的权重
+    pub(crate) fn sizing_weights(&self) -> Vec<f64> {
+        match &self.parts {
+            Some(parts) if !parts.is_empty() => parts.iter().map(|part| part.weight).collect(),
+            _ => vec![self.sizing_weight()],
+        }
+    }
+}
+
+impl LayoutEngine {
+    // 在共同基线上排列组合词的各部分，返回各部分位置以及整体的宽和高
+    pub(crate) fn layout_compound(
+        &self,
+        word: &WordItem,
+        context: &LayoutContext,
+        factor: f64,
+    ) -> Option<(Vec<PartPosition>, f64, f64)> {
+        let parts = word.parts.as_ref().filter(|parts| !parts.is_empty())?;
+        let separator = word.separator.as_deref().unwrap_or(DEFAULT_SEPARATOR);
+
+        let sizes: Vec<f64> = parts
+            .iter()
+            .map(|part| {
+                self.word_size(part.weight, context.min_weight, context.max_weight) * factor
+            })
+            .collect();
+        let height = sizes.iter().copied().fold(0.0, f64::max);
+
+        // 依次排列各部分和分隔符，分隔符使用相邻两部分中较小的字号
+        let mut pieces: Vec<(String, f64, Option<String>, bool)> = Vec::new();
+        for (index, part) in parts.iter().enumerate() {
+            if index > 0 && !separator.is_empty() {
+                let size = sizes[index - 1].min(sizes[index]);
+                pieces.push((separator.to_string(), size, word.color.clone(), true));
+            }
+            let color = part.color.clone().or_else(|| word.color.clone());
+            pieces.push((part.text.clone(), sizes[index], color, false));
+        }
+
+        let widths: Vec<f64> = pieces
+            .iter()
+            .map(|(text, size, _, _)| self.estimate_word_width(text, *size))
+            .collect();
+        let width: f64 = widths.iter().sum();
+
+        // 所有部分共享同一条基线
+        let baseline = BASELINE_RATIO * height;
+        let mut cursor = -width / 2.0;
+        let positions = pieces
+            .into_iter()
+            .zip(widths)
+            .map(|((text, size, color, separator), part_width)| {
+                let dx = cursor + part_width / 2.0;
+                cursor += part_width;
+                PartPosition {
+                    text,
+                    size,
+                    dx,
+                    dy: baseline - BASELINE_RATIO * size,
+                    color,
+                    separator,
+                }
+            })
+            .collect();
+
+        Some((positions, width, height))
+    }
+
+    // 已放置组合词的整体宽度
+    pub(crate) fn compound_width(&self, parts: &[PartPosition]) -> f64 {
+        let (left, right) =
+            parts
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(left, right), part| {
+                    let half = self.estimate_word_width(&part.text, part.size) / 2.0;
+                    (left.min(part.dx - half), right.max(part.dx + half))
+                });
+        (right - left).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_cloud;
+
+    const WORDS: &str = r#"[
+        {"parts": [{"text": "salt", "weight": 10}, {"text": "pepper", "weight": 4}], "separator": " & "},
+        {"text": "alpha", "weight": 8},
+        {"text": "beta", "weight": 6},
+        {"text": "gamma", "weight": 2}
+    ]"#;
+
+    #[test]
+    fn compound_parts_sit_inline_on_a_shared_baseline() {
+        let mut cloud = test_cloud();
+        cloud.generate_layout(WORDS.to_string());
+        assert_eq!(cloud.placed.len(), 4);
+        let word = cloud
+            .placed
+            .iter()
+            .find(|word| word.text == "salt & pepper")
+            .unwrap();
+        let parts = word.parts.as_ref().unwrap();
+        let texts: Vec<&str> = parts.iter().map(|part| part.text.as_str()).collect();
+        assert_eq!(texts, ["salt", " & ", "pepper"]);
+        assert!(parts[1].separator && !parts[2].separator);
+        assert!(parts[0].size > parts[2].size);
+        assert_eq!(parts[1].size, parts[2].size);
+
+        // 整体宽度等于各部分与分隔符宽度之和
+        let widths: Vec<f64> = parts
+            .iter()
+            .map(|part| cloud.estimate_word_width(&part.text, part.size))
+            .collect();
+        let (width, _) = cloud.word_box(word);
+        assert!((width - widths.iter().sum::<f64>()).abs() < 1e-9);
+
+        // 按偏移还原的各部分首尾相接、互不重叠，且共享同一条基线
+        for i in 1..parts.len() {
+            let previous_right = parts[i - 1].dx + widths[i - 1] / 2.0;
+            let left = parts[i].dx - widths[i] / 2.0;
+            assert!((left - previous_right).abs() < 1e-9);
+        }
+        let baseline = |part: &PartPosition| part.dy + BASELINE_RATIO * part.size;
+        for part in &parts[1..] {
+            assert!((baseline(part) - baseline(&parts[0])).abs() < 1e-9);
+        }
+
+        // 碰撞检测使用整体包围盒
+        let unit = cloud.word_obb(word);
+        for other in cloud.placed.iter().filter(|other| other.text != word.text) {
+            assert!(!unit.intersects(&cloud.word_obb(other)));
+        }
+    }
+}
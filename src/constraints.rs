@@ -0,0 +1,160 @@
+use wasm_bindgen::prelude::*;
+
+use crate::geometry::Obb;
+use crate::{LayoutEngine, WordCloud, WordItem};
+
+// 两个单词之间的最小间距规则，规则是对称的
+#[derive(Clone)]
+pub(crate) struct SeparationRule {
+    key_a: String,
+    key_b: String,
+    min_distance: f64,
+}
+
+impl SeparationRule {
+    // 若规则涉及 key，返回另一侧单词的 key
+    fn partner_of(&self, key: &str) -> Option<&str> {
+        if self.key_a == key {
+            Some(&self.key_b)
+        } else if self.key_b == key {
+            Some(&self.key_a)
+        } else {
+            None
+        }
+    }
+
+    fn matches(&self, key_a: &str, key_b: &str) -> bool {
+        (self.key_a == key_a && self.key_b == key_b) || (self.key_a == key_b && self.key_b == key_a)
+    }
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 添加单词对之间的最小间距规则（按 key 或 text 匹配），同一对单词的规则会被覆盖
+    #[wasm_bindgen]
+    pub fn add_separation_rule(&mut self, key_a: String, key_b: String, min_distance: f64) {
+        self.separation_rules
+            .retain(|rule| !rule.matches(&key_a, &key_b));
+        self.separation_rules.push(SeparationRule {
+            key_a,
+            key_b,
+            min_distance: min_distance.max(0.0),
+        });
+    }
+
+    // 移除单词对之间的间距规则
+    #[wasm_bindgen]
+    pub fn remove_separation_rule(&mut self, key_a: String, key_b: String) -> bool {
+        let before = self.separation_rules.len();
+        self.separation_rules
+            .retain(|rule| !rule.matches(&key_a, &key_b));
+        self.separation_rules.len() != before
+    }
+
+    // 清除所有间距规则
+    #[wasm_bindgen]
+    pub fn clear_separation_rules(&mut self) {
+        self.separation_rules.clear();
+    }
+}
+
+impl LayoutEngine {
+    // 检查候选位置是否违反单词间的约束
+    pub(crate) fn violates_word_constraints(&self, word: &WordItem, candidate: &Obb) -> bool {
+        self.violates_separation_rules(word.key(), candidate)
+            || self.violates_same_color_distance(word, candidate)
+    }
+
+    // 检查与已放置的同色单词之间是否满足最小间距
+    fn violates_same_color_distance(&self, word: &WordItem, candidate: &Obb) -> bool {
+        let (min_distance, color) = match (self.options.min_same_color_distance, &word.color) {
+            (Some(min_distance), Some(color)) if min_distance > 0.0 => (min_distance, color),
+            _ => return false,
+        };
+
+        self.placed
+            .iter()
+            .filter(|other| other.color.as_ref() == Some(color))
+            .any(|other| too_close(candidate, &self.word_obb(other), min_distance))
+    }
+
+    // 检查单词对之间的间距规则
+    fn violates_separation_rules(&self, key: &str, candidate: &Obb) -> bool {
+        for rule in &self.separation_rules {
+            let partner = match rule.partner_of(key) {
+                Some(partner) => partner,
+                None => continue,
+            };
+
+            let violated = self
+                .placed
+                .iter()
+                .filter(|w| w.key() == partner)
+                .any(|other| too_close(candidate, &self.word_obb(other), rule.min_distance));
+            if violated {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+// 两个包围盒的间距是否小于 min_distance：先用外接圆粗略筛选，再计算精确距离
+fn too_close(a: &Obb, b: &Obb, min_distance: f64) -> bool {
+    let center_distance = ((a.cx - b.cx).powi(2) + (a.cy - b.cy).powi(2)).sqrt();
+    let reach = a.circumradius() + b.circumradius() + min_distance;
+    center_distance <= reach && a.distance(b) < min_distance
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cloud, WordCloud};
+
+    const WORDS: &str = r#"[
+        {"text": "brand", "weight": 10},
+        {"text": "rival", "weight": 9},
+        {"text": "alpha", "weight": 5},
+        {"text": "beta", "weight": 4},
+        {"text": "gamma", "weight": 3}
+    ]"#;
+
+    // 400x300 画布上 rival 仍放得下的间距
+    const MIN_GAP: f64 = 60.0;
+
+    // brand 与 rival 包围盒之间的距离
+    fn gap(cloud: &WordCloud) -> f64 {
+        let obb = |key: &str| {
+            let word = cloud.placed.iter().find(|w| w.key() == key).unwrap();
+            cloud.word_obb(word)
+        };
+        obb("brand").distance(&obb("rival"))
+    }
+
+    #[test]
+    fn separation_rule_keeps_words_apart_until_removed() {
+        let mut cloud = test_cloud();
+        cloud.generate_layout(WORDS.to_string());
+        let unconstrained = gap(&cloud);
+        assert!(unconstrained < MIN_GAP);
+
+        cloud.add_separation_rule("rival".to_string(), "brand".to_string(), MIN_GAP);
+        cloud.generate_layout(WORDS.to_string());
+        assert!(gap(&cloud) >= MIN_GAP - 1e-6);
+
+        assert!(cloud.remove_separation_rule("brand".to_string(), "rival".to_string()));
+        cloud.generate_layout(WORDS.to_string());
+        assert!((gap(&cloud) - unconstrained).abs() < 1e-9);
+    }
+
+    #[test]
+    fn separation_rule_holds_with_subpixel_refinement_and_snapping() {
+        for snap in [false, true] {
+            let mut cloud = test_cloud();
+            cloud.set_subpixel_positioning(!snap);
+            cloud.set_snap_to_grid(snap, 8);
+            cloud.add_separation_rule("brand".to_string(), "rival".to_string(), MIN_GAP);
+            cloud.generate_layout(WORDS.to_string());
+            assert!(gap(&cloud) >= MIN_GAP - 1e-6);
+        }
+    }
+}
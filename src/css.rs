@@ -0,0 +1,87 @@
+use wasm_bindgen::prelude::*;
+
+use crate::render::escape_xml;
+use crate::{LayoutEngine, WordCloud};
+
+// 去掉可能提前结束 CSS 声明或 <style> 块的字符
+fn css_value(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, '<' | '>' | '{' | '}' | ';'))
+        .collect()
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 把当前布局导出为绝对定位的 HTML span。颜色、字号和字体都通过 CSS 自定义属性引用：
+    // color: var(--wc-color-{index}, 原色)，font-size: calc(var(--wc-size-scale, 1) * 字号px)，
+    // 并附带一个 <style> 块以布局数据定义这些属性，运行时覆盖属性即可换主题而无需重新布局
+    #[wasm_bindgen]
+    pub fn generate_css_spans(&self) -> String {
+        let mut variables = format!(
+            "--wc-size-scale:1;--wc-font-family:{};--wc-font-weight:{};",
+            css_value(&self.options.font_family),
+            css_value(&self.options.font_weight)
+        );
+        let mut spans = String::new();
+
+        for (index, word) in self.placed.iter().enumerate() {
+            let size = word.size.unwrap_or(self.options.max_size);
+            let color = css_value(word.color.as_deref().unwrap_or("#000000"));
+            variables.push_str(&format!("--wc-color-{}:{};", index, color));
+
+            // 外层 span 位于单词中心并负责旋转，内层 span 按局部偏移放置各部分
+            spans.push_str(&format!(
+                r#"<span class="wc-word" style="position:absolute;left:{}px;top:{}px;transform:rotate({}deg)">"#,
+                self.round_output(word.x),
+                self.round_output(word.y),
+                self.svg_rotation(word.rotate)
+            ));
+            let fallback = format!("var(--wc-color-{}, {})", index, color);
+            match &word.parts {
+                Some(parts) => {
+                    for (part_index, part) in parts.iter().enumerate() {
+                        let part_color = match &part.color {
+                            Some(part_color) => {
+                                let part_color = css_value(part_color);
+                                variables.push_str(&format!(
+                                    "--wc-color-{}-{}:{};",
+                                    index, part_index, part_color
+                                ));
+                                format!("var(--wc-color-{}-{}, {})", index, part_index, part_color)
+                            }
+                            None => fallback.clone(),
+                        };
+                        spans.push_str(&self.css_span(
+                            part.dx,
+                            part.dy,
+                            part.size,
+                            &part_color,
+                            &part.text,
+                        ));
+                    }
+                }
+                None => spans.push_str(&self.css_span(0.0, 0.0, size, &fallback, &word.text)),
+            }
+            spans.push_str("</span>");
+        }
+
+        format!(
+            r#"<style>.wc-cloud{{{}}}.wc-word span{{position:absolute;transform:translate(-50%,-50%);white-space:nowrap;font-family:var(--wc-font-family);font-weight:var(--wc-font-weight);letter-spacing:{}px}}</style><div class="wc-cloud" style="position:relative;width:{}px;height:{}px">{}</div>"#,
+            variables, self.options.letter_spacing, self.options.width, self.options.height, spans
+        )
+    }
+}
+
+impl LayoutEngine {
+    fn css_span(&self, dx: f64, dy: f64, size: f64, color: &str, text: &str) -> String {
+        format!(
+            r#"<span style="left:{}px;top:{}px;color:{};font-size:calc(var(--wc-size-scale, 1) * {}px)">{}</span>"#,
+            self.round_output(dx),
+            self.round_output(dy),
+            escape_xml(color),
+            self.round_output(size),
+            escape_xml(text)
+        )
+    }
+}
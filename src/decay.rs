@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::color::desaturate_color;
+use crate::env::log;
+use crate::{LayoutEngine, WordCloud, WordItem};
+
+// 时间衰减配置：以 now_ms 为当前时间，每经过 half_life_ms 衰减系数减半
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct TimeDecay {
+    now_ms: f64,
+    half_life_ms: f64,
+    #[serde(flatten)]
+    effects: DecayEffects,
+}
+
+// 衰减系数作用的方面，以及单词的最大年龄（超过时不参与布局）
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct DecayEffects {
+    // 权重乘以衰减系数，影响字号
+    #[serde(default)]
+    weight: bool,
+    // 不透明度等于衰减系数
+    #[serde(default)]
+    opacity: bool,
+    // 颜色饱和度乘以衰减系数
+    #[serde(default)]
+    saturation: bool,
+    #[serde(default)]
+    max_age_ms: Option<f64>,
+}
+
+// 单词的年龄（毫秒）和衰减系数
+#[derive(Clone, Copy)]
+pub(crate) struct Decay {
+    pub(crate) age_ms: f64,
+    pub(crate) factor: f64,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 按单词的 timestamp（毫秒时间戳）设置时间衰减：衰减系数为 0.5 ^ (年龄 / half_life_ms)。
+    // effects 形如 {weight: true, opacity: true, saturation: true, max_age_ms: 86400000}，
+    // 超过 max_age_ms 的单词以 "expired" 记入跳过列表。参数无效时返回 false
+    #[wasm_bindgen]
+    pub fn set_time_decay(&mut self, now_ms: f64, half_life_ms: f64, effects: JsValue) -> bool {
+        if !(now_ms.is_finite() && half_life_ms > 0.0 && half_life_ms.is_finite()) {
+            return false;
+        }
+        let effects: DecayEffects = if effects.is_undefined() || effects.is_null() {
+            DecayEffects::default()
+        } else {
+            match serde_wasm_bindgen::from_value(effects) {
+                Ok(effects) => effects,
+                Err(e) => {
+                    log(&format!("解析时间衰减效果失败: {}", e));
+                    return false;
+                }
+            }
+        };
+        self.options.time_decay = Some(TimeDecay {
+            now_ms,
+            half_life_ms,
+            effects,
+        });
+        true
+    }
+
+    // 关闭时间衰减
+    #[wasm_bindgen]
+    pub fn clear_time_decay(&mut self) {
+        self.options.time_decay = None;
+    }
+}
+
+impl LayoutEngine {
+    // 计算带时间戳单词的衰减：过期的单词记为跳过，按需把衰减系数乘到权重上
+    pub(crate) fn apply_time_decay(&mut self, words: Vec<WordItem>) -> Vec<WordItem> {
+        let decay = match self.options.time_decay.clone() {
+            Some(decay) => decay,
+            None => return words,
+        };
+
+        let mut kept = Vec::with_capacity(words.len());
+        for mut word in words {
+            let timestamp = match word.timestamp {
+                Some(timestamp) => timestamp,
+                None => {
+                    kept.push(word);
+                    continue;
+                }
+            };
+            // 时间戳晚于 now_ms 的单词视为刚刚出现
+            let age_ms = (decay.now_ms - timestamp).max(0.0);
+            if decay.effects.max_age_ms.is_some_and(|max| age_ms > max) {
+                self.record_skipped(&word, 0.0, "expired");
+                continue;
+            }
+            let factor = 0.5f64.powf(age_ms / decay.half_life_ms);
+            if decay.effects.weight {
+                word.weight *= factor;
+            }
+            word.decay = Some(Decay { age_ms, factor });
+            kept.push(word);
+        }
+        kept
+    }
+
+    // 衰减后的不透明度，未开启 opacity 效果时为 None
+    pub(crate) fn decay_opacity(&self, decay: Option<Decay>) -> Option<f64> {
+        let effects = &self.options.time_decay.as_ref()?.effects;
+        decay.filter(|_| effects.opacity).map(|decay| decay.factor)
+    }
+
+    // 衰减后的颜色，开启 saturation 效果时按衰减系数降低饱和度
+    pub(crate) fn decay_color(
+        &self,
+        color: Option<String>,
+        decay: Option<Decay>,
+    ) -> Option<String> {
+        let saturation = self
+            .options
+            .time_decay
+            .as_ref()
+            .is_some_and(|time_decay| time_decay.effects.saturation);
+        match (color, decay) {
+            (Some(color), Some(decay)) if saturation => {
+                Some(desaturate_color(&color, 1.0 - decay.factor))
+            }
+            (color, _) => color,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_cloud;
+
+    const NOW: f64 = 1_700_000_000_000.0;
+    const HALF_LIFE: f64 = 3_600_000.0;
+
+    #[test]
+    fn equal_weights_decay_by_the_half_life_and_expired_words_are_skipped() {
+        let mut cloud = test_cloud();
+        cloud.options.time_decay = Some(TimeDecay {
+            now_ms: NOW,
+            half_life_ms: HALF_LIFE,
+            effects: DecayEffects {
+                weight: true,
+                opacity: true,
+                saturation: true,
+                max_age_ms: Some(3.0 * HALF_LIFE),
+            },
+        });
+        // anchor 没有时间戳，固定权重范围为 1..8
+        let words = serde_json::json!([
+            {"text": "anchor", "weight": 8},
+            {"text": "fresh", "weight": 4, "timestamp": NOW, "color": "#ff0000"},
+            {"text": "stale", "weight": 4, "timestamp": NOW - 2.0 * HALF_LIFE, "color": "#ff0000"},
+            {"text": "ancient", "weight": 4, "timestamp": NOW - 4.0 * HALF_LIFE},
+            {"text": "floor", "weight": 1}
+        ]);
+        let layout: serde_json::Value =
+            serde_json::from_str(&cloud.generate_layout(words.to_string())).unwrap();
+        let word = |text: &str| {
+            layout
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|word| word["text"] == text)
+                .unwrap()
+                .clone()
+        };
+
+        let (fresh, stale) = (word("fresh"), word("stale"));
+        assert_eq!(fresh["age_ms"], 0.0);
+        assert_eq!(fresh["decay"], 1.0);
+        assert_eq!(fresh["opacity"], 1.0);
+        assert_eq!(stale["age_ms"], 2.0 * HALF_LIFE);
+        assert_eq!(stale["decay"], 0.25);
+        assert_eq!(stale["opacity"], 0.25);
+        // 衰减后的权重决定字号：4 和 4 * 0.25 = 1
+        let size = |word: &serde_json::Value| word["size"].as_f64().unwrap();
+        assert!((size(&fresh) - cloud.word_size(4.0, 1.0, 8.0)).abs() < 1e-9);
+        assert!((size(&stale) - cloud.word_size(1.0, 1.0, 8.0)).abs() < 1e-9);
+        assert!(size(&fresh) > size(&stale));
+        // 饱和度按衰减系数降低，未衰减的颜色不变
+        assert_eq!(fresh["color"], "#ff0000");
+        assert_ne!(stale["color"], "#ff0000");
+
+        assert_eq!(layout.as_array().unwrap().len(), 4);
+        assert_eq!(cloud.skipped.len(), 1);
+        assert_eq!(cloud.skipped[0].text, "ancient");
+        assert_eq!(cloud.skipped[0].reason, "expired");
+    }
+}
@@ -0,0 +1,124 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{LayoutContext, LayoutEngine, WordCloud, WordItem};
+
+// 因字号过小而未放置的单词，size 为按权重（及超大缩放）计算出的字号
+#[derive(Clone, Serialize)]
+pub(crate) struct DemotedWord {
+    text: String,
+    weight: f64,
+    size: f64,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置最小显示字号：计算出的字号低于该值的单词不参与放置，改为列入 get_demoted，
+    // 0 表示关闭
+    #[wasm_bindgen]
+    pub fn set_min_render_size(&mut self, min_render_size: f64) {
+        self.options.min_render_size = min_render_size.max(0.0);
+    }
+
+    // 设置汇总词：有单词被降级时在最后放置一个汇总词，模板中的 {count} 替换为降级的数量，
+    // 例如 "+{count} more"；传入 None 表示不放置汇总词
+    #[wasm_bindgen]
+    pub fn set_demoted_summary(&mut self, template: Option<String>, size: f64) {
+        self.options.demoted_summary_template = template;
+        self.options.demoted_summary_size = size.max(0.0);
+    }
+
+    // 获取最近一次布局中因字号过小而降级的单词
+    #[wasm_bindgen]
+    pub fn get_demoted(&self) -> String {
+        serde_json::to_string(&self.demoted).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+impl LayoutEngine {
+    // 放置之前按最终字号筛掉过小的单词，避免它们占用空间；按需在末尾追加汇总词
+    pub(crate) fn demote_unreadable(
+        &mut self,
+        words: Vec<WordItem>,
+        context: &LayoutContext,
+    ) -> Vec<WordItem> {
+        let threshold = self.options.min_render_size;
+        if threshold <= 0.0 {
+            return words;
+        }
+
+        let mut kept = Vec::with_capacity(words.len());
+        for word in words {
+            let size = self
+                .measure_word(&word, context, word.size_factor.unwrap_or(1.0))
+                .size;
+            if size < threshold {
+                self.demoted.push(DemotedWord {
+                    text: word.text.clone(),
+                    weight: word.weight,
+                    size,
+                });
+            } else {
+                kept.push(word);
+            }
+        }
+        self.layout_stats.demoted = self.demoted.len() as u32;
+
+        if let Some(template) = &self.options.demoted_summary_template {
+            if !self.demoted.is_empty() {
+                // 汇总词按最小权重计算基础字号，再缩放到配置的字号
+                let base =
+                    self.word_size(context.min_weight, context.min_weight, context.max_weight);
+                let size = self.options.demoted_summary_size.max(threshold);
+                kept.push(WordItem {
+                    text: template.replace("{count}", &self.demoted.len().to_string()),
+                    weight: context.min_weight,
+                    size_factor: (base > 0.0).then(|| size / base),
+                    ..WordItem::default()
+                });
+            }
+        }
+        kept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::{test_cloud, weighted_words};
+
+    #[test]
+    fn small_words_are_demoted_and_summarised_instead_of_placed() {
+        let mut cloud = test_cloud();
+        cloud.set_min_render_size(25.0);
+        cloud.set_demoted_summary(Some("+{count} more".to_string()), 12.0);
+        cloud.generate_layout(weighted_words(12));
+        assert!(cloud.skipped.is_empty());
+
+        let demoted: serde_json::Value = serde_json::from_str(&cloud.get_demoted()).unwrap();
+        let demoted = demoted.as_array().unwrap();
+        assert!(!demoted.is_empty());
+        for word in demoted {
+            assert!(word["size"].as_f64().unwrap() < 25.0);
+        }
+        assert_eq!(cloud.layout_stats.demoted as usize, demoted.len());
+
+        // 汇总词按降级数量生成，字号不低于阈值
+        let summary = format!("+{} more", demoted.len());
+        let placed: Vec<_> = cloud.placed.iter().filter(|w| w.text != summary).collect();
+        assert_eq!(placed.len() + 1, cloud.placed.len());
+        assert!(cloud.placed.iter().all(|word| word.size.unwrap() >= 25.0));
+
+        // 放置的单词和降级的单词恰好覆盖全部输入
+        let mut texts: Vec<String> = placed.iter().map(|word| word.text.clone()).collect();
+        texts.extend(
+            demoted
+                .iter()
+                .map(|word| word["text"].as_str().unwrap().to_string()),
+        );
+        assert_eq!(texts.len(), 12);
+        let expected: BTreeSet<String> = (0..12).map(|i| format!("w{}", i)).collect();
+        assert_eq!(texts.into_iter().collect::<BTreeSet<_>>(), expected);
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::bundling::{bundle, Segment};
+use crate::env::log;
+use crate::{LayoutEngine, WordCloud};
+
+// 输入的关系边，a、b 为单词的 key 或 text
+#[derive(Deserialize)]
+struct EdgeInput {
+    a: String,
+    b: String,
+    #[serde(default)]
+    weight: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+// 附带布局坐标的关系边；任一端点未被放置时坐标为空并标记 skipped
+#[derive(Serialize)]
+struct EdgeOutput {
+    a: String,
+    b: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weight: Option<f64>,
+    from: Option<Point>,
+    to: Option<Point>,
+    midpoint: Option<Point>,
+    distance: Option<f64>,
+    // 开启 bundle_connections 时的贝塞尔控制点及对应的 SVG 路径
+    #[serde(skip_serializing_if = "Option::is_none")]
+    controls: Option<[Point; 2]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    skipped: bool,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 把关系列表 [{a, b, weight?}] 与当前布局的坐标关联，用于在词云上叠加连线
+    // clip_to_boxes 为 true 时线段起止于两个单词包围盒的边界而非中心
+    #[wasm_bindgen]
+    pub fn export_edges(&self, edges_json: String, clip_to_boxes: bool) -> String {
+        let edges: Vec<EdgeInput> = match serde_json::from_str(&edges_json) {
+            Ok(edges) => edges,
+            Err(e) => {
+                log(&format!("解析关系JSON失败: {}", e));
+                return "[]".to_string();
+            }
+        };
+
+        let mut result: Vec<EdgeOutput> = edges
+            .into_iter()
+            .map(|edge| self.export_edge(edge, clip_to_boxes))
+            .collect();
+        if self.options.bundle_connections {
+            self.bundle_edges(&mut result);
+        }
+        serde_json::to_string(&result).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    // 设置导出连线时是否进行边捆绑
+    #[wasm_bindgen]
+    pub fn set_bundle_connections(&mut self, enabled: bool) {
+        self.options.bundle_connections = enabled;
+    }
+}
+
+impl LayoutEngine {
+    fn export_edge(&self, edge: EdgeInput, clip_to_boxes: bool) -> EdgeOutput {
+        let find = |key: &str| self.placed.iter().find(|word| word.key() == key);
+        let (word_a, word_b) = match (find(&edge.a), find(&edge.b)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => {
+                return EdgeOutput {
+                    a: edge.a,
+                    b: edge.b,
+                    weight: edge.weight,
+                    from: None,
+                    to: None,
+                    midpoint: None,
+                    distance: None,
+                    controls: None,
+                    path: None,
+                    skipped: true,
+                }
+            }
+        };
+
+        let (dx, dy) = (word_b.x - word_a.x, word_b.y - word_a.y);
+        let length = (dx * dx + dy * dy).sqrt();
+
+        // 沿连线方向从两端各自退到包围盒边界；两个包围盒重叠时线段收缩为中点
+        let (start, end) = if clip_to_boxes && length > 0.0 {
+            let start_t = self.word_obb(word_a).ray_exit(dx, dy) / length;
+            let end_t = 1.0 - self.word_obb(word_b).ray_exit(-dx, -dy) / length;
+            if start_t < end_t {
+                (start_t, end_t)
+            } else {
+                let mid = ((start_t + end_t) / 2.0).clamp(0.0, 1.0);
+                (mid, mid)
+            }
+        } else {
+            (0.0, 1.0)
+        };
+
+        let point = |t: f64| Point {
+            x: self.round_output(word_a.x + dx * t),
+            y: self.round_output(self.flip_y(word_a.y + dy * t)),
+        };
+
+        EdgeOutput {
+            a: edge.a,
+            b: edge.b,
+            weight: edge.weight,
+            from: Some(point(start)),
+            to: Some(point(end)),
+            midpoint: Some(point((start + end) / 2.0)),
+            distance: Some(self.round_output(length * (end - start))),
+            controls: None,
+            path: None,
+            skipped: false,
+        }
+    }
+
+    // 按单词连通关系分组，为每条连线计算捆绑后的控制点
+    fn bundle_edges(&self, edges: &mut [EdgeOutput]) {
+        // 并查集：同一连通分量中的单词属于同一组
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut parent: Vec<usize> = Vec::new();
+        let mut endpoints = Vec::new();
+        for edge in edges.iter().filter(|edge| !edge.skipped) {
+            let mut id = |key| {
+                *index.entry(key).or_insert_with(|| {
+                    parent.push(parent.len());
+                    parent.len() - 1
+                })
+            };
+            let (a, b) = (id(edge.a.as_str()), id(edge.b.as_str()));
+            let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+            parent[ra] = rb;
+            endpoints.push(a);
+        }
+
+        let segments: Vec<Segment> = edges
+            .iter()
+            .filter(|edge| !edge.skipped)
+            .zip(&endpoints)
+            .filter_map(|(edge, &a)| {
+                let (from, to) = (edge.from.as_ref()?, edge.to.as_ref()?);
+                Some(Segment {
+                    from: (from.x, from.y),
+                    to: (to.x, to.y),
+                    group: root(&mut parent, a),
+                })
+            })
+            .collect();
+
+        let controls = bundle(&segments);
+        for (edge, [c1, c2]) in edges.iter_mut().filter(|edge| !edge.skipped).zip(controls) {
+            let point = |(x, y): (f64, f64)| Point {
+                x: self.round_output(x),
+                y: self.round_output(y),
+            };
+            let (c1, c2) = (point(c1), point(c2));
+            if let (Some(from), Some(to)) = (&edge.from, &edge.to) {
+                edge.path = Some(format!(
+                    "M{} {}C{} {} {} {} {} {}",
+                    from.x, from.y, c1.x, c1.y, c2.x, c2.y, to.x, to.y
+                ));
+            }
+            edge.controls = Some([c1, c2]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::{test_cloud, weighted_words, WordCloud};
+
+    const EDGES: &str = r#"[
+        {"a": "w0", "b": "w1", "weight": 2},
+        {"a": "w0", "b": "missing"}
+    ]"#;
+
+    fn point(value: &Value) -> (f64, f64) {
+        (value["x"].as_f64().unwrap(), value["y"].as_f64().unwrap())
+    }
+
+    fn laid_out() -> (WordCloud, Value) {
+        let mut cloud = test_cloud();
+        let layout: Value =
+            serde_json::from_str(&cloud.generate_layout(weighted_words(10))).unwrap();
+        (cloud, layout)
+    }
+
+    #[test]
+    fn edge_endpoints_match_the_layout_and_missing_words_are_flagged() {
+        let (cloud, layout) = laid_out();
+        let edges: Value =
+            serde_json::from_str(&cloud.export_edges(EDGES.to_string(), false)).unwrap();
+
+        let (a, b) = (point(&layout[0]), point(&layout[1]));
+        assert_eq!(point(&edges[0]["from"]), a);
+        assert_eq!(point(&edges[0]["to"]), b);
+        assert_eq!(
+            point(&edges[0]["midpoint"]),
+            ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+        );
+        let distance = edges[0]["distance"].as_f64().unwrap();
+        assert!((distance - (b.0 - a.0).hypot(b.1 - a.1)).abs() < 1e-9);
+        assert_eq!(edges[0]["weight"], 2.0);
+        assert_eq!(edges[0]["skipped"], false);
+
+        assert_eq!(edges[1]["skipped"], true);
+        assert!(edges[1]["from"].is_null() && edges[1]["to"].is_null());
+        assert!(edges[1]["distance"].is_null());
+    }
+
+    #[test]
+    fn clipping_shortens_edges_to_the_box_boundaries() {
+        let (cloud, _) = laid_out();
+        let full: Value =
+            serde_json::from_str(&cloud.export_edges(EDGES.to_string(), false)).unwrap();
+        let clipped: Value =
+            serde_json::from_str(&cloud.export_edges(EDGES.to_string(), true)).unwrap();
+
+        let (from, to) = (point(&clipped[0]["from"]), point(&clipped[0]["to"]));
+        let distance = clipped[0]["distance"].as_f64().unwrap();
+        assert!(distance < full[0]["distance"].as_f64().unwrap());
+        assert!((distance - (to.0 - from.0).hypot(to.1 - from.1)).abs() < 1e-9);
+
+        // 裁剪后的端点落在各自包围盒的边界上：稍向内在盒内，稍向外在盒外
+        let (obb_a, obb_b) = (
+            cloud.word_obb(&cloud.placed[0]),
+            cloud.word_obb(&cloud.placed[1]),
+        );
+        let (ux, uy) = ((to.0 - from.0) / distance, (to.1 - from.1) / distance);
+        let eps = 1e-6;
+        assert!(obb_a.contains(from.0 - ux * eps, from.1 - uy * eps));
+        assert!(!obb_a.contains(from.0 + ux * eps, from.1 + uy * eps));
+        assert!(obb_b.contains(to.0 + ux * eps, to.1 + uy * eps));
+        assert!(!obb_b.contains(to.0 - ux * eps, to.1 - uy * eps));
+    }
+}
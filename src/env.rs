@@ -0,0 +1,181 @@
+#[cfg(target_arch = "wasm32")]
+use js_sys::Reflect;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use web_sys::console;
+
+use crate::WordCloud;
+
+// 运行环境中可用的宿主能力，构造时探测一次
+#[derive(Clone, Copy, Serialize)]
+pub(crate) struct Environment {
+    console: bool,
+    canvas: bool,
+    offscreen_canvas: bool,
+    performance: bool,
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    // 日志输出前检查 console 是否存在，只探测一次
+    static CONSOLE_AVAILABLE: bool = has_global("console");
+}
+
+// 全局对象上是否存在指定名称的属性；原生目标上没有 JS 宿主，总是 false
+#[cfg(target_arch = "wasm32")]
+fn has_global(name: &str) -> bool {
+    Reflect::has(&js_sys::global(), &JsValue::from_str(name)).unwrap_or(false)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn has_global(_name: &str) -> bool {
+    false
+}
+
+impl Environment {
+    pub(crate) fn probe() -> Environment {
+        Environment {
+            console: has_global("console"),
+            canvas: has_global("document") && has_global("HTMLCanvasElement"),
+            offscreen_canvas: has_global("OffscreenCanvas"),
+            performance: has_global("performance"),
+        }
+    }
+}
+
+// 输出日志；没有 console 的环境（包括原生目标）中不做任何事
+pub(crate) fn log(message: &str) {
+    #[cfg(target_arch = "wasm32")]
+    if CONSOLE_AVAILABLE.with(|available| *available) {
+        console::log_1(&JsValue::from_str(message));
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = message;
+}
+
+// 当前时间（毫秒），用于统计耗时
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now_ms() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0)
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 报告构造时探测到的运行环境能力：console、canvas、OffscreenCanvas、performance
+    #[wasm_bindgen]
+    pub fn detect_environment(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.environment).unwrap_or(JsValue::NULL)
+    }
+}
+
+impl WordCloud {
+    // 需要 DOM canvas 的方法在调用前检查，不可用时返回明确的错误
+    pub(crate) fn require_canvas(&self) -> Result<(), JsValue> {
+        if self.environment.canvas {
+            Ok(())
+        } else {
+            Err(JsError::new(
+                "canvas measurement unavailable in this environment; pass a canvas context to measure_with_context, or use generate_layout, which falls back to the heuristic width estimation",
+            )
+            .into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_cloud;
+
+    #[test]
+    fn native_targets_report_no_host_features_and_still_lay_out() {
+        let mut cloud = test_cloud();
+        let environment = cloud.environment;
+        assert!(!environment.console);
+        assert!(!environment.canvas);
+        assert!(!environment.offscreen_canvas);
+        assert!(!environment.performance);
+
+        // 没有 console 时日志被丢弃，布局照常完成
+        let layout = cloud.generate_layout(r#"[{"text":"alpha","weight":5}]"#.to_string());
+        let placed: Vec<serde_json::Value> = serde_json::from_str(&layout).unwrap();
+        assert_eq!(placed.len(), 1);
+    }
+}
+
+// 在 Node 中运行（wasm-pack test --node）：有 console，没有 document 和 canvas
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+    use web_sys::CanvasRenderingContext2d;
+
+    use crate::test_cloud;
+
+    // 没有 canvas 时在使用 ctx 之前就拒绝，传入占位的 null 即可
+    fn no_context() -> CanvasRenderingContext2d {
+        JsValue::NULL.unchecked_into()
+    }
+
+    #[wasm_bindgen_test]
+    fn node_has_console_but_no_canvas() {
+        let cloud = test_cloud();
+        assert!(cloud.environment.console);
+        assert!(!cloud.environment.canvas);
+    }
+
+    #[wasm_bindgen_test]
+    fn detect_environment_reports_the_probed_capabilities() {
+        let cloud = test_cloud();
+        let report = cloud.detect_environment();
+        let get = |name: &str| {
+            js_sys::Reflect::get(&report, &JsValue::from_str(name))
+                .unwrap()
+                .as_bool()
+        };
+        assert_eq!(get("console"), Some(true));
+        assert_eq!(get("canvas"), Some(false));
+        assert_eq!(
+            get("offscreen_canvas"),
+            Some(cloud.environment.offscreen_canvas)
+        );
+        assert_eq!(get("performance"), Some(cloud.environment.performance));
+    }
+
+    #[wasm_bindgen_test]
+    fn heuristic_layout_and_svg_export_run_without_browser_apis() {
+        let mut cloud = test_cloud();
+        let layout = cloud.generate_layout(crate::weighted_words(30));
+        let placed: Vec<serde_json::Value> = serde_json::from_str(&layout).unwrap();
+        assert!(!placed.is_empty());
+        let svg = cloud.generate_svg();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(">w0<"));
+    }
+
+    #[wasm_bindgen_test]
+    fn canvas_methods_reject_without_a_dom() {
+        let mut cloud = test_cloud();
+        let error = cloud
+            .generate_glyph_fill_layout("A".to_string(), "[]".to_string(), &no_context())
+            .unwrap_err();
+        let message: String = error.unchecked_into::<js_sys::Error>().message().into();
+        assert!(message.contains("measure_with_context"));
+        assert!(message.contains("heuristic width estimation"));
+        assert!(cloud
+            .explode_word_in_silhouette("word".to_string(), None, &no_context())
+            .is_err());
+
+        // 普通布局不依赖 canvas
+        let layout = cloud.generate_layout(r#"[{"text":"alpha","weight":5}]"#.to_string());
+        assert!(layout.contains("alpha"));
+    }
+}
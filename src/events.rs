@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::env::log;
+use crate::{LayoutContext, LayoutEngine, WordCloud, WordItem, WordPosition};
+
+// 流式更新事件：type 为 "add"、"remove" 或 "update"
+#[derive(Deserialize)]
+struct WordEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    word: WordItem,
+}
+
+// 处理事件后的结果：当前已放置的单词数量，以及本次新增、移除或更新的单词
+#[derive(Serialize)]
+struct EventResult {
+    placed_count: usize,
+    delta: Option<WordPosition>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 处理一条流式更新事件（例如来自 server-sent events），在当前布局上增量修改：
+    // add 放置新单词，remove 按 key 移除单词，update 以新权重重新放置单词
+    #[wasm_bindgen]
+    pub fn update_from_event(&mut self, event_json: String) -> String {
+        let event: WordEvent = match serde_json::from_str(&event_json) {
+            Ok(event) => event,
+            Err(e) => {
+                log(&format!("解析事件JSON失败: {}", e));
+                return "{}".to_string();
+            }
+        };
+
+        let delta = match event.kind.as_str() {
+            "add" => self.try_place_word(event.word),
+            "remove" => self.remove_word(event.word.key()),
+            "update" => self.update_word_weight(event.word),
+            other => {
+                log(&format!("未知的事件类型: {}", other));
+                None
+            }
+        };
+
+        let result = EventResult {
+            placed_count: self.placed.len(),
+            delta: delta.map(|word| self.emit_position(&word)),
+        };
+        serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+impl LayoutEngine {
+    // 在当前布局上放置一个单词，成功时返回它的位置；
+    // 沿用上次布局的权重范围，还没有布局时以该单词自身的权重建立
+    fn try_place_word(&mut self, mut word: WordItem) -> Option<WordPosition> {
+        word.normalize_compound();
+        let context = match &self.layout_context {
+            Some(context) => context.clone(),
+            None => {
+                let (center_x, center_y) = self.layout_center();
+                let context = LayoutContext {
+                    min_weight: word.weight,
+                    max_weight: word.weight,
+                    center_x,
+                    center_y,
+                    palette: self.effective_palette(),
+                };
+                self.layout_context = Some(context.clone());
+                context
+            }
+        };
+
+        if self.place_word(word, &context) {
+            self.placed.last().cloned()
+        } else {
+            None
+        }
+    }
+
+    // 按 key 移除已放置的单词并释放其占用的网格，返回被移除的单词
+    fn remove_word(&mut self, key: &str) -> Option<WordPosition> {
+        let index = self.placed.iter().position(|word| word.key() == key)?;
+        let removed = self.placed.remove(index);
+        self.rebuild_grid();
+        self.layout_stats.placed = self.layout_stats.placed.saturating_sub(1);
+        self.touch_layout();
+        Some(removed)
+    }
+
+    // 移除同 key 的单词后按新权重重新放置；单词不存在时等同于 add。
+    // 新权重下放不下时恢复原来的位置，返回 None
+    fn update_word_weight(&mut self, word: WordItem) -> Option<WordPosition> {
+        let index = self.placed.iter().position(|w| w.key() == word.key());
+        let removed = self.remove_word(word.key());
+        let placed = self.try_place_word(word);
+        if placed.is_none() {
+            if let (Some(index), Some(removed)) = (index, removed) {
+                self.placed.insert(index, removed);
+                self.rebuild_grid();
+                self.layout_stats.placed += 1;
+                self.touch_layout();
+            }
+        }
+        placed
+    }
+}
@@ -0,0 +1,299 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::env::log;
+use crate::footprint::Footprint;
+use crate::geometry::Obb;
+use crate::{LayoutContext, LayoutEngine, WordCloud, WordItem, WordPosition};
+
+// 寻找驱逐位置时沿螺旋采样的候选位置数量及角度步长
+const EVICTION_CANDIDATES: usize = 400;
+const EVICTION_SPIRAL_STEP: f64 = 0.3;
+// 按得分从低到高实际尝试的候选位置数量（每次尝试都需要重建网格）
+const EVICTION_ATTEMPTS: usize = 8;
+
+// 驱逐放置的结果，均以单词 key 表示
+#[derive(Serialize)]
+struct EvictionResult {
+    placed: bool,
+    evicted: Vec<String>,
+    // 被驱逐后在其他位置重新放置成功的单词
+    replaced: Vec<String>,
+    // 被驱逐后无处可放的单词
+    skipped: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 放置一个单词；正常放置失败时，驱逐中心附近最多 max_evictions 个权重更低的单词
+    // 腾出位置，再尝试把被驱逐的单词放到别处
+    #[wasm_bindgen]
+    pub fn place_with_eviction(&mut self, word_json: String, max_evictions: u32) -> JsValue {
+        let mut word: WordItem = match serde_json::from_str(&word_json) {
+            Ok(word) => word,
+            Err(e) => {
+                log(&format!("解析单词JSON失败: {}", e));
+                return JsValue::NULL;
+            }
+        };
+        word.normalize_compound();
+        let result = self.place_evicting(word, max_evictions);
+        serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL)
+    }
+}
+
+impl LayoutEngine {
+    // place_with_eviction 的实现，返回未序列化的结果
+    fn place_evicting(&mut self, mut word: WordItem, max_evictions: u32) -> EvictionResult {
+        let context = self.layout_context.clone().unwrap_or_else(|| {
+            let (center_x, center_y) = self.layout_center();
+            LayoutContext {
+                min_weight: word.weight,
+                max_weight: word.weight,
+                center_x,
+                center_y,
+                palette: self.effective_palette(),
+            }
+        });
+
+        let mut result = EvictionResult {
+            placed: false,
+            evicted: Vec::new(),
+            replaced: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        let (measured, visual_rotation) = self.prepare_word(&mut word, &context);
+        // 搜索和驱逐都按扩展后的碰撞包围盒及碰撞旋转角度进行
+        let rotation = self.collision_rotation(visual_rotation);
+        let inflation =
+            self.footprint_inflation(&word.text, measured.size, measured.width, measured.height);
+        word.inflation = Some(inflation);
+        let search = Footprint::inflate(
+            0.0,
+            0.0,
+            measured.width,
+            measured.height,
+            rotation,
+            &inflation,
+        );
+        let (width, height) = (search.width, search.height);
+        if let Some((x, y)) = self.find_position_for_word(
+            &word,
+            context.center_x,
+            context.center_y,
+            width,
+            height,
+            rotation,
+        ) {
+            let (x, y) = Footprint { x, y, ..search }.word_center(rotation, &inflation);
+            self.commit_word(word, measured, x, y, visual_rotation, &context);
+            result.placed = true;
+            return result;
+        }
+
+        let candidates =
+            self.eviction_candidates(&word, &context, width, height, rotation, max_evictions);
+        let mut chosen = None;
+        for (x, y, victims) in candidates.into_iter().take(EVICTION_ATTEMPTS) {
+            // 从后往前移除，保证下标有效
+            let mut evicted: Vec<WordPosition> = Vec::new();
+            for &index in victims.iter().rev() {
+                evicted.push(self.placed.remove(index));
+            }
+            self.rebuild_grid();
+            self.touch_layout();
+
+            // 网格比包围盒粗糙，移除后仍可能碰撞，此时恢复原状并尝试下一个候选
+            let obb = Obb::new(x, y, width, height, rotation);
+            if !self.position_free(&word, &obb) {
+                for (&index, victim) in victims.iter().zip(evicted.into_iter().rev()) {
+                    self.placed.insert(index, victim);
+                }
+                self.rebuild_grid();
+                self.touch_layout();
+                continue;
+            }
+
+            chosen = Some((x, y, evicted));
+            break;
+        }
+
+        let (x, y, mut evicted) = match chosen {
+            Some(chosen) => chosen,
+            None => {
+                self.record_skipped(&word, measured.size, "no_space");
+                return result;
+            }
+        };
+
+        self.layout_stats.placed -= evicted.len() as u32;
+        let (x, y) = Footprint { x, y, ..search }.word_center(rotation, &inflation);
+        self.commit_word(word, measured, x, y, visual_rotation, &context);
+        result.placed = true;
+
+        // 权重高的被驱逐单词优先重新放置
+        evicted.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        for victim in evicted {
+            let key = victim.key().to_string();
+            result.evicted.push(key.clone());
+            if self.relocate(&victim) {
+                result.replaced.push(key);
+            } else {
+                let stub = WordItem {
+                    text: victim.text.clone(),
+                    weight: victim.weight,
+                    ..WordItem::default()
+                };
+                let size = victim.size.unwrap_or(self.options.max_size);
+                self.record_skipped(&stub, size, "evicted");
+                result.skipped.push(key);
+            }
+        }
+
+        result
+    }
+
+    // 沿螺旋采样候选位置，计算每个位置需要驱逐的单词，按驱逐的总权重升序排列
+    // 只驱逐权重低于新单词的单词，且数量不超过 max_evictions
+    fn eviction_candidates(
+        &self,
+        word: &WordItem,
+        context: &LayoutContext,
+        width: f64,
+        height: f64,
+        rotation: f64,
+        max_evictions: u32,
+    ) -> Vec<(f64, f64, Vec<usize>)> {
+        let canvas_width = self.options.width as f64;
+        let canvas_height = self.options.height as f64;
+        // 半径增长速度使相邻两圈相距约一个单词高度
+        let growth = height.max(1.0) / (2.0 * std::f64::consts::PI);
+        // 网格单元的余量，使按包围盒选出的驱逐集合也能满足网格检测
+        let margin = self.grid_size as f64 * 2.0;
+
+        let mut candidates: Vec<(f64, f64, f64, Vec<usize>)> = Vec::new();
+        for step in 0..EVICTION_CANDIDATES {
+            let t = step as f64 * EVICTION_SPIRAL_STEP;
+            let x = context.center_x + growth * t * t.cos();
+            let y = context.center_y + growth * t * t.sin();
+            let obb = Obb::new(x, y, width, height, rotation);
+            let (min_x, min_y, max_x, max_y) = obb.bounds();
+            if min_x < 0.0 || min_y < 0.0 || max_x > canvas_width || max_y > canvas_height {
+                continue;
+            }
+
+            let inflated = Obb::new(x, y, width + margin, height + margin, rotation);
+            let victims: Vec<usize> = self
+                .placed
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| self.collision_obb(other).intersects(&inflated))
+                .map(|(index, _)| index)
+                .collect();
+            if victims.is_empty()
+                || victims.len() > max_evictions as usize
+                || victims
+                    .iter()
+                    .any(|&i| self.placed[i].weight >= word.weight)
+            {
+                continue;
+            }
+
+            let cost: f64 = victims.iter().map(|&i| self.placed[i].weight).sum();
+            candidates.push((x, y, cost, victims));
+        }
+
+        // 稳定排序：总权重相同时保持螺旋顺序，即更靠近中心的位置优先
+        candidates.sort_by(|a, b| a.2.total_cmp(&b.2));
+        candidates
+            .into_iter()
+            .map(|(x, y, _, victims)| (x, y, victims))
+            .collect()
+    }
+
+    // 保持字号和旋转不变，为已有单词寻找新的位置
+    fn relocate(&mut self, word: &WordPosition) -> bool {
+        // 按碰撞包围盒搜索，单词中心与包围盒中心保持原有偏移
+        let obb = self.collision_obb(word);
+        let (width, height) = (obb.half_width * 2.0, obb.half_height * 2.0);
+        let (offset_x, offset_y) = (word.x - obb.cx, word.y - obb.cy);
+        let stub = WordItem {
+            text: word.text.clone(),
+            key: word.key.clone(),
+            color: word.color.clone(),
+            ..WordItem::default()
+        };
+        let (center_x, center_y) = self.layout_center();
+        match self.find_position_for_word(&stub, center_x, center_y, width, height, obb.rotation) {
+            Some((x, y)) => {
+                self.mark_grid_as_occupied(x, y, width, height, obb.rotation);
+                let footprint = word.footprint.as_ref().map(|footprint| Footprint {
+                    x,
+                    y,
+                    ..footprint.clone()
+                });
+                self.placed.push(WordPosition {
+                    x: x + offset_x,
+                    y: y + offset_y,
+                    footprint,
+                    ..word.clone()
+                });
+                self.layout_stats.placed += 1;
+                self.touch_layout();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::Obb;
+    use crate::{test_cloud, WordCloud, WordItem};
+
+    fn placed_weight(cloud: &WordCloud) -> f64 {
+        cloud.placed.iter().map(|word| word.weight).sum()
+    }
+
+    #[test]
+    fn heavy_latecomer_displaces_lighter_words_on_a_full_canvas() {
+        // 大量等权重的小单词铺满画布
+        let mut cloud = test_cloud();
+        cloud.options.max_size = 16.0;
+        let words: Vec<serde_json::Value> = (0..300)
+            .map(|i| serde_json::json!({"text": format!("t{}", i), "weight": 1}))
+            .collect();
+        cloud.generate_layout(serde_json::to_string(&words).unwrap());
+        let before = placed_weight(&cloud);
+        let count = cloud.placed.len();
+
+        let word = WordItem {
+            text: "heavyweight".to_string(),
+            weight: 5.0,
+            ..WordItem::default()
+        };
+        let result = cloud.place_evicting(word, 6);
+        assert!(result.placed);
+        assert!(!result.evicted.is_empty() && result.evicted.len() <= 6);
+        assert_eq!(
+            result.replaced.len() + result.skipped.len(),
+            result.evicted.len()
+        );
+        assert!(cloud.placed.iter().any(|word| word.text == "heavyweight"));
+        assert!(placed_weight(&cloud) > before);
+        assert_eq!(cloud.placed.len(), count + 1 - result.skipped.len());
+
+        let boxes: Vec<Obb> = cloud
+            .placed
+            .iter()
+            .map(|w| cloud.collision_obb(w))
+            .collect();
+        for (i, a) in boxes.iter().enumerate() {
+            for b in &boxes[i + 1..] {
+                assert!(!a.intersects(b));
+            }
+        }
+    }
+}
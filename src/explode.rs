@@ -0,0 +1,193 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::env::log;
+use crate::{LayoutEngine, WordCloud, WordItem, WordPosition};
+
+// 拆散后的单个字符及其在原字符串中的位置（按字符计）
+#[derive(Serialize)]
+struct ExplodedPiece {
+    index: usize,
+    #[serde(flatten)]
+    word: WordPosition,
+}
+
+// 把单词拆成字符（跳过空白），权重未给出或长度不符时使用该字符在单词中出现的次数；
+// 每个字符的 key 为其位置，以便在输出中找回顺序
+fn explode_items(text: &str, letter_weights: Option<Vec<f64>>) -> Vec<WordItem> {
+    let letters: Vec<(usize, char)> = text
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| !c.is_whitespace())
+        .collect();
+    let weights = letter_weights.filter(|weights| {
+        let valid = weights.len() == letters.len();
+        if !valid {
+            log("字符权重数量与字符数不符，按字符出现次数计算");
+        }
+        valid
+    });
+
+    letters
+        .iter()
+        .enumerate()
+        .map(|(i, &(index, c))| {
+            let weight = match &weights {
+                Some(weights) => weights[i],
+                None => letters.iter().filter(|(_, other)| *other == c).count() as f64,
+            };
+            WordItem {
+                text: c.to_string(),
+                weight,
+                key: Some(index.to_string()),
+                ..WordItem::default()
+            }
+        })
+        .collect()
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 把一个单词拆成字符，按 letter_weights 决定各字符的字号后正常布局；
+    // 输出中每个字符带有它在原字符串中的位置 index
+    #[wasm_bindgen]
+    pub fn explode_word(&mut self, text: String, letter_weights: Option<Vec<f64>>) -> String {
+        let items = explode_items(&text, letter_weights);
+        self.layout_exploded(items)
+    }
+
+    // 与 explode_word 相同，但把字符限制在原单词字形的遮罩内，使字符大致拼回单词的轮廓。
+    // 没有 DOM canvas 时以错误拒绝
+    #[wasm_bindgen]
+    pub fn explode_word_in_silhouette(
+        &mut self,
+        text: String,
+        letter_weights: Option<Vec<f64>>,
+        ctx: &CanvasRenderingContext2d,
+    ) -> Result<String, JsValue> {
+        self.require_canvas()?;
+        let glyph_mask = match self.render_glyph_mask(&text, ctx) {
+            Some(mask) => mask,
+            None => {
+                log("无法创建离屏 canvas，字形遮罩不可用");
+                return Ok("[]".to_string());
+            }
+        };
+
+        let items = explode_items(&text, letter_weights);
+        let previous = self.mask.replace(glyph_mask);
+        let result = self.layout_exploded(items);
+        self.mask = previous;
+        Ok(result)
+    }
+}
+
+impl LayoutEngine {
+    fn layout_exploded(&mut self, items: Vec<WordItem>) -> String {
+        self.reset_layout_state();
+        if let Some((items, context)) = self.prepare_words(items) {
+            self.place_all(items, &context);
+        }
+
+        let pieces: Vec<ExplodedPiece> = self
+            .emit_layout(&self.placed)
+            .into_iter()
+            .map(|word| ExplodedPiece {
+                index: word.key().parse().unwrap_or_default(),
+                word,
+            })
+            .collect();
+        serde_json::to_string(&pieces).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_cloud;
+
+    // 按 index 排序后拼回原字符串（不含空白）
+    fn recovered(output: &str) -> (Vec<usize>, String) {
+        let mut pieces: Vec<serde_json::Value> = serde_json::from_str(output).unwrap();
+        pieces.sort_by_key(|piece| piece["index"].as_u64().unwrap());
+        let indices = pieces
+            .iter()
+            .map(|piece| piece["index"].as_u64().unwrap() as usize)
+            .collect();
+        let text = pieces
+            .iter()
+            .map(|piece| piece["text"].as_str().unwrap())
+            .collect();
+        (indices, text)
+    }
+
+    #[test]
+    fn one_piece_per_character_in_recoverable_order() {
+        let mut cloud = test_cloud();
+        let output = cloud.explode_word("Rust wasm".to_string(), None);
+        let (indices, text) = recovered(&output);
+        assert_eq!(indices, vec![0, 1, 2, 3, 5, 6, 7, 8]);
+        assert_eq!(text, "Rustwasm");
+    }
+
+    #[test]
+    fn letter_weights_set_the_sizes() {
+        let mut cloud = test_cloud();
+        let output = cloud.explode_word("LOGO".to_string(), Some(vec![4.0, 1.0, 1.0, 1.0]));
+        let pieces: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+        assert_eq!(pieces.len(), 4);
+        for piece in &pieces {
+            let expected = if piece["index"] == 0 { 40.0 } else { 10.0 };
+            assert_eq!(piece["size"].as_f64().unwrap(), expected);
+        }
+    }
+}
+
+// 字形遮罩需要真实的 canvas，在浏览器中运行（wasm-pack test --headless --firefox）
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+    use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+    use crate::test_cloud;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn context() -> CanvasRenderingContext2d {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let canvas: HtmlCanvasElement = document
+            .create_element("canvas")
+            .unwrap()
+            .dyn_into()
+            .unwrap();
+        canvas
+            .get_context("2d")
+            .unwrap()
+            .unwrap()
+            .dyn_into()
+            .unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn silhouette_letters_land_inside_the_glyph_mask() {
+        let mut cloud = test_cloud();
+        let ctx = context();
+        let output = cloud
+            .explode_word_in_silhouette("HELLO".to_string(), None, &ctx)
+            .unwrap();
+        let pieces: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+        assert!(!pieces.is_empty());
+        // 布局结束后遮罩已恢复，重新生成同样的字形遮罩进行检查
+        assert!(cloud.mask.is_none());
+        let mask = cloud.render_glyph_mask("HELLO", &ctx).unwrap();
+        let (width, height) = (cloud.options.width as f64, cloud.options.height as f64);
+        for word in &cloud.placed {
+            assert!(cloud
+                .word_obb(word)
+                .corners()
+                .iter()
+                .all(|&(x, y)| mask.contains(x, y, width, height)));
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use js_sys::Function;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::geometry::Obb;
+use crate::{LayoutEngine, WordCloud, WordPosition};
+
+// 回调抛出异常或返回值无效时的警告
+const FOOTPRINT_WARNING: &str = "footprint callback failed, using zero inflation";
+
+// 碰撞包围盒在单词局部坐标系中四个方向上的额外扩展（像素）
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Inflation {
+    pub(crate) top: f64,
+    pub(crate) right: f64,
+    pub(crate) bottom: f64,
+    pub(crate) left: f64,
+}
+
+impl Inflation {
+    fn is_zero(&self) -> bool {
+        self.top == 0.0 && self.right == 0.0 && self.bottom == 0.0 && self.left == 0.0
+    }
+}
+
+// 扩展后的碰撞包围盒：中心坐标和尺寸，旋转角度与单词相同
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Footprint {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+}
+
+impl Footprint {
+    // 由单词中心和尺寸计算扩展后的包围盒，扩展量按单词旋转
+    pub(crate) fn inflate(
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        rotation: f64,
+        inflation: &Inflation,
+    ) -> Footprint {
+        let offset_x = (inflation.right - inflation.left) / 2.0;
+        let offset_y = (inflation.bottom - inflation.top) / 2.0;
+        let (sin_rot, cos_rot) = rotation.sin_cos();
+        Footprint {
+            x: x + offset_x * cos_rot - offset_y * sin_rot,
+            y: y + offset_x * sin_rot + offset_y * cos_rot,
+            width: width + inflation.left + inflation.right,
+            height: height + inflation.top + inflation.bottom,
+        }
+    }
+
+    // 由单词中心和尺寸反推四个方向的扩展量，是 inflate 的逆运算
+    pub(crate) fn inflation(
+        &self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        rotation: f64,
+    ) -> Inflation {
+        let (sin_rot, cos_rot) = rotation.sin_cos();
+        let (dx, dy) = (self.x - x, self.y - y);
+        let offset_x = dx * cos_rot + dy * sin_rot;
+        let offset_y = -dx * sin_rot + dy * cos_rot;
+        let extra_x = self.width - width;
+        let extra_y = self.height - height;
+        Inflation {
+            top: (extra_y / 2.0 - offset_y).max(0.0),
+            right: (extra_x / 2.0 + offset_x).max(0.0),
+            bottom: (extra_y / 2.0 + offset_y).max(0.0),
+            left: (extra_x / 2.0 - offset_x).max(0.0),
+        }
+    }
+
+    // 由扩展后包围盒的中心反推单词中心
+    pub(crate) fn word_center(&self, rotation: f64, inflation: &Inflation) -> (f64, f64) {
+        let offset_x = (inflation.right - inflation.left) / 2.0;
+        let offset_y = (inflation.bottom - inflation.top) / 2.0;
+        let (sin_rot, cos_rot) = rotation.sin_cos();
+        (
+            self.x - (offset_x * cos_rot - offset_y * sin_rot),
+            self.y - (offset_x * sin_rot + offset_y * cos_rot),
+        )
+    }
+}
+
+// 回调结果缓存，key 由回调的全部参数（文本、字号、宽、高）组成，
+// 字体或测量方式改变后宽高不同，不会命中旧的结果
+#[derive(Clone, Default)]
+pub(crate) struct FootprintCache {
+    entries: HashMap<(String, u64, u64, u64), Inflation>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置碰撞包围盒扩展回调，调用形式为 (text, size, width, height) =>
+    // {extra_top, extra_right, extra_bottom, extra_left}；扩展只影响碰撞检测，
+    // 输出的单词会附带 footprint 字段描述扩展后的包围盒
+    #[wasm_bindgen]
+    pub fn set_footprint_callback(&mut self, callback: Function) {
+        self.js_host.footprint_callback = Some(callback);
+        self.sync_host();
+        self.clear_footprint_cache();
+    }
+
+    // 清除碰撞包围盒扩展回调
+    #[wasm_bindgen]
+    pub fn clear_footprint_callback(&mut self) {
+        self.js_host.footprint_callback = None;
+        self.sync_host();
+        self.clear_footprint_cache();
+    }
+}
+
+impl LayoutEngine {
+    // 字宽表或测量方式改变时调用，丢弃按旧的宽高得到的结果
+    pub(crate) fn clear_footprint_cache(&mut self) {
+        self.footprint_cache = FootprintCache::default();
+    }
+
+    // 查询单词的包围盒扩展量，结果按回调参数缓存
+    pub(crate) fn footprint_inflation(
+        &mut self,
+        text: &str,
+        size: f64,
+        width: f64,
+        height: f64,
+    ) -> Inflation {
+        let key = (
+            text.to_string(),
+            size.to_bits(),
+            width.to_bits(),
+            height.to_bits(),
+        );
+        if let Some(inflation) = self.footprint_cache.entries.get(&key) {
+            return *inflation;
+        }
+
+        let inflation = match self.host.footprint(text, size, width, height) {
+            None => return Inflation::default(),
+            Some(Ok(inflation)) => inflation,
+            Some(Err(())) => {
+                // 同一次布局中只记录一次警告
+                if !self.warnings.iter().any(|w| w == FOOTPRINT_WARNING) {
+                    self.warnings.push(FOOTPRINT_WARNING.to_string());
+                }
+                Inflation::default()
+            }
+        };
+
+        self.footprint_cache.entries.insert(key, inflation);
+        inflation
+    }
+
+    // 没有扩展时返回 None，避免在输出中附带冗余的 footprint 字段
+    pub(crate) fn footprint_for(
+        &self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        rotation: f64,
+        inflation: &Inflation,
+    ) -> Option<Footprint> {
+        (!inflation.is_zero()).then(|| Footprint::inflate(x, y, width, height, rotation, inflation))
+    }
+
+    // 已放置单词参与碰撞检测的包围盒：有 footprint 时使用扩展后的包围盒
+    pub(crate) fn collision_obb(&self, word: &WordPosition) -> Obb {
+        match &word.footprint {
+            Some(footprint) => Obb::new(
+                footprint.x,
+                footprint.y,
+                footprint.width,
+                footprint.height,
+                self.collision_rotation(word.rotate),
+            ),
+            None => self.word_obb(word),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inflation_inverts_inflate_for_rotated_words() {
+        let inflation = Inflation {
+            top: 3.0,
+            right: 5.0,
+            bottom: 20.0,
+            left: 0.0,
+        };
+        let footprint = Footprint::inflate(100.0, 80.0, 60.0, 20.0, 0.7, &inflation);
+        let recovered = footprint.inflation(100.0, 80.0, 60.0, 20.0, 0.7);
+        for (a, b) in [
+            (recovered.top, inflation.top),
+            (recovered.right, inflation.right),
+            (recovered.bottom, inflation.bottom),
+            (recovered.left, inflation.left),
+        ] {
+            assert!((a - b).abs() < 1e-9);
+        }
+        let (x, y) = footprint.word_center(0.7, &inflation);
+        assert!((x - 100.0).abs() < 1e-9 && (y - 80.0).abs() < 1e-9);
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use js_sys::Function;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen_test::*;
+
+    use super::FOOTPRINT_WARNING;
+    use crate::{test_cloud, weighted_words};
+
+    #[wasm_bindgen_test]
+    fn extra_bottom_keeps_20px_clear_below_every_word() {
+        let mut cloud = test_cloud();
+        cloud.set_footprint_callback(Function::new_with_args(
+            "text, size, width, height",
+            "return { extra_bottom: 20 };",
+        ));
+        cloud.generate_layout(weighted_words(15));
+        assert!(cloud.placed.len() > 5);
+
+        for word in &cloud.placed {
+            // 输出的绘制几何不变，footprint 描述向下扩展 20 像素后的包围盒
+            let (width, height) = cloud.word_box(word);
+            let footprint = cloud.emit_position(word).footprint.unwrap();
+            assert!((footprint.width - width).abs() < 1e-9);
+            assert!((footprint.height - (height + 20.0)).abs() < 1e-9);
+            assert!((footprint.y - (word.y + 10.0)).abs() < 1e-9);
+            assert_eq!(footprint.x, word.x);
+        }
+
+        // 水平方向有重叠、位于下方的单词与上方单词底边至少相距 20 像素
+        let bounds: Vec<_> = cloud
+            .placed
+            .iter()
+            .map(|word| cloud.word_obb(word).bounds())
+            .collect();
+        for (i, a) in bounds.iter().enumerate() {
+            for (j, b) in bounds.iter().enumerate() {
+                let overlaps_horizontally = a.0 < b.2 && b.0 < a.2;
+                if i != j && overlaps_horizontally && b.1 >= a.1 {
+                    assert!(b.1 - a.3 >= 20.0 - 1e-9);
+                }
+            }
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn throwing_callback_warns_once_and_uses_no_inflation() {
+        let mut cloud = test_cloud();
+        cloud.set_footprint_callback(Function::new_no_args("throw new Error('boom');"));
+        cloud.generate_layout(weighted_words(10));
+        assert!(cloud.placed.iter().all(|word| word.footprint.is_none()));
+        let warnings = cloud
+            .warnings
+            .iter()
+            .filter(|warning| *warning == FOOTPRINT_WARNING)
+            .count();
+        assert_eq!(warnings, 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn callback_results_are_cached_per_measurement() {
+        let calls = js_sys::Array::new();
+        let callback = Function::new_with_args(
+            "calls",
+            "return (text, size, width, height) => { calls.push(text); return { extra_left: 4 }; };",
+        )
+        .call1(&JsValue::NULL, &calls)
+        .unwrap();
+        let mut cloud = test_cloud();
+        cloud.set_footprint_callback(callback.into());
+        cloud.generate_layout(weighted_words(10));
+        let first = calls.length();
+        assert!(first >= 10);
+        cloud.generate_layout(weighted_words(10));
+        assert_eq!(calls.length(), first);
+    }
+}
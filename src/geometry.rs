@@ -0,0 +1,189 @@
+// 旋转矩形（有向包围盒），用于精确的几何检测
+#[derive(Clone, Copy)]
+pub(crate) struct Obb {
+    pub(crate) cx: f64,
+    pub(crate) cy: f64,
+    pub(crate) half_width: f64,
+    pub(crate) half_height: f64,
+    pub(crate) rotation: f64,
+}
+
+impl Obb {
+    pub(crate) fn new(cx: f64, cy: f64, width: f64, height: f64, rotation: f64) -> Obb {
+        Obb {
+            cx,
+            cy,
+            half_width: width / 2.0,
+            half_height: height / 2.0,
+            rotation,
+        }
+    }
+
+    // 旋转后的四个角
+    pub(crate) fn corners(&self) -> [(f64, f64); 4] {
+        let (sin_rot, cos_rot) = self.rotation.sin_cos();
+        let (hw, hh) = (self.half_width, self.half_height);
+        [(-hw, -hh), (hw, -hh), (hw, hh), (-hw, hh)].map(|(x, y)| {
+            (
+                x * cos_rot - y * sin_rot + self.cx,
+                x * sin_rot + y * cos_rot + self.cy,
+            )
+        })
+    }
+
+    // 两条分离轴（矩形的两条边方向）
+    pub(crate) fn axes(&self) -> [(f64, f64); 2] {
+        let (sin_rot, cos_rot) = self.rotation.sin_cos();
+        [(cos_rot, sin_rot), (-sin_rot, cos_rot)]
+    }
+
+    // 在某条轴上的投影区间
+    fn project(&self, axis: (f64, f64)) -> (f64, f64) {
+        self.corners()
+            .iter()
+            .map(|(x, y)| x * axis.0 + y * axis.1)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                (lo.min(p), hi.max(p))
+            })
+    }
+
+    // 从中心沿方向 (dir_x, dir_y) 出发到达边界的距离，方向无需归一化
+    pub(crate) fn ray_exit(&self, dir_x: f64, dir_y: f64) -> f64 {
+        let length = (dir_x * dir_x + dir_y * dir_y).sqrt();
+        if length == 0.0 {
+            return 0.0;
+        }
+        let [u, v] = self.axes();
+        let along_u = ((dir_x * u.0 + dir_y * u.1) / length).abs();
+        let along_v = ((dir_x * v.0 + dir_y * v.1) / length).abs();
+        let exit_u = if along_u > 0.0 {
+            self.half_width / along_u
+        } else {
+            f64::INFINITY
+        };
+        let exit_v = if along_v > 0.0 {
+            self.half_height / along_v
+        } else {
+            f64::INFINITY
+        };
+        exit_u.min(exit_v)
+    }
+
+    // 轴对齐包围盒 (min_x, min_y, max_x, max_y)
+    pub(crate) fn bounds(&self) -> (f64, f64, f64, f64) {
+        self.corners().iter().fold(
+            (
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |(min_x, min_y, max_x, max_y), &(x, y)| {
+                (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+            },
+        )
+    }
+
+    // 点是否落在矩形内（含边界）
+    pub(crate) fn contains(&self, x: f64, y: f64) -> bool {
+        let [u, v] = self.axes();
+        let (dx, dy) = (x - self.cx, y - self.cy);
+        (dx * u.0 + dy * u.1).abs() <= self.half_width
+            && (dx * v.0 + dy * v.1).abs() <= self.half_height
+    }
+
+    // 外接圆半径
+    pub(crate) fn circumradius(&self) -> f64 {
+        (self.half_width * self.half_width + self.half_height * self.half_height).sqrt()
+    }
+
+    // 两个旋转矩形之间的最短距离，相交时为 0
+    pub(crate) fn distance(&self, other: &Obb) -> f64 {
+        if self.intersects(other) {
+            return 0.0;
+        }
+
+        let a = self.corners();
+        let b = other.corners();
+        let mut best = f64::INFINITY;
+        for i in 0..4 {
+            let (b0, b1) = (b[i], b[(i + 1) % 4]);
+            let (a0, a1) = (a[i], a[(i + 1) % 4]);
+            for &p in &a {
+                best = best.min(point_segment_distance(p, b0, b1));
+            }
+            for &p in &b {
+                best = best.min(point_segment_distance(p, a0, a1));
+            }
+        }
+        best
+    }
+
+    // 使 self 与 other 分离所需的最小平移向量（分离轴上重叠最小的方向），不相交时为 None
+    pub(crate) fn penetration(&self, other: &Obb) -> Option<(f64, f64)> {
+        let mut best: Option<(f64, (f64, f64))> = None;
+        for &axis in self.axes().iter().chain(other.axes().iter()) {
+            let (a_lo, a_hi) = self.project(axis);
+            let (b_lo, b_hi) = other.project(axis);
+            let overlap = (a_hi - b_lo).min(b_hi - a_lo);
+            if overlap <= 0.0 {
+                return None;
+            }
+            if best.is_none_or(|(min, _)| overlap < min) {
+                best = Some((overlap, axis));
+            }
+        }
+        // 沿着从 other 指向 self 的方向推开
+        best.map(|(overlap, axis)| {
+            let toward = (self.cx - other.cx) * axis.0 + (self.cy - other.cy) * axis.1;
+            let sign = if toward < 0.0 { -1.0 } else { 1.0 };
+            (axis.0 * overlap * sign, axis.1 * overlap * sign)
+        })
+    }
+
+    // self 沿单位方向 dir 平移多远后与 other 分离（恰好接触），不相交时为 0；
+    // dir 与所有分离轴都垂直时无法分离，返回 None
+    pub(crate) fn separation_along(&self, other: &Obb, dir: (f64, f64)) -> Option<f64> {
+        if !self.intersects(other) {
+            return Some(0.0);
+        }
+        self.axes()
+            .iter()
+            .chain(other.axes().iter())
+            .filter_map(|&axis| {
+                let speed = dir.0 * axis.0 + dir.1 * axis.1;
+                let (a_lo, a_hi) = self.project(axis);
+                let (b_lo, b_hi) = other.project(axis);
+                if speed > f64::EPSILON {
+                    Some((b_hi - a_lo) / speed)
+                } else if speed < -f64::EPSILON {
+                    Some((a_hi - b_lo) / -speed)
+                } else {
+                    None
+                }
+            })
+            .min_by(f64::total_cmp)
+    }
+
+    // 分离轴定理 (SAT) 检测两个旋转矩形是否相交，仅接触不算相交
+    pub(crate) fn intersects(&self, other: &Obb) -> bool {
+        self.axes().iter().chain(other.axes().iter()).all(|&axis| {
+            let (a_lo, a_hi) = self.project(axis);
+            let (b_lo, b_hi) = other.project(axis);
+            a_lo < b_hi && b_lo < a_hi
+        })
+    }
+}
+
+// 点到线段的距离
+fn point_segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (abx, aby) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = abx * abx + aby * aby;
+    let t = if length_sq > 0.0 {
+        (((p.0 - a.0) * abx + (p.1 - a.1) * aby) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (x, y) = (a.0 + t * abx, a.1 + t * aby);
+    ((p.0 - x).powi(2) + (p.1 - y).powi(2)).sqrt()
+}
@@ -0,0 +1,222 @@
+use crate::LayoutEngine;
+
+// 碰撞网格按行存放在一段连续的 Vec<u8> 中，每个字节记录同一行相邻 8 个单元，
+// 单元 (i, j) 位于 grid[j * grid_stride + i / 8] 的第 i % 8 位
+
+// 网格的列数、行数和每行占用的字节数
+pub(crate) fn grid_layout(width: u32, height: u32, grid_size: usize) -> (usize, usize, usize) {
+    let columns = (width as usize / grid_size) + 1;
+    let rows = (height as usize / grid_size) + 1;
+    (columns, rows, columns.div_ceil(8))
+}
+
+impl LayoutEngine {
+    // 按画布尺寸分配一张全空的网格
+    pub(crate) fn allocate_grid(&mut self) -> (usize, usize) {
+        let (columns, rows, stride) =
+            grid_layout(self.options.width, self.options.height, self.grid_size);
+        self.grid = vec![0; stride * rows];
+        self.grid_columns = columns;
+        self.grid_stride = stride;
+        (columns, rows)
+    }
+
+    // 网格的列数和行数
+    pub(crate) fn grid_dimensions(&self) -> (usize, usize) {
+        let rows = self.grid.len().checked_div(self.grid_stride).unwrap_or(0);
+        (self.grid_columns, rows)
+    }
+
+    // 单元 (i, j) 是否已占用，超出网格的单元视为空闲
+    pub(crate) fn grid_cell(&self, i: usize, j: usize) -> bool {
+        i < self.grid_columns
+            && self
+                .grid
+                .get(j * self.grid_stride + i / 8)
+                .is_some_and(|byte| byte >> (i % 8) & 1 == 1)
+    }
+
+    // 标记单元 (i, j) 为已占用，超出网格的单元忽略
+    pub(crate) fn occupy_grid_cell(&mut self, i: usize, j: usize) {
+        if i >= self.grid_columns {
+            return;
+        }
+        if let Some(byte) = self.grid.get_mut(j * self.grid_stride + i / 8) {
+            *byte |= 1 << (i % 8);
+        }
+    }
+
+    // 清除单元 (i, j) 的占用标记，超出网格的单元忽略
+    pub(crate) fn free_grid_cell(&mut self, i: usize, j: usize) {
+        if i >= self.grid_columns {
+            return;
+        }
+        if let Some(byte) = self.grid.get_mut(j * self.grid_stride + i / 8) {
+            *byte &= !(1 << (i % 8));
+        }
+    }
+
+    // 已放置单词 index 在网格中标记的范围，与 rebuild_grid 使用的碰撞包围盒一致
+    fn placed_cell_range(&self, index: usize) -> (usize, usize, usize, usize) {
+        let obb = self.collision_obb(&self.placed[index]);
+        self.grid_cell_range(
+            obb.cx,
+            obb.cy,
+            obb.half_width * 2.0,
+            obb.half_height * 2.0,
+            obb.rotation,
+        )
+    }
+
+    // 在网格中标记已放置的单词 index
+    pub(crate) fn mark_placed_word(&mut self, index: usize) {
+        let obb = self.collision_obb(&self.placed[index]);
+        self.mark_grid_as_occupied(
+            obb.cx,
+            obb.cy,
+            obb.half_width * 2.0,
+            obb.half_height * 2.0,
+            obb.rotation,
+        );
+    }
+
+    // 清除单词 index 占用的单元，再补标与这些单元重叠的其他单词，
+    // 结果与不含该单词时重建的网格相同；随后 mark_placed_word 即可恢复原网格
+    pub(crate) fn unmark_placed_word(&mut self, index: usize) {
+        let (min_i, max_i, min_j, max_j) = self.placed_cell_range(index);
+        for i in min_i..=max_i {
+            for j in min_j..=max_j {
+                self.free_grid_cell(i, j);
+            }
+        }
+
+        for other in (0..self.placed.len()).filter(|&other| other != index) {
+            let (other_min_i, other_max_i, other_min_j, other_max_j) =
+                self.placed_cell_range(other);
+            let overlaps = other_min_i <= max_i
+                && min_i <= other_max_i
+                && other_min_j <= max_j
+                && min_j <= other_max_j;
+            if overlaps {
+                self.mark_placed_word(other);
+            }
+        }
+    }
+
+    // 已占用的单元数；每行末尾的填充位不会被置位，可以直接按字节计数
+    pub(crate) fn occupied_grid_cells(&self) -> usize {
+        self.grid
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crate::{test_cloud, weighted_words};
+
+    #[test]
+    fn cells_round_trip_and_out_of_range_cells_are_free() {
+        let mut cloud = test_cloud();
+        let (columns, rows) = cloud.grid_dimensions();
+        assert_eq!(cloud.occupied_grid_cells(), 0);
+
+        cloud.occupy_grid_cell(0, 0);
+        cloud.occupy_grid_cell(9, 3);
+        cloud.occupy_grid_cell(columns - 1, rows - 1);
+        // 超出网格的单元被忽略，不会写进下一行的字节
+        cloud.occupy_grid_cell(columns, 0);
+        cloud.occupy_grid_cell(0, rows);
+
+        assert!(cloud.grid_cell(0, 0));
+        assert!(cloud.grid_cell(9, 3));
+        assert!(cloud.grid_cell(columns - 1, rows - 1));
+        assert!(!cloud.grid_cell(8, 3));
+        assert!(!cloud.grid_cell(columns, 0));
+        assert!(!cloud.grid_cell(0, 1));
+        assert_eq!(cloud.occupied_grid_cells(), 3);
+    }
+
+    #[test]
+    fn unmarking_a_word_matches_the_grid_rebuilt_without_it() {
+        let mut cloud = test_cloud();
+        cloud.generate_layout(weighted_words(30));
+        let full = cloud.grid.clone();
+
+        cloud.unmark_placed_word(4);
+        let unmarked = cloud.grid.clone();
+        let original = cloud.placed.remove(4);
+        cloud.rebuild_grid();
+        assert_eq!(unmarked, cloud.grid);
+        assert_ne!(unmarked, full);
+
+        cloud.placed.insert(4, original);
+        cloud.grid = unmarked;
+        cloud.mark_placed_word(4);
+        assert_eq!(cloud.grid, full);
+    }
+
+    // 与改用位图之前的 Vec<Vec<bool>> 网格比较分配以及标记加扫描的耗时：
+    // cargo test --release grid -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn compare_bitset_grid_with_nested_vectors() {
+        const ROUNDS: usize = 20;
+        let mut cloud = test_cloud();
+        cloud.set_size(4000, 4000);
+        let (columns, rows) = cloud.grid_dimensions();
+
+        let started = Instant::now();
+        for _ in 0..ROUNDS {
+            cloud.allocate_grid();
+        }
+        let bitset_allocation = started.elapsed();
+        let started = Instant::now();
+        let mut nested_grids = Vec::with_capacity(ROUNDS);
+        for _ in 0..ROUNDS {
+            nested_grids.push(vec![vec![false; rows]; columns]);
+        }
+        let nested_allocation = started.elapsed();
+
+        let started = Instant::now();
+        for i in (0..columns).step_by(3) {
+            for j in (0..rows).step_by(2) {
+                cloud.occupy_grid_cell(i, j);
+            }
+        }
+        let occupied = (0..columns)
+            .flat_map(|i| (0..rows).map(move |j| (i, j)))
+            .filter(|&(i, j)| cloud.grid_cell(i, j))
+            .count();
+        let bitset_access = started.elapsed();
+
+        let grid = &mut nested_grids[0];
+        let started = Instant::now();
+        for i in (0..columns).step_by(3) {
+            for j in (0..rows).step_by(2) {
+                grid[i][j] = true;
+            }
+        }
+        let nested_occupied = (0..columns)
+            .flat_map(|i| (0..rows).map(move |j| (i, j)))
+            .filter(|&(i, j)| grid[i][j])
+            .count();
+        let nested_access = started.elapsed();
+
+        assert_eq!(occupied, nested_occupied);
+        println!(
+            "{}x{} grid: allocation x{} bitset {:?} / Vec<Vec<bool>> {:?}, \
+             mark + scan bitset {:?} / Vec<Vec<bool>> {:?}",
+            columns,
+            rows,
+            ROUNDS,
+            bitset_allocation,
+            nested_allocation,
+            bitset_access,
+            nested_access
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use js_sys::{Array, Function, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+use crate::env::log;
+use crate::footprint::Inflation;
+use crate::render::draw_word;
+use crate::{LayoutEngine, WordItem, WordPosition};
+
+// 布局引擎向宿主要的能力。引擎本身不接触 JS，缺少某项能力时按默认实现退化：
+// 不实测宽度、不栅格化、保持输入顺序、不扩展包围盒
+pub(crate) trait Host {
+    // 按 CSS font 字符串实测文本宽度
+    fn measure_text(&self, _font: &str, _text: &str) -> Option<f64> {
+        None
+    }
+
+    // 在 width x height 的透明画布上按 word 的坐标绘制单词，返回 RGBA 像素
+    fn rasterize_word(
+        &self,
+        _engine: &LayoutEngine,
+        _word: &WordPosition,
+        _width: u32,
+        _height: u32,
+    ) -> Option<Vec<u8>> {
+        None
+    }
+
+    // 按自定义顺序排列待放置的单词
+    fn order_words(&self, words: Vec<WordItem>) -> Vec<WordItem> {
+        words
+    }
+
+    // 单词碰撞包围盒的扩展量：没有设置回调时为 None，回调失败时为 Some(Err(()))
+    fn footprint(
+        &self,
+        _text: &str,
+        _size: f64,
+        _width: f64,
+        _height: f64,
+    ) -> Option<Result<Inflation, ()>> {
+        None
+    }
+}
+
+// 没有任何宿主能力，wordcloud_core 和新建的引擎使用
+pub(crate) struct NoHost;
+
+impl Host for NoHost {}
+
+// WordCloud 通过 JS 设置的 canvas 上下文和回调
+#[derive(Clone, Default)]
+pub(crate) struct JsHost {
+    pub(crate) measure_context: Option<CanvasRenderingContext2d>,
+    pub(crate) priority_fn: Option<Function>,
+    pub(crate) footprint_callback: Option<Function>,
+}
+
+impl Host for JsHost {
+    fn measure_text(&self, font: &str, text: &str) -> Option<f64> {
+        let ctx = self.measure_context.as_ref()?;
+
+        ctx.save();
+        ctx.set_font(font);
+        let metrics = ctx.measure_text(text);
+        ctx.restore();
+
+        Some(metrics.ok()?.width())
+    }
+
+    // 在离屏 canvas 上用最终渲染的绘制函数画出单词，组合词、注音和趋势线都会被栅格化
+    fn rasterize_word(
+        &self,
+        engine: &LayoutEngine,
+        word: &WordPosition,
+        width: u32,
+        height: u32,
+    ) -> Option<Vec<u8>> {
+        let ctx = self.measure_context.as_ref()?;
+        let document = ctx.canvas()?.owner_document()?;
+        let canvas: HtmlCanvasElement = document.create_element("canvas").ok()?.dyn_into().ok()?;
+        canvas.set_width(width);
+        canvas.set_height(height);
+        let offscreen: CanvasRenderingContext2d =
+            canvas.get_context("2d").ok()??.dyn_into().ok()?;
+
+        draw_word(engine, &offscreen, word);
+
+        Some(
+            offscreen
+                .get_image_data(0.0, 0.0, width as f64, height as f64)
+                .ok()?
+                .data()
+                .0,
+        )
+    }
+
+    // 直接使用 JS 的 Array.prototype.sort，比较函数不满足全序时也不会出错。
+    // 任何一步失败都保持原顺序
+    fn order_words(&self, words: Vec<WordItem>) -> Vec<WordItem> {
+        let callback = match &self.priority_fn {
+            Some(callback) => callback,
+            None => return words,
+        };
+
+        let array: Array = words
+            .iter()
+            .map(|word| JsValue::from_str(&serde_json::to_string(word).unwrap_or_default()))
+            .collect();
+
+        let sorted = Reflect::get(&array, &JsValue::from_str("sort"))
+            .ok()
+            .and_then(|sort| sort.dyn_into::<Function>().ok())
+            .and_then(|sort| sort.call1(&array, callback).ok());
+        if sorted.is_none() {
+            log("自定义排序失败，保持输入顺序");
+            return words;
+        }
+
+        let reordered: Option<Vec<WordItem>> = array
+            .iter()
+            .map(|value| serde_json::from_str(&value.as_string()?).ok())
+            .collect();
+        reordered.unwrap_or(words)
+    }
+
+    // 回调形式为 (text, size, width, height) => {extra_top, extra_right, extra_bottom, extra_left}，
+    // 缺少的字段和负数按 0 处理
+    fn footprint(
+        &self,
+        text: &str,
+        size: f64,
+        width: f64,
+        height: f64,
+    ) -> Option<Result<Inflation, ()>> {
+        let callback = self.footprint_callback.as_ref()?;
+
+        let args = Array::of4(
+            &JsValue::from_str(text),
+            &JsValue::from_f64(size),
+            &JsValue::from_f64(width),
+            &JsValue::from_f64(height),
+        );
+        Some(match callback.apply(&JsValue::NULL, &args) {
+            Ok(result) if result.is_object() => {
+                let field = |name: &str| {
+                    Reflect::get(&result, &JsValue::from_str(name))
+                        .ok()
+                        .and_then(|value| value.as_f64())
+                        .filter(|value| value.is_finite())
+                        .unwrap_or(0.0)
+                        .max(0.0)
+                };
+                Ok(Inflation {
+                    top: field("extra_top"),
+                    right: field("extra_right"),
+                    bottom: field("extra_bottom"),
+                    left: field("extra_left"),
+                })
+            }
+            _ => Err(()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::Host;
+    use crate::test_cloud;
+
+    // 每个字符固定 10 像素宽的宿主，不需要 canvas
+    struct MonospaceHost;
+
+    impl Host for MonospaceHost {
+        fn measure_text(&self, _font: &str, text: &str) -> Option<f64> {
+            Some(10.0 * text.chars().count() as f64)
+        }
+    }
+
+    #[test]
+    fn engine_measures_text_through_a_rust_host() {
+        let mut cloud = test_cloud();
+        assert_eq!(cloud.measured_width("abc", 20.0), None);
+
+        cloud.host = Rc::new(MonospaceHost);
+        assert_eq!(cloud.measured_width("abc", 20.0), Some(30.0));
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::env::log;
+use crate::{LayoutEngine, WordCloud};
+
+// 断字点左右至少保留的字符数（与 TeX 的 lefthyphenmin / righthyphenmin 相同）
+const LEFT_HYPHEN_MIN: usize = 2;
+const RIGHT_HYPHEN_MIN: usize = 3;
+
+// Liang 算法的英文断字模式（精简集合）：数字表示该位置的断字优先级，奇数允许断开，
+// "." 表示单词边界
+#[cfg(feature = "hyphenation-en")]
+const PATTERNS: &[&str] = &[
+    // Liang 论文中 hyphenation 一词用到的模式
+    "hy3ph", "he2n", "hena4", "hen5at", "1na", "n2at", "1tio", "2io", "o2n",
+    // 双写辅音之间断开
+    "b1b", "c1c", "d1d", "f1f", "g1g", "l1l", "m1m", "n1n", "p1p", "r1r", "s1s", "t1t", "z1z",
+    // 常见后缀之前断开
+    "1ment.", "1ness.", "1less.", "1ful.", "1able.", "1ible.", "1sion.", "1ture.", "1ship.",
+    "1hood.", "1ward.", "1ous.", "1ing.", "1ism.", "1ist.",
+    // 常见前缀之后断开
+    ".con1", ".dis1", ".mis1", ".non1", ".pre1", ".inter1", ".over1", ".under1", ".super1",
+    ".trans1",
+];
+
+#[cfg(not(feature = "hyphenation-en"))]
+const PATTERNS: &[&str] = &[];
+
+// 解析模式为字母序列和各字母之前的优先级（长度为字母数 + 1）
+fn parse_pattern(pattern: &str) -> (Vec<char>, Vec<u8>) {
+    let mut letters = Vec::new();
+    let mut values = Vec::new();
+    let mut pending = 0;
+    for c in pattern.chars() {
+        match c.to_digit(10) {
+            Some(digit) => pending = digit as u8,
+            None => {
+                letters.push(c);
+                values.push(pending);
+                pending = 0;
+            }
+        }
+    }
+    values.push(pending);
+    (letters, values)
+}
+
+// 按模式计算允许断字的位置（字符下标，在该字符之前断开）
+fn pattern_points(word: &str) -> Vec<usize> {
+    let chars: Vec<char> = word.chars().collect();
+    let mut padded = vec!['.'];
+    padded.extend(chars.iter().copied());
+    padded.push('.');
+
+    let mut priorities = vec![0u8; padded.len() + 1];
+    for pattern in PATTERNS {
+        let (letters, values) = parse_pattern(pattern);
+        for start in 0..padded.len() {
+            if padded[start..].starts_with(&letters) {
+                for (k, &value) in values.iter().enumerate() {
+                    priorities[start + k] = priorities[start + k].max(value);
+                }
+            }
+        }
+    }
+
+    // 原字符 j 之前的位置对应填充后的 j + 1
+    (LEFT_HYPHEN_MIN..=chars.len().saturating_sub(RIGHT_HYPHEN_MIN))
+        .filter(|&j| priorities[j + 1] % 2 == 1)
+        .collect()
+}
+
+// 把断字后的单词拆成两行，第一行末尾带连字符
+pub(crate) fn hyphen_lines(text: &str, at: usize) -> [String; 2] {
+    let first: String = text.chars().take(at).collect();
+    let second: String = text.chars().skip(at).collect();
+    [format!("{}-", first), second]
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置是否在单词放不下时尝试断字为两行
+    #[wasm_bindgen]
+    pub fn set_hyphenate(&mut self, hyphenate: bool) {
+        self.options.hyphenate = hyphenate;
+    }
+
+    // 设置断字例外，格式为 {"单词": "断-字-位-置"}，例外优先于内置模式
+    #[wasm_bindgen]
+    pub fn set_hyphenation_exceptions(&mut self, exceptions_json: String) -> bool {
+        match serde_json::from_str::<BTreeMap<String, String>>(&exceptions_json) {
+            Ok(exceptions) => {
+                self.options.hyphenation_exceptions = exceptions
+                    .into_iter()
+                    .map(|(word, hyphenated)| (word.to_lowercase(), hyphenated))
+                    .collect();
+                true
+            }
+            Err(e) => {
+                log(&format!("解析断字例外JSON失败: {}", e));
+                false
+            }
+        }
+    }
+}
+
+impl LayoutEngine {
+    // 单词中允许断字的位置：有例外时按例外中的连字符，否则按内置模式
+    fn hyphenation_points(&self, text: &str) -> Vec<usize> {
+        let lower = text.to_lowercase();
+        if let Some(exception) = self.options.hyphenation_exceptions.get(&lower) {
+            let mut points = Vec::new();
+            let mut index = 0;
+            for c in exception.chars() {
+                if c == '-' {
+                    points.push(index);
+                } else {
+                    index += 1;
+                }
+            }
+            // 例外与单词字符数不符时视为无效
+            return if index == text.chars().count() {
+                points
+            } else {
+                Vec::new()
+            };
+        }
+        pattern_points(&lower)
+    }
+
+    // 最佳断字位置：最接近单词中点的断字点，使两行宽度尽量接近
+    pub(crate) fn best_hyphen_point(&self, text: &str) -> Option<usize> {
+        if !self.options.hyphenate || text.chars().any(char::is_whitespace) {
+            return None;
+        }
+        let middle = text.chars().count() as f64 / 2.0;
+        self.hyphenation_points(text).into_iter().min_by(|&a, &b| {
+            (a as f64 - middle)
+                .abs()
+                .total_cmp(&(b as f64 - middle).abs())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cloud, WordCloud};
+
+    const WORDS: &str = r#"[{"text": "hyphenation", "weight": 2}, {"text": "ok", "weight": 1}]"#;
+
+    // 画布中间 160 像素宽的竖条，窄于 30 像素字号下 "hyphenation" 的整行宽度
+    fn narrow_cloud(hyphenate: bool) -> WordCloud {
+        let mut cloud = test_cloud();
+        cloud.options.min_size = 30.0;
+        cloud.options.max_size = 30.0;
+        let mut data = vec![0; 40 * 30];
+        for row in data.chunks_mut(40) {
+            row[12..28].fill(1);
+        }
+        cloud.set_mask(data, 40, 30);
+        cloud.set_hyphenate(hyphenate);
+        cloud
+    }
+
+    #[test]
+    fn long_word_in_a_narrow_region_is_hyphenated_across_two_lines() {
+        let mut cloud = narrow_cloud(true);
+        assert!(cloud.estimate_word_width("hyphenation", 30.0) > 160.0);
+        let layout: serde_json::Value =
+            serde_json::from_str(&cloud.generate_layout(WORDS.to_string())).unwrap();
+        let word = layout
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|w| w["text"] == "hyphenation")
+            .expect("断字后应能放下");
+        assert_eq!(word["hyphenated"], true);
+        assert_eq!(word["lines"], serde_json::json!(["hyphen-", "ation"]));
+        assert!(cloud.skipped.is_empty());
+
+        let mut cloud = narrow_cloud(false);
+        cloud.generate_layout(WORDS.to_string());
+        assert_eq!(cloud.placed.len(), 1);
+        assert_eq!(cloud.skipped.len(), 1);
+        assert_eq!(cloud.skipped[0].text, "hyphenation");
+        assert_eq!(cloud.skipped[0].reason, "no_space");
+    }
+}
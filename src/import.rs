@@ -0,0 +1,297 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::compare::parse_layout;
+use crate::geometry::Obb;
+use crate::{LayoutEngine, WordCloud, WordPosition};
+
+// 微调重叠单词时螺旋搜索的步数、每步半径增量和角度增量
+const NUDGE_STEPS: usize = 64;
+const NUDGE_RADIUS_STEP: f64 = 2.0;
+const NUDGE_ANGLE_STEP: f64 = 0.6;
+
+// 被微调的单词：原坐标和调整后的坐标（输出坐标系）
+#[derive(Serialize)]
+struct ImportAdjustment {
+    key: String,
+    from_x: f64,
+    from_y: f64,
+    x: f64,
+    y: f64,
+}
+
+// 导入结果：rejected 为 true 时当前布局保持不变
+#[derive(Serialize)]
+struct ImportResult {
+    placed: Vec<WordPosition>,
+    adjusted: Vec<ImportAdjustment>,
+    // 互相重叠的单词 key 对
+    conflicts: Vec<[String; 2]>,
+    // nudge 模式下找不到空位、只能保留原位置（仍然重叠）的单词 key
+    unresolved: Vec<String>,
+    rejected: bool,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置导入布局时遇到重叠单词的处理方式：
+    // "accept" 原样接受并在 warnings 中列出重叠，"nudge" 将后出现的单词沿小螺旋移开，
+    // 移不开的单词保留原位置并列入 unresolved，"reject" 拒绝整个导入并返回重叠的单词对
+    #[wasm_bindgen]
+    pub fn set_import_conflict_policy(&mut self, policy: String) {
+        self.options.import_conflict_policy = policy;
+    }
+
+    // 用一份已有布局替换当前布局，并按 import_conflict_policy 处理互相重叠的单词。
+    // 网格按最终返回的位置重建，之后放置的单词不会与它们重叠
+    #[wasm_bindgen]
+    pub fn load_layout(&mut self, layout_json: String) -> String {
+        let words: Vec<WordPosition> = parse_layout(&layout_json)
+            .into_iter()
+            .map(|word| self.import_position(word))
+            .collect();
+        let policy = self.options.import_conflict_policy.clone();
+
+        if policy == "reject" {
+            let conflicts = self.layout_conflicts(&words);
+            if !conflicts.is_empty() {
+                let result = ImportResult {
+                    placed: Vec::new(),
+                    adjusted: Vec::new(),
+                    conflicts,
+                    unresolved: Vec::new(),
+                    rejected: true,
+                };
+                return serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string());
+            }
+        }
+
+        self.reset_layout_state();
+        let mut adjusted = Vec::new();
+        let mut conflicts = Vec::new();
+        let mut unresolved = Vec::new();
+        for mut word in words {
+            let obb = self.collision_obb(&word);
+            let overlapping: Vec<String> = self
+                .placed
+                .iter()
+                .filter(|other| self.collision_obb(other).intersects(&obb))
+                .map(|other| other.key().to_string())
+                .collect();
+
+            if !overlapping.is_empty() && policy == "nudge" {
+                if let Some((x, y)) = self.nudge_position(&word) {
+                    let emitted = self.emit_position(&word);
+                    word.footprint = word.footprint.map(|mut footprint| {
+                        footprint.x += x - word.x;
+                        footprint.y += y - word.y;
+                        footprint
+                    });
+                    word.x = x;
+                    word.y = y;
+                    let moved = self.emit_position(&word);
+                    adjusted.push(ImportAdjustment {
+                        key: word.key().to_string(),
+                        from_x: emitted.x,
+                        from_y: emitted.y,
+                        x: moved.x,
+                        y: moved.y,
+                    });
+                    self.placed.push(word);
+                    continue;
+                }
+                self.warnings.push(format!(
+                    "imported word \"{}\" could not be nudged clear of its overlaps",
+                    word.key()
+                ));
+                unresolved.push(word.key().to_string());
+            }
+
+            for other in overlapping {
+                self.warnings.push(format!(
+                    "imported words \"{}\" and \"{}\" overlap",
+                    other,
+                    word.key()
+                ));
+                conflicts.push([other, word.key().to_string()]);
+            }
+            self.placed.push(word);
+        }
+
+        self.layout_stats.placed = self.placed.len() as u32;
+        self.rebuild_grid();
+        self.touch_layout();
+
+        let result = ImportResult {
+            placed: self.emit_layout(&self.placed),
+            adjusted,
+            conflicts,
+            unresolved,
+            rejected: false,
+        };
+        serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+impl LayoutEngine {
+    // 两两检查布局中的单词，返回所有互相重叠的单词对
+    fn layout_conflicts(&self, words: &[WordPosition]) -> Vec<[String; 2]> {
+        let boxes: Vec<Obb> = words.iter().map(|word| self.collision_obb(word)).collect();
+        let mut conflicts = Vec::new();
+        for (i, a) in boxes.iter().enumerate() {
+            for (j, b) in boxes.iter().enumerate().skip(i + 1) {
+                if a.intersects(b) {
+                    conflicts.push([words[i].key().to_string(), words[j].key().to_string()]);
+                }
+            }
+        }
+        conflicts
+    }
+
+    // 从原位置出发沿小螺旋寻找不与已导入单词重叠、且不超出画布的位置
+    fn nudge_position(&self, word: &WordPosition) -> Option<(f64, f64)> {
+        let obb = self.collision_obb(word);
+        let (width, height) = (self.options.width as f64, self.options.height as f64);
+        (1..=NUDGE_STEPS).find_map(|step| {
+            let angle = step as f64 * NUDGE_ANGLE_STEP;
+            let radius = step as f64 * NUDGE_RADIUS_STEP;
+            let candidate = Obb {
+                cx: obb.cx + radius * angle.cos(),
+                cy: obb.cy + radius * angle.sin(),
+                ..obb
+            };
+            let (min_x, min_y, max_x, max_y) = candidate.bounds();
+            let clear = min_x >= 0.0
+                && min_y >= 0.0
+                && max_x <= width
+                && max_y <= height
+                && !self
+                    .placed
+                    .iter()
+                    .any(|other| self.collision_obb(other).intersects(&candidate));
+            clear.then_some((
+                word.x + candidate.cx - obb.cx,
+                word.y + candidate.cy - obb.cy,
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::{test_cloud, WordCloud};
+
+    // 两个大部分重叠的单词，second 在后面
+    const OVERLAPPING: &str = r#"[
+        {"text": "first", "weight": 2, "x": 200, "y": 150, "rotate": 0, "size": 30},
+        {"text": "second", "weight": 1, "x": 215, "y": 155, "rotate": 0, "size": 30}
+    ]"#;
+
+    fn load(policy: &str) -> (WordCloud, Value) {
+        let mut cloud = test_cloud();
+        cloud.set_import_conflict_policy(policy.to_string());
+        let result = serde_json::from_str(&cloud.load_layout(OVERLAPPING.to_string())).unwrap();
+        (cloud, result)
+    }
+
+    // 只按返回的位置重新标记网格，应与导入后的网格完全一致
+    fn assert_grid_matches(cloud: &WordCloud, result: &Value) {
+        let mut expected = test_cloud();
+        let placed = crate::compare::parse_layout(&result["placed"].to_string());
+        for word in placed {
+            let word = expected.import_position(word);
+            let obb = expected.collision_obb(&word);
+            expected.mark_grid_as_occupied(
+                obb.cx,
+                obb.cy,
+                obb.half_width * 2.0,
+                obb.half_height * 2.0,
+                obb.rotation,
+            );
+        }
+        assert!(expected.occupied_grid_cells() > 0);
+        assert_eq!(cloud.grid, expected.grid);
+    }
+
+    fn position(result: &Value, text: &str) -> (f64, f64) {
+        let word = result["placed"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|word| word["text"] == text)
+            .unwrap();
+        (word["x"].as_f64().unwrap(), word["y"].as_f64().unwrap())
+    }
+
+    #[test]
+    fn accept_keeps_both_words_and_warns_about_the_overlap() {
+        let (cloud, result) = load("accept");
+        assert_eq!(result["rejected"], false);
+        assert_eq!(
+            result["conflicts"],
+            serde_json::json!([["first", "second"]])
+        );
+        assert_eq!(result["adjusted"], serde_json::json!([]));
+        assert_eq!(position(&result, "first"), (200.0, 150.0));
+        assert_eq!(position(&result, "second"), (215.0, 155.0));
+        assert_eq!(
+            cloud.warnings,
+            vec!["imported words \"first\" and \"second\" overlap".to_string()]
+        );
+        assert_grid_matches(&cloud, &result);
+    }
+
+    #[test]
+    fn nudge_moves_the_later_word_clear_and_reports_its_coordinates() {
+        let (cloud, result) = load("nudge");
+        assert_eq!(result["rejected"], false);
+        assert_eq!(result["conflicts"], serde_json::json!([]));
+        assert_eq!(result["unresolved"], serde_json::json!([]));
+        assert!(cloud.warnings.is_empty());
+        assert_eq!(position(&result, "first"), (200.0, 150.0));
+
+        let adjusted = result["adjusted"].as_array().unwrap();
+        assert_eq!(adjusted.len(), 1);
+        assert_eq!(adjusted[0]["key"], "second");
+        assert_eq!(
+            (
+                adjusted[0]["from_x"].as_f64(),
+                adjusted[0]["from_y"].as_f64()
+            ),
+            (Some(215.0), Some(155.0))
+        );
+        let moved = position(&result, "second");
+        assert_eq!(
+            (adjusted[0]["x"].as_f64(), adjusted[0]["y"].as_f64()),
+            (Some(moved.0), Some(moved.1))
+        );
+        assert_ne!(moved, (215.0, 155.0));
+        assert!(cloud.layout_conflicts(&cloud.placed).is_empty());
+        assert_grid_matches(&cloud, &result);
+    }
+
+    #[test]
+    fn reject_lists_the_pair_and_leaves_the_current_layout_untouched() {
+        let mut cloud = test_cloud();
+        cloud.generate_layout(crate::weighted_words(3));
+        let placed = cloud.emit_layout(&cloud.placed);
+        let grid = cloud.grid.clone();
+
+        cloud.set_import_conflict_policy("reject".to_string());
+        let result: Value =
+            serde_json::from_str(&cloud.load_layout(OVERLAPPING.to_string())).unwrap();
+        assert_eq!(result["rejected"], true);
+        assert_eq!(
+            result["conflicts"],
+            serde_json::json!([["first", "second"]])
+        );
+        assert_eq!(result["placed"], serde_json::json!([]));
+        assert_eq!(
+            serde_json::to_value(cloud.emit_layout(&cloud.placed)).unwrap(),
+            serde_json::to_value(placed).unwrap()
+        );
+        assert_eq!(cloud.grid, grid);
+    }
+}
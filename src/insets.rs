@@ -0,0 +1,46 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{LayoutEngine, WordCloud};
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置画布四边的安全区内边距（像素），单词不会放进内边距中，
+    // 用于避开刘海、圆角和手势区域
+    #[wasm_bindgen]
+    pub fn set_canvas_insets(&mut self, top: f64, right: f64, bottom: f64, left: f64) {
+        self.options.padding_top = top.max(0.0);
+        self.options.padding_right = right.max(0.0);
+        self.options.padding_bottom = bottom.max(0.0);
+        self.options.padding_left = left.max(0.0);
+    }
+
+    // 设置四边内边距后生成布局
+    #[wasm_bindgen]
+    pub fn generate_layout_with_canvas_padding_asymmetric(
+        &mut self,
+        words_json: String,
+        top: f64,
+        right: f64,
+        bottom: f64,
+        left: f64,
+    ) -> String {
+        self.set_canvas_insets(top, right, bottom, left);
+        self.generate_layout(words_json)
+    }
+}
+
+impl LayoutEngine {
+    // 可放置单词的安全区 (min_x, min_y, max_x, max_y)，内边距过大时退化为一条线或一个点
+    pub(crate) fn safe_bounds(&self) -> (f64, f64, f64, f64) {
+        let width = self.options.width as f64;
+        let height = self.options.height as f64;
+        let min_x = self.options.padding_left.min(width);
+        let min_y = self.options.padding_top.min(height);
+        (
+            min_x,
+            min_y,
+            (width - self.options.padding_right).max(min_x),
+            (height - self.options.padding_bottom).max(min_y),
+        )
+    }
+}
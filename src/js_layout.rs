@@ -0,0 +1,89 @@
+use wasm_bindgen::prelude::*;
+
+use crate::checked::{layout_error, PARSE_ERROR};
+use crate::{WordCloud, WordItem};
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 与 generate_layout 相同，但直接接收 JS 数组并返回位置对象数组，省去 JSON 序列化。
+    // 输入不是数组，或某一项缺少 text、text 为空、weight 不是数字等时返回带下标的错误；
+    // 输入为空、配置无效时的错误与 generate_layout_checked 相同
+    #[wasm_bindgen]
+    pub fn generate_layout_js(&mut self, words: JsValue) -> Result<JsValue, JsValue> {
+        let words = parse_words(words)?;
+        self.validate_layout_input(&words)?;
+        self.layout_words(words);
+
+        let positions = self.layout_positions();
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        serde::Serialize::serialize(&positions, &serializer)
+            .map_err(|e| JsError::new(&format!("failed to serialize layout: {}", e)).into())
+    }
+}
+
+// 逐项反序列化单词，出错时报告第一个无效项的下标
+fn parse_words(words: JsValue) -> Result<Vec<WordItem>, JsValue> {
+    if !js_sys::Array::is_array(&words) {
+        return Err(layout_error(PARSE_ERROR, "words must be an array"));
+    }
+
+    js_sys::Array::from(&words)
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let has_text = !js_sys::Reflect::get(&entry, &JsValue::from_str("text"))
+                .unwrap_or(JsValue::UNDEFINED)
+                .is_undefined();
+            let word: WordItem = serde_wasm_bindgen::from_value(entry).map_err(|e| {
+                layout_error(
+                    PARSE_ERROR,
+                    &format!("invalid word at index {}: {}", index, e),
+                )
+            })?;
+            match word.text_error(index, has_text) {
+                Some(error) => Err(layout_error(PARSE_ERROR, &error)),
+                None => Ok(word),
+            }
+        })
+        .collect()
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen_test::*;
+
+    use crate::test_cloud;
+
+    fn message(error: JsValue) -> String {
+        js_sys::Reflect::get(&error, &JsValue::from_str("message"))
+            .unwrap()
+            .as_string()
+            .unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn entries_without_text_are_rejected_like_the_json_api() {
+        let mut cloud = test_cloud();
+        for (json, expected) in [
+            (
+                r#"[{"text": "a", "weight": 1}, {"text": "", "weight": 2}]"#,
+                "empty `text` at index 1",
+            ),
+            (
+                r#"[{"text": "a", "weight": 1}, {"weight": 2}]"#,
+                "invalid word at index 1: missing field `text`",
+            ),
+        ] {
+            let words = js_sys::JSON::parse(json).unwrap();
+            let js_error = cloud.generate_layout_js(words).unwrap_err();
+            let json_error = cloud.generate_layout_checked(json.to_string()).unwrap_err();
+            assert_eq!(message(js_error), expected);
+            assert_eq!(message(json_error), expected);
+        }
+
+        let compound = r#"[{"parts": [{"text": "salt", "weight": 1}], "weight": 1}]"#;
+        let words = js_sys::JSON::parse(compound).unwrap();
+        assert!(cloud.generate_layout_js(words).is_ok());
+    }
+}
@@ -0,0 +1,295 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::env::log;
+use crate::geometry::Obb;
+use crate::{LayoutContext, LayoutEngine, WordCloud, WordItem, WordPosition};
+
+// 标签围绕锚点搜索时的螺旋步长（弧度）和半径增长速度（像素/弧度）
+const LABEL_SPIRAL_STEP: f64 = 0.1;
+const LABEL_SPIRAL_GROWTH: f64 = 0.5;
+
+// 散点图标签：锚点固定，标签在 max_offset 范围内移动
+#[derive(Deserialize)]
+struct LabelItem {
+    text: String,
+    #[serde(default)]
+    weight: f64,
+    anchor_x: f64,
+    anchor_y: f64,
+    max_offset: f64,
+    #[serde(default)]
+    color: Option<String>,
+}
+
+// 从锚点指向标签包围盒边缘的引线向量
+#[derive(Serialize)]
+struct Leader {
+    dx: f64,
+    dy: f64,
+}
+
+#[derive(Serialize)]
+struct PlacedLabel {
+    text: String,
+    x: f64,
+    y: f64,
+    size: f64,
+    width: f64,
+    height: f64,
+    anchor_x: f64,
+    anchor_y: f64,
+    offset: f64,
+    leader: Leader,
+}
+
+// 无法放置的标签；nearest_blocked_distance 为标签放在锚点时与最近的已放置标签的间距，
+// 为 0 表示直接重叠，为空表示只被画布边界或遮罩阻挡
+#[derive(Serialize)]
+struct UnplacedLabel {
+    text: String,
+    anchor_x: f64,
+    anchor_y: f64,
+    nearest_blocked_distance: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct LabelLayout {
+    labels: Vec<PlacedLabel>,
+    unplaced: Vec<UnplacedLabel>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 散点图标签布局：每个标签从自己的锚点向外螺旋搜索，但不超过 max_offset，
+    // 标签之间以及与遮罩外区域不重叠。权重高的标签优先放置
+    #[wasm_bindgen]
+    pub fn layout_labels(&mut self, items_json: String) -> String {
+        let mut items: Vec<LabelItem> = match serde_json::from_str(&items_json) {
+            Ok(items) => items,
+            Err(e) => {
+                log(&format!("解析标签JSON失败: {}", e));
+                return "{}".to_string();
+            }
+        };
+
+        // 锚点按配置的 y 轴方向给出，布局在内部坐标中进行
+        for item in &mut items {
+            item.anchor_y = self.flip_y(item.anchor_y);
+        }
+
+        self.reset_layout_state();
+
+        let max_weight = items
+            .iter()
+            .map(|i| i.weight)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let min_weight = items.iter().map(|i| i.weight).fold(f64::INFINITY, f64::min);
+        let (center_x, center_y) = self.layout_center();
+        let context = LayoutContext {
+            min_weight,
+            max_weight,
+            center_x,
+            center_y,
+            palette: self.effective_palette(),
+        };
+
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by(|&a, &b| items[b].weight.total_cmp(&items[a].weight));
+
+        let mut results: Vec<Option<Result<PlacedLabel, UnplacedLabel>>> =
+            (0..items.len()).map(|_| None).collect();
+        for index in order {
+            results[index] = Some(self.place_label(&items[index], &context));
+        }
+
+        let mut layout = LabelLayout {
+            labels: Vec::new(),
+            unplaced: Vec::new(),
+        };
+        for result in results.into_iter().flatten() {
+            match result {
+                Ok(label) => layout.labels.push(label),
+                Err(label) => layout.unplaced.push(label),
+            }
+        }
+        serde_json::to_string(&layout).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+impl LayoutEngine {
+    fn place_label(
+        &mut self,
+        item: &LabelItem,
+        context: &LayoutContext,
+    ) -> Result<PlacedLabel, UnplacedLabel> {
+        let word = WordItem {
+            text: item.text.clone(),
+            weight: item.weight,
+            ..WordItem::default()
+        };
+        let measured = self.measure_word(&word, context, 1.0);
+        let (width, height) = (measured.width, measured.height);
+
+        let (x, y) = match self.find_label_position(item, width, height) {
+            Some(position) => position,
+            None => {
+                self.record_skipped(&word, measured.size, "no_space");
+                let at_anchor = Obb::new(item.anchor_x, item.anchor_y, width, height, 0.0);
+                let nearest_blocked_distance = self
+                    .placed
+                    .iter()
+                    .map(|other| at_anchor.distance(&self.word_obb(other)))
+                    .reduce(f64::min);
+                return Err(UnplacedLabel {
+                    text: item.text.clone(),
+                    anchor_x: item.anchor_x,
+                    anchor_y: self.flip_y(item.anchor_y),
+                    nearest_blocked_distance,
+                });
+            }
+        };
+
+        self.mark_grid_as_occupied(x, y, width, height, 0.0);
+        self.placed.push(WordPosition {
+            text: item.text.clone(),
+            weight: item.weight,
+            x,
+            y,
+            rotate: 0.0,
+            color: item.color.clone(),
+            size: Some(measured.size),
+            ..WordPosition::default()
+        });
+        self.layout_stats.placed += 1;
+        self.touch_layout();
+
+        // 引线终点为标签包围盒上朝向锚点的边缘点，锚点在包围盒内时引线为零
+        let (dx, dy) = (x - item.anchor_x, y - item.anchor_y);
+        let offset = (dx * dx + dy * dy).sqrt();
+        let inside = Obb::new(x, y, width, height, 0.0).ray_exit(dx, dy);
+        let scale = if offset > inside {
+            (offset - inside) / offset
+        } else {
+            0.0
+        };
+
+        Ok(PlacedLabel {
+            text: item.text.clone(),
+            x: self.round_output(x),
+            y: self.round_output(self.flip_y(y)),
+            size: self.round_output(measured.size),
+            width: self.round_output(width),
+            height: self.round_output(height),
+            anchor_x: item.anchor_x,
+            anchor_y: self.flip_y(item.anchor_y),
+            offset: self.round_output(offset),
+            leader: Leader {
+                dx: self.round_output(dx * scale),
+                dy: self.round_output(self.flip_y(y + dy * scale) - self.flip_y(y)),
+            },
+        })
+    }
+
+    // 从锚点开始的阿基米德螺旋，半径超过 max_offset 即放弃
+    fn find_label_position(&self, item: &LabelItem, width: f64, height: f64) -> Option<(f64, f64)> {
+        let mut t: f64 = 0.0;
+        loop {
+            let radius = LABEL_SPIRAL_GROWTH * t;
+            if radius > item.max_offset.max(0.0) {
+                return None;
+            }
+            let x = item.anchor_x + radius * t.cos();
+            let y = item.anchor_y + radius * t.sin();
+            if !self.check_collision(x, y, width, height, 0.0) {
+                return Some((x, y));
+            }
+            t += LABEL_SPIRAL_STEP;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::geometry::Obb;
+    use crate::test_cloud;
+
+    fn run(items: Value) -> Value {
+        let mut cloud = test_cloud();
+        serde_json::from_str(&cloud.layout_labels(items.to_string())).unwrap()
+    }
+
+    fn number(value: &Value, field: &str) -> f64 {
+        value[field].as_f64().unwrap()
+    }
+
+    #[test]
+    fn overlapping_anchors_get_separate_labels_within_max_offset() {
+        let items: Vec<Value> = (0..5)
+            .map(|i| {
+                serde_json::json!({
+                    "text": format!("point{}", i),
+                    "weight": 5 - i,
+                    "anchor_x": 200.0 + i as f64,
+                    "anchor_y": 150.0,
+                    "max_offset": 80.0
+                })
+            })
+            .collect();
+        let layout = run(Value::Array(items));
+        let labels = layout["labels"].as_array().unwrap();
+        assert_eq!(labels.len(), 5);
+        assert!(layout["unplaced"].as_array().unwrap().is_empty());
+
+        let boxes: Vec<Obb> = labels
+            .iter()
+            .map(|label| {
+                let (x, y) = (number(label, "x"), number(label, "y"));
+                Obb::new(x, y, number(label, "width"), number(label, "height"), 0.0)
+            })
+            .collect();
+        for (i, a) in boxes.iter().enumerate() {
+            for b in &boxes[i + 1..] {
+                assert!(!a.intersects(b));
+            }
+        }
+
+        for (label, obb) in labels.iter().zip(&boxes) {
+            let (anchor_x, anchor_y) = (number(label, "anchor_x"), number(label, "anchor_y"));
+            let (dx, dy) = (number(label, "x") - anchor_x, number(label, "y") - anchor_y);
+            let offset = number(label, "offset");
+            assert!(offset <= 80.0);
+            assert!((offset - dx.hypot(dy)).abs() < 1e-9);
+
+            // 引线终点落在标签包围盒边缘上；锚点在包围盒内时引线为零
+            let (leader_x, leader_y) = (
+                number(&label["leader"], "dx"),
+                number(&label["leader"], "dy"),
+            );
+            if obb.contains(anchor_x, anchor_y) {
+                assert_eq!((leader_x, leader_y), (0.0, 0.0));
+                continue;
+            }
+            let (end_x, end_y) = (anchor_x + leader_x, anchor_y + leader_y);
+            let (ux, uy) = (dx / offset, dy / offset);
+            assert!(obb.contains(end_x + ux * 1e-6, end_y + uy * 1e-6));
+            assert!(!obb.contains(end_x - ux * 1e-6, end_y - uy * 1e-6));
+        }
+        // 至少有一个标签被推离锚点，引线不为零
+        assert!(labels.iter().any(|label| number(label, "offset") > 10.0));
+    }
+
+    #[test]
+    fn labels_without_room_report_the_nearest_blocked_distance() {
+        let layout = run(serde_json::json!([
+            {"text": "first", "weight": 2, "anchor_x": 200, "anchor_y": 150, "max_offset": 0},
+            {"text": "second", "weight": 1, "anchor_x": 200, "anchor_y": 150, "max_offset": 0}
+        ]));
+        assert_eq!(layout["labels"].as_array().unwrap().len(), 1);
+        let unplaced = &layout["unplaced"][0];
+        assert_eq!(unplaced["text"], "second");
+        assert_eq!(unplaced["nearest_blocked_distance"], 0.0);
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::env::log;
+use crate::render::escape_xml;
+use crate::{LayoutEngine, WordCloud};
+
+// 类别图例色块的边长、间距和标签字号
+const SWATCH_SIZE: f64 = 14.0;
+const SWATCH_GAP: f64 = 4.0;
+const LEGEND_LABEL_SIZE: f64 = 12.0;
+// 标签文字宽度的估算系数（相对字号）
+const LABEL_CHAR_WIDTH: f64 = 0.6;
+
+// 图例中的一个类别：实际输出的颜色（按首次出现的顺序）、单词数量和权重之和
+#[derive(Serialize)]
+struct LegendEntry {
+    category: String,
+    colors: Vec<String>,
+    count: u32,
+    weight: f64,
+}
+
+#[derive(Serialize)]
+struct CategoryLegend {
+    entries: Vec<LegendEntry>,
+    svg: String,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置各类别的调色板，格式为 {"类别": ["#颜色", ...]}；
+    // 带类别的单词在所属类别的调色板中依次取色，没有对应调色板时使用全局调色板
+    #[wasm_bindgen]
+    pub fn set_category_palettes(&mut self, palettes_json: String) -> bool {
+        match serde_json::from_str::<BTreeMap<String, Vec<String>>>(&palettes_json) {
+            Ok(palettes) => {
+                self.options.category_palettes = palettes;
+                true
+            }
+            Err(e) => {
+                log(&format!("解析类别调色板JSON失败: {}", e));
+                false
+            }
+        }
+    }
+
+    // 清除类别调色板
+    #[wasm_bindgen]
+    pub fn clear_category_palettes(&mut self) {
+        self.options.category_palettes.clear();
+    }
+
+    // 导出当前布局的类别图例：颜色取自最终输出的单词，而不是配置的调色板，
+    // 类别按权重之和从大到小排列，同时给出 JSON 条目和 SVG 色块条
+    #[wasm_bindgen]
+    pub fn export_legend(&self) -> String {
+        let mut entries: Vec<LegendEntry> = Vec::new();
+        for word in &self.placed {
+            let category = match &word.category {
+                Some(category) => category,
+                None => continue,
+            };
+            let index = match entries.iter().position(|e| &e.category == category) {
+                Some(index) => index,
+                None => {
+                    entries.push(LegendEntry {
+                        category: category.clone(),
+                        colors: Vec::new(),
+                        count: 0,
+                        weight: 0.0,
+                    });
+                    entries.len() - 1
+                }
+            };
+            let entry = &mut entries[index];
+            entry.count += 1;
+            entry.weight += word.weight;
+            if let Some(color) = &word.color {
+                if !entry.colors.contains(color) {
+                    entry.colors.push(color.clone());
+                }
+            }
+        }
+        // 稳定排序，权重相同的类别保持首次出现的顺序
+        entries.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+
+        let legend = CategoryLegend {
+            svg: self.svg_category_legend(&entries),
+            entries,
+        };
+        serde_json::to_string(&legend).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+impl LayoutEngine {
+    // 每个类别一行：颜色色块后跟 "类别 (数量)"
+    fn svg_category_legend(&self, entries: &[LegendEntry]) -> String {
+        let row_height = SWATCH_SIZE + SWATCH_GAP;
+        let mut width: f64 = 0.0;
+        let mut rows = String::new();
+        for (row, entry) in entries.iter().enumerate() {
+            let y = SWATCH_GAP + row as f64 * row_height;
+            let mut x = SWATCH_GAP;
+            for color in &entry.colors {
+                rows.push_str(&format!(
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                    x,
+                    y,
+                    SWATCH_SIZE,
+                    SWATCH_SIZE,
+                    escape_xml(color)
+                ));
+                x += SWATCH_SIZE + SWATCH_GAP;
+            }
+            let label = format!("{} ({})", entry.category, entry.count);
+            rows.push_str(&format!(
+                r#"<text x="{}" y="{}" font-family="{}" font-size="{}" dominant-baseline="middle">{}</text>"#,
+                x,
+                y + SWATCH_SIZE / 2.0,
+                escape_xml(&self.options.font_family),
+                LEGEND_LABEL_SIZE,
+                escape_xml(&label)
+            ));
+            let label_width = label.chars().count() as f64 * LEGEND_LABEL_SIZE * LABEL_CHAR_WIDTH;
+            width = width.max(x + label_width + SWATCH_GAP);
+        }
+        let height = SWATCH_GAP + entries.len() as f64 * row_height;
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">{}</svg>"#,
+            width, height, width, height, rows
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::{contrast_ratio, Rgb};
+    use crate::test_cloud;
+
+    const WORDS: &str = r#"[
+        {"text": "apple", "weight": 3, "category": "fruit"},
+        {"text": "carrot", "weight": 8, "category": "veg"},
+        {"text": "pear", "weight": 2, "category": "fruit"},
+        {"text": "leek", "weight": 4, "category": "veg"},
+        {"text": "plain", "weight": 1}
+    ]"#;
+
+    #[test]
+    fn legend_shows_adjusted_colors_ordered_by_weight() {
+        let mut cloud = test_cloud();
+        cloud.set_background_color(Some("#ffffff".to_string()));
+        cloud.set_palette_contrast_adjustment(true);
+        // 浅黄色与白色背景的对比度远低于默认阈值 3，会被调整
+        assert!(cloud
+            .set_category_palettes(r##"{"fruit": ["#ffff66"], "veg": ["#1f77b4"]}"##.to_string()));
+        cloud.generate_layout(WORDS.to_string());
+        assert_eq!(cloud.placed.len(), 5);
+
+        let legend: serde_json::Value = serde_json::from_str(&cloud.export_legend()).unwrap();
+        let entries = legend["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["category"], "veg");
+        assert_eq!(entries[0]["colors"], serde_json::json!(["#1f77b4"]));
+        assert_eq!(
+            (entries[0]["count"].as_u64(), entries[0]["weight"].as_f64()),
+            (Some(2), Some(12.0))
+        );
+        assert_eq!(entries[1]["category"], "fruit");
+        assert_eq!(
+            (entries[1]["count"].as_u64(), entries[1]["weight"].as_f64()),
+            (Some(2), Some(5.0))
+        );
+
+        // 图例给出的是实际输出的调整后颜色
+        let adjusted = entries[1]["colors"][0].as_str().unwrap();
+        assert_ne!(adjusted, "#ffff66");
+        let white = Rgb::parse("#ffffff").unwrap();
+        assert!(contrast_ratio(Rgb::parse(adjusted).unwrap(), white) >= 3.0);
+        for word in cloud
+            .placed
+            .iter()
+            .filter(|w| w.category.as_deref() == Some("fruit"))
+        {
+            assert_eq!(word.color.as_deref(), Some(adjusted));
+        }
+        let svg = legend["svg"].as_str().unwrap();
+        assert!(svg.contains(&format!(r#"fill="{}""#, adjusted)));
+        assert!(!svg.contains("#ffff66"));
+        assert!(svg.find("veg (2)").unwrap() < svg.find("fruit (2)").unwrap());
+    }
+}
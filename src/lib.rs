@@ -1,23 +1,215 @@
-use js_sys::{Array, Math, Object, Reflect};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
-use web_sys::{CanvasRenderingContext2d, TextMetrics, console};
+
+mod accessibility;
+mod animation;
+mod autosize;
+mod bundling;
+mod calibration;
+mod capacity;
+mod checked;
+mod clipboard;
+mod color;
+mod compare;
+mod compound;
+mod constraints;
+mod css;
+mod decay;
+mod demotion;
+mod edges;
+mod env;
+mod events;
+mod eviction;
+mod explode;
+mod footprint;
+mod geometry;
+mod grid;
+mod host;
+mod hyphenate;
+mod import;
+mod insets;
+mod js_layout;
+mod labels;
+mod legend;
+mod mask;
+mod metrics;
+mod orientation;
+mod output;
+mod paging;
+mod paper;
+mod phonetic;
+mod pipeline;
+mod print;
+mod priority;
+mod regression;
+mod render;
+mod rng;
+mod sections;
+mod single;
+mod skip;
+mod sparkline;
+mod spatial;
+mod sprite;
+mod stats;
+mod stem;
+mod stepped;
+mod symmetry;
+mod tags;
+mod theme;
+mod thumbnail;
+mod trial;
+mod voronoi;
+mod watermark;
+pub mod wordcloud_core;
+
+use calibration::{GlyphTable, DEFAULT_KERNING_PAIRS};
+use capacity::PackingHistory;
+use color::ensure_contrast;
+use compound::{CompoundPart, PartPosition};
+use constraints::SeparationRule;
+use decay::{Decay, TimeDecay};
+use demotion::DemotedWord;
+use env::{log, now_ms, Environment};
+use footprint::{Footprint, FootprintCache, Inflation};
+use geometry::Obb;
+use grid::grid_layout;
+use host::{Host, JsHost, NoHost};
+use hyphenate::hyphen_lines;
+use mask::Mask;
+use rng::RngStream;
+use sections::Section;
+use sparkline::Sparkline;
+use spatial::{CenterIndex, SpatialIndex};
+use sprite::Sprite;
+use stats::{LayoutStats, PlacementStats, SkippedWord};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+use stepped::PendingLayout;
+use web_sys::HtmlImageElement;
 
 // 词条数据结构
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct WordItem {
+    // 组合词可以省略 text 和 weight，由 parts 推导
+    #[serde(default)]
     text: String,
+    #[serde(default)]
     weight: f64,
     // 可选字段
     #[serde(skip_serializing_if = "Option::is_none")]
     color: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     rotate: Option<f64>,
+    // 单词的唯一标识，缺省时使用 text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+    // 组合词的各部分及分隔符，例如 "salt & pepper"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parts: Option<Vec<CompoundPart>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    separator: Option<String>,
+    // SVG 中以箭头标注的说明文字，方向如 "top-right"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    annotation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    annotation_direction: Option<String>,
+    // SVG 渐变填充的角度（度），从单词颜色渐变到其浅色
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gradient_angle: Option<f64>,
+    // 权重的置信区间，SVG 中以半透明色带显示对应字号的包围盒范围
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weight_ci_low: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weight_ci_high: Option<f64>,
+    // 单词所属的类别，用于按类别划分背景区域
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    // 布局时对字号的额外缩放（例如超大单词被缩小）
+    #[serde(skip)]
+    size_factor: Option<f64>,
+    // 碰撞包围盒的额外扩展，由 footprint 回调给出
+    #[serde(skip)]
+    inflation: Option<Inflation>,
+    // 合并重复单词时被舍弃的其他写法
+    #[serde(skip)]
+    variants: Option<Vec<String>>,
+    // 单词的标签，用于按标签筛选显示的单词
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+    // 断字位置（字符下标），单词放不下时由断字重试设置
+    #[serde(skip)]
+    hyphen_at: Option<usize>,
+    // 单词发音的音频地址
+    #[serde(default)]
+    audio_url: Option<String>,
+    // 注音（如假名读音或拼音），以较小字号显示在单词上方
+    #[serde(default)]
+    phonetic: Option<String>,
+    // 时间序列数据，以迷你趋势线显示在单词右侧
+    #[serde(default)]
+    trend: Option<Vec<f64>>,
+    // 屏幕阅读器读出的标签，缺省时由文字和权重生成
+    #[serde(default)]
+    aria_label: Option<String>,
+    // 单词出现的时间（毫秒时间戳），用于时间衰减
+    #[serde(default)]
+    timestamp: Option<f64>,
+    // 按 timestamp 计算出的年龄和衰减系数
+    #[serde(skip)]
+    decay: Option<Decay>,
+}
+
+impl WordItem {
+    pub(crate) fn key(&self) -> &str {
+        self.key.as_deref().unwrap_or(&self.text)
+    }
+
+    // 计算字号使用的权重：有置信区间时取区间中点
+    pub(crate) fn sizing_weight(&self) -> f64 {
+        match (self.weight_ci_low, self.weight_ci_high) {
+            (Some(low), Some(high)) => (low + high) / 2.0,
+            _ => self.weight,
+        }
+    }
+
+    // 只有组合词可以省略 text：非组合词缺少 text 或 text 为空时返回错误说明，
+    // has_text 表示输入中是否给出了 text 字段。JSON 和 JsValue 两种输入共用
+    pub(crate) fn text_error(&self, index: usize, has_text: bool) -> Option<String> {
+        if !self.text.is_empty() || self.parts.is_some() {
+            return None;
+        }
+        Some(if has_text {
+            format!("empty `text` at index {}", index)
+        } else {
+            format!("invalid word at index {}: missing field `text`", index)
+        })
+    }
+}
+
+// 逐项解析 JSON 单词数组，出错时报告第一个无效项的下标
+pub(crate) fn parse_words_json(words_json: &str) -> Result<Vec<WordItem>, String> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(words_json)
+        .map_err(|e| format!("failed to parse words JSON: {}", e))?;
+
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let has_text = entry.get("text").is_some();
+            let word: WordItem = serde_json::from_value(entry)
+                .map_err(|e| format!("invalid word at index {}: {}", index, e))?;
+            match word.text_error(index, has_text) {
+                Some(error) => Err(error),
+                None => Ok(word),
+            }
+        })
+        .collect()
 }
 
 // 位置数据结构
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct WordPosition {
     text: String,
     weight: f64,
@@ -28,10 +220,111 @@ pub struct WordPosition {
     color: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     size: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+    // 组合词各部分相对锚点的偏移
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parts: Option<Vec<PartPosition>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    annotation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    annotation_direction: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gradient_angle: Option<f64>,
+    // 置信区间上下限对应的字号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ci_low_size: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ci_high_size: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    // 对称布局中镜像配对单词的 key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mirror: Option<String>,
+    // 参与碰撞检测的扩展包围盒，仅在 footprint 回调给出扩展时存在
+    #[serde(skip_serializing_if = "Option::is_none")]
+    footprint: Option<Footprint>,
+    // 合并重复单词时被舍弃的其他写法
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variants: Option<Vec<String>>,
+    // 动画开始时间和持续时间（毫秒），仅在带动画信息的布局中存在
+    #[serde(skip_serializing_if = "Option::is_none")]
+    animation_start_time: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    animation_duration: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+    // 断字为两行的单词及各行文本（第一行末尾带连字符）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hyphenated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lines: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audio_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phonetic: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trend: Option<Vec<f64>>,
+    // 趋势线的位置和折线，仅在带趋势线信息的布局中存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sparkline: Option<Sparkline>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    aria_label: Option<String>,
+    // 时间衰减：单词的年龄（毫秒）、衰减系数和对应的不透明度
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    age_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    decay: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    opacity: Option<f64>,
+    // 放置时测得的包围盒（宽、高），之后重新计算包围盒时优先使用，
+    // 避免测量方式改变（如 generate_layout_with_ctx 结束）后结果不一致
+    #[serde(skip)]
+    measured_box: Option<(f64, f64)>,
+}
+
+impl WordPosition {
+    pub(crate) fn key(&self) -> &str {
+        self.key.as_deref().unwrap_or(&self.text)
+    }
+}
+
+// 合并布局的结果
+#[derive(Serialize)]
+struct MergeResult<'a> {
+    placed: &'a [WordPosition],
+    rejected: &'a [WordPosition],
+}
+
+// 带跳过单词的布局结果
+#[derive(Serialize)]
+struct DetailedLayout<'a> {
+    placed: &'a [WordPosition],
+    skipped: &'a [SkippedWord],
+}
+
+// 布局过程中的上下文：权重范围与螺旋中心
+#[derive(Clone)]
+pub(crate) struct LayoutContext {
+    min_weight: f64,
+    max_weight: f64,
+    center_x: f64,
+    center_y: f64,
+    // 本次布局使用的调色板顺序
+    palette: Vec<String>,
+}
+
+// 单词的测量结果
+#[derive(Clone)]
+struct MeasuredWord {
+    size: f64,
+    width: f64,
+    height: f64,
+    parts: Option<Vec<PartPosition>>,
 }
 
 // 配置选项
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CloudOptions {
     width: u32,
     height: u32,
@@ -43,220 +336,2039 @@ pub struct CloudOptions {
     rotation_range: f64,
     #[serde(default = "default_spiral")]
     spiral: String,
+    // 背景图片地址，导出时作为第一层绘制
+    #[serde(default)]
+    background_image_url: Option<String>,
+    // 背景填充色，位于背景图片之下
+    #[serde(default)]
+    background_color: Option<String>,
+    // 平铺在背景上的水印文字及其不透明度，不参与布局
+    #[serde(default)]
+    watermark_text: Option<String>,
+    #[serde(default = "default_watermark_opacity")]
+    watermark_opacity: f64,
+    // 方格纸背景及方格边长（像素），只在导出时绘制
+    #[serde(default)]
+    grid_background: bool,
+    #[serde(default = "default_grid_background_size_px")]
+    grid_background_size_px: u32,
+    // SVG 的整体描述，输出为 <desc> 元素
+    #[serde(default)]
+    alt_text: Option<String>,
+    // 按单词时间戳的衰减设置，None 表示不衰减
+    #[serde(default)]
+    time_decay: Option<TimeDecay>,
+    // 径向字号衰减系数，0 表示关闭
+    #[serde(default)]
+    radial_size_falloff: f64,
+    // 字间距（像素），对应 CSS letter-spacing
+    #[serde(default)]
+    letter_spacing: f64,
+    // 可读的最小字号，低于该值时给出警告
+    #[serde(default = "default_min_legible_size")]
+    min_legible_size: f64,
+    // 严格模式下，警告会变为错误并中止布局
+    #[serde(default)]
+    strict_mode: bool,
+    // 亚像素定位：找到网格位置后，再按精确包围盒向中心微调
+    #[serde(default)]
+    subpixel_positioning: bool,
+    // 网格对齐：单词中心对齐到 snap_grid_size 的整数倍，优先于亚像素定位
+    #[serde(default)]
+    snap_to_grid: bool,
+    #[serde(default = "default_snap_grid_size")]
+    snap_grid_size: u32,
+    // 碰撞网格每个单元的像素大小，越大越快但越不精确，范围 [1, 32]
+    #[serde(default = "default_grid_size")]
+    grid_size: usize,
+    // 画布四边的安全区内边距（像素）
+    #[serde(default)]
+    padding_top: f64,
+    #[serde(default)]
+    padding_right: f64,
+    #[serde(default)]
+    padding_bottom: f64,
+    #[serde(default)]
+    padding_left: f64,
+    // 最小显示字号，计算出的字号低于它的单词不放置而是列入降级列表，0 表示关闭
+    #[serde(default)]
+    min_render_size: f64,
+    // 降级单词的汇总词模板（{count} 为数量）及其字号
+    #[serde(default)]
+    demoted_summary_template: Option<String>,
+    #[serde(default = "default_demoted_summary_size")]
+    demoted_summary_size: f64,
+    // 碰撞检测的旋转吸附容差（度），0 表示不吸附
+    #[serde(default)]
+    rotation_snap: f64,
+    // 分步布局的让出策略："count" 或 "deadline"
+    #[serde(default = "default_yield_strategy")]
+    yield_strategy: String,
+    // count 模式下每一步放置的单词数量
+    #[serde(default = "default_yield_every")]
+    yield_every: u32,
+    // deadline 模式下的剩余时间阈值（毫秒）
+    #[serde(default = "default_yield_threshold_ms")]
+    yield_threshold_ms: f64,
+    // 调色板，用于未指定颜色的单词
+    #[serde(default)]
+    palette: Vec<String>,
+    // 调色板模式："cycle"、"cvd_safe" 或 "random"
+    #[serde(default = "default_palette_mode")]
+    palette_mode: String,
+    // 按类别设置的调色板：带类别的单词在所属类别的调色板中依次取色
+    #[serde(default)]
+    category_palettes: BTreeMap<String, Vec<String>>,
+    // 调色板校验：与背景的最小对比度
+    #[serde(default = "default_palette_min_contrast")]
+    palette_min_contrast: f64,
+    // 调色板校验：颜色间（含色觉缺陷模拟）的最小 CIE76 色差
+    #[serde(default = "default_palette_min_distance")]
+    palette_min_distance: f64,
+    // 是否调整调色板中与背景色对比度不足的颜色，使其达到 palette_min_contrast
+    #[serde(default)]
+    adjust_palette_contrast: bool,
+    // 响应式字号：按 width / reference_width 缩放字号
+    #[serde(default)]
+    responsive_font_scaling: bool,
+    // 字号所参照的画布宽度，默认为初始宽度
+    #[serde(default)]
+    reference_width: u32,
+    // 超出可用区域的单词的处理方式："shrink"、"skip" 或 "error"
+    #[serde(default = "default_oversized_policy")]
+    oversized_policy: String,
+    // 镜像螺旋的水平方向，适用于从右向左书写的文字
+    #[serde(default)]
+    rtl_spiral_bias: bool,
+    // 文字书写方向："ltr" 或 "rtl"
+    #[serde(default = "default_text_direction")]
+    text_direction: String,
+    // 对称布局："none"、"vertical"（左右镜像）或 "horizontal"（上下镜像）
+    #[serde(default = "default_symmetry")]
+    symmetry: String,
+    // 相同颜色的单词之间的最小间距（像素），用于分散同一类别的单词
+    #[serde(default)]
+    min_same_color_distance: Option<f64>,
+    // 旋转角度单位："radians" 或 "degrees"
+    #[serde(default = "default_rotation_unit")]
+    rotation_unit: String,
+    // 输出数值保留的小数位数，None 表示不舍入
+    #[serde(default)]
+    output_precision: Option<u32>,
+    // 随机种子，None 表示每次布局随机
+    #[serde(default)]
+    seed: Option<u64>,
+    // generate_svg 是否附带参考线，以及参考线间距（像素）
+    #[serde(default)]
+    include_guides: bool,
+    #[serde(default = "default_guide_every_px")]
+    guide_every_px: u32,
+    // 在 SVG 底部附加字号图例，说明字号与权重的对应关系
+    #[serde(default)]
+    include_legend: bool,
+    // 在单词下方绘制占用密度热力图，颜色在冷色和暖色之间插值
+    #[serde(default)]
+    heatmap_overlay: bool,
+    #[serde(default = "default_heatmap_cold_color")]
+    heatmap_cold_color: String,
+    #[serde(default = "default_heatmap_hot_color")]
+    heatmap_hot_color: String,
+    // 镂空模式：只描边不填充，描边宽度 = 字号 * stroke_width_ratio
+    #[serde(default)]
+    outline_only: bool,
+    #[serde(default = "default_stroke_width_ratio")]
+    stroke_width_ratio: f64,
+    // 频次环：对每个 n，在第 n 个放置的单词中心所在的半径处绘制同心圆
+    #[serde(default)]
+    frequency_rings: Option<Vec<u32>>,
+    // 回归报告中“轻微偏移”与“移动”的分界距离（像素）
+    #[serde(default = "default_regression_minor_threshold")]
+    regression_minor_threshold: f64,
+    // 缩略图中保留的最小方块高度（缩放后像素），以及是否把更小的方块合并为密度块
+    #[serde(default = "default_thumbnail_min_height")]
+    thumbnail_min_height: f64,
+    #[serde(default)]
+    thumbnail_merge_small: bool,
+    // 导出连线时按连通分组捆绑成贝塞尔曲线束
+    #[serde(default)]
+    bundle_connections: bool,
+    // 用二维 Halton 序列（基数 2 和 3）均匀采样整个画布，代替螺旋搜索
+    #[serde(default)]
+    equalize_spiral_density: bool,
+    // 布局前忽略大小写合并重复的单词
+    #[serde(default)]
+    deduplication_case_insensitive: bool,
+    // 合并重复单词时选取显示文本的方式：
+    // "first"、"most_frequent"、"title" 或 "preserve_max_weight"
+    #[serde(default = "default_casing_strategy")]
+    casing_strategy: String,
+    // 是否在输出中用 variants 字段列出被舍弃的写法
+    #[serde(default)]
+    emit_casing_variants: bool,
+    // 画布饱和后的跳过策略："tail" 依次尝试剩余单词（通常丢掉整个低权重段），
+    // "proportional" 以饱和前放下的单词数估计容量，重新按归一化权重随机接纳全部单词，
+    // 使被跳过的单词分布在各个权重段
+    #[serde(default = "default_skip_policy")]
+    skip_policy: String,
+    // 连续放置失败多少个单词后认为画布已饱和
+    #[serde(default = "default_full_threshold")]
+    full_threshold: u32,
+    // 字号按哪些单词的权重范围计算："survivors" 只看过滤后剩下的单词，
+    // "all_input" 看过滤前的全部输入，被过滤掉的单词仍会影响字号
+    #[serde(default = "default_scale_over")]
+    scale_over: String,
+    // load_layout 遇到互相重叠的单词时的处理方式："accept"、"nudge" 或 "reject"
+    #[serde(default = "default_import_conflict_policy")]
+    import_conflict_policy: String,
+    // 碰撞模式："box" 按旋转包围盒占用网格，"pixel" 按栅格化后的字形像素占用网格
+    #[serde(default = "default_collision_mode")]
+    collision_mode: String,
+    // 按螺旋中心附近空闲区域的形状决定先尝试横排还是竖排
+    #[serde(default)]
+    smart_rotation: bool,
+    // 字体校准时额外测量的字距调整对
+    #[serde(default = "default_kerning_pairs")]
+    kerning_pairs: Vec<String>,
+    // 单词动画模式："sequential" 按放置顺序依次开始，"parallel" 同时开始
+    #[serde(default = "default_animation_mode")]
+    animation_mode: String,
+    // 依次开始时相邻单词的间隔（毫秒）
+    #[serde(default = "default_animation_stagger_ms")]
+    animation_stagger_ms: f64,
+    // 动画持续时间的采样范围（毫秒）
+    #[serde(default = "default_animation_duration_min_ms")]
+    animation_duration_min_ms: f64,
+    #[serde(default = "default_animation_duration_max_ms")]
+    animation_duration_max_ms: f64,
+    // y 轴方向："down" 或 "up"，作用于输入输出的坐标和旋转角度
+    #[serde(default = "default_y_axis")]
+    y_axis: String,
+    // 交互式 SVG：单词悬停时放大，<text> 附带 data-size
+    #[serde(default)]
+    interactive: bool,
+    // 打印尺寸（毫米）：设置后 SVG 以毫米为单位给出宽高，<text> 附带 data-pt-size
+    #[serde(default)]
+    physical_width_mm: Option<f64>,
+    #[serde(default)]
+    physical_height_mm: Option<f64>,
+    // 打印时单词的最小可读高度（毫米），低于该值时 print_info 给出警告
+    #[serde(default = "default_min_print_height_mm")]
+    min_print_height_mm: f64,
+    // 当前显示的标签，非空时只布局和输出带有其中至少一个标签的单词
+    #[serde(default)]
+    visible_tags: Vec<String>,
+    // 单词中心之间的最小距离（像素）
+    #[serde(default)]
+    min_word_center_distance: Option<f64>,
+    // 从文本统计词频时的词干提取："none"、"plural"（只还原复数和所有格）或 "porter"
+    #[serde(default = "default_stem")]
+    stem: String,
+    // 单词放不下时是否尝试断字为两行
+    #[serde(default)]
+    hyphenate: bool,
+    // 螺旋中心周围不放置单词的半径（像素）
+    #[serde(default)]
+    center_exclusion_radius: Option<f64>,
+    // SVG 中带发音的单词可点击播放，并附带播放脚本
+    #[serde(default)]
+    enable_audio_support: bool,
+    // 断字例外：小写单词 -> 用连字符标出断字位置的写法
+    #[serde(default)]
+    hyphenation_exceptions: BTreeMap<String, String>,
+}
+
+fn default_rotation_range() -> f64 {
+    0.0
+}
+
+fn default_spiral() -> String {
+    "archimedean".to_string()
+}
+
+fn default_min_legible_size() -> f64 {
+    4.0
+}
+
+fn default_yield_strategy() -> String {
+    "count".to_string()
+}
+
+fn default_yield_every() -> u32 {
+    50
+}
+
+fn default_yield_threshold_ms() -> f64 {
+    1.0
+}
+
+fn default_oversized_policy() -> String {
+    "shrink".to_string()
+}
+
+fn default_stroke_width_ratio() -> f64 {
+    0.03
+}
+
+fn default_casing_strategy() -> String {
+    "preserve_max_weight".to_string()
+}
+
+fn default_skip_policy() -> String {
+    "tail".to_string()
+}
+
+fn default_watermark_opacity() -> f64 {
+    0.05
+}
+
+fn default_kerning_pairs() -> Vec<String> {
+    DEFAULT_KERNING_PAIRS
+        .iter()
+        .map(|pair| pair.to_string())
+        .collect()
+}
+
+fn default_import_conflict_policy() -> String {
+    "accept".to_string()
+}
+
+fn default_grid_background_size_px() -> u32 {
+    20
+}
+
+fn default_collision_mode() -> String {
+    "box".to_string()
+}
+
+fn default_scale_over() -> String {
+    "survivors".to_string()
+}
+
+fn default_full_threshold() -> u32 {
+    3
+}
+
+fn default_animation_mode() -> String {
+    "sequential".to_string()
+}
+
+fn default_animation_stagger_ms() -> f64 {
+    50.0
+}
+
+fn default_animation_duration_min_ms() -> f64 {
+    300.0
+}
+
+fn default_animation_duration_max_ms() -> f64 {
+    600.0
+}
+
+fn default_min_print_height_mm() -> f64 {
+    2.0
+}
+
+fn default_stem() -> String {
+    "none".to_string()
+}
+
+fn default_y_axis() -> String {
+    "down".to_string()
+}
+
+fn default_demoted_summary_size() -> f64 {
+    12.0
+}
+
+fn default_grid_size() -> usize {
+    DEFAULT_GRID_SIZE
+}
+
+fn default_snap_grid_size() -> u32 {
+    20
+}
+
+fn default_regression_minor_threshold() -> f64 {
+    2.0
+}
+
+fn default_thumbnail_min_height() -> f64 {
+    2.0
+}
+
+fn default_heatmap_cold_color() -> String {
+    "#2c7bb6".to_string()
+}
+
+fn default_heatmap_hot_color() -> String {
+    "#d7191c".to_string()
+}
+
+fn default_guide_every_px() -> u32 {
+    50
+}
+
+fn default_symmetry() -> String {
+    "none".to_string()
+}
+
+fn default_rotation_unit() -> String {
+    "radians".to_string()
+}
+
+fn default_text_direction() -> String {
+    "ltr".to_string()
+}
+
+fn default_palette_mode() -> String {
+    "cycle".to_string()
+}
+
+fn default_palette_min_contrast() -> f64 {
+    3.0
+}
+
+fn default_palette_min_distance() -> f64 {
+    10.0
+}
+
+// 单词权重的 (最小, 最大) 范围，组合词按各部分的权重计算；没有单词时为 (inf, -inf)
+pub(crate) fn weight_range(words: &[WordItem]) -> (f64, f64) {
+    words
+        .iter()
+        .flat_map(|w| w.sizing_weights())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), w| {
+            (lo.min(w), hi.max(w))
+        })
+}
+
+// 忽略大小写合并 key 相同的单词：权重累加，位置取第一次出现处，
+// 显示文本由 strategy 决定：
+// - first：第一次出现的写法
+// - most_frequent：出现次数最多的写法，次数相同时取先出现的
+// - title：第一次出现的写法按单词首字母大写
+// - preserve_max_weight：权重最高的那一项的写法
+// 除 first 和 title 外，其他属性随显示文本取自同一项
+fn dedupe_case_insensitive(
+    words: Vec<WordItem>,
+    strategy: &str,
+    keep_variants: bool,
+) -> Vec<WordItem> {
+    let mut groups: Vec<Vec<WordItem>> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for word in words {
+        let key = word.key().to_lowercase();
+        match index.get(&key) {
+            Some(&i) => groups[i].push(word),
+            None => {
+                index.insert(key, groups.len());
+                groups.push(vec![word]);
+            }
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|group| {
+            let total: f64 = group.iter().map(|word| word.weight).sum();
+            let chosen = match strategy {
+                "first" | "title" => 0,
+                "most_frequent" => {
+                    let count = |text: &str| group.iter().filter(|w| w.text == text).count();
+                    // 从后往前取最大值，使次数相同时先出现的写法胜出
+                    (0..group.len())
+                        .rev()
+                        .max_by_key(|&i| count(&group[i].text))
+                        .unwrap_or(0)
+                }
+                other => {
+                    if other != "preserve_max_weight" {
+                        log(&format!("未知的大小写策略: {}，使用权重最高的写法", other));
+                    }
+                    (0..group.len())
+                        .rev()
+                        .max_by(|&a, &b| group[a].weight.total_cmp(&group[b].weight))
+                        .unwrap_or(0)
+                }
+            };
+
+            let mut variants: Vec<String> = Vec::new();
+            for word in &group {
+                if word.text != group[chosen].text && !variants.contains(&word.text) {
+                    variants.push(word.text.clone());
+                }
+            }
+
+            let mut word = group.into_iter().nth(chosen).unwrap_or_default();
+            if strategy == "title" {
+                let titled = title_case(&word.text);
+                if titled != word.text && !variants.contains(&word.text) {
+                    variants.insert(0, word.text.clone());
+                }
+                variants.retain(|variant| *variant != titled);
+                word.text = titled;
+            }
+            WordItem {
+                weight: total,
+                variants: (keep_variants && !variants.is_empty()).then_some(variants),
+                ..word
+            }
+        })
+        .collect()
+}
+
+// 每个以空白分隔的单词首字母大写，其余字母小写
+fn title_case(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut at_start = true;
+    for c in text.chars() {
+        if at_start {
+            result.extend(c.to_uppercase());
+        } else {
+            result.extend(c.to_lowercase());
+        }
+        at_start = c.is_whitespace();
+    }
+    result
+}
+
+// 默认网格大小及允许的最大网格大小
+pub(crate) const DEFAULT_GRID_SIZE: usize = 4;
+const MAX_GRID_SIZE: usize = 32;
+
+// 网格对齐时，最近的网格点有碰撞后继续尝试的圈数
+const SNAP_SEARCH_RINGS: i32 = 3;
+
+// 布局引擎：配置、占用网格、放置算法和最近一次布局的结果。
+// 不依赖 wasm-bindgen，canvas 测量、栅格化和 JS 回调等宿主能力通过 Host 提供，
+// WordCloud 把它包装成 JS 类，wordcloud_core 在原生目标上直接使用它
+#[derive(Clone)]
+pub struct LayoutEngine {
+    options: CloudOptions,
+    grid: Vec<u8>,
+    // 网格的列数和每行占用的字节数
+    grid_columns: usize,
+    grid_stride: usize,
+    // 当前生效的网格大小，单词过小时会自动降为 1；配置的网格大小见 options.grid_size
+    grid_size: usize,
+    // 最近一次布局产生的警告
+    warnings: Vec<String>,
+    // 最近一次布局中已放置的单词
+    placed: Vec<WordPosition>,
+    // 形状遮罩
+    mask: Option<Mask>,
+    // 手动指定的螺旋中心
+    center: Option<(f64, f64)>,
+    // 单词对之间的最小间距规则，跨多次布局保留
+    separation_rules: Vec<SeparationRule>,
+    // 调试视图中显示的命名区域
+    sections: Vec<Section>,
+    // 尚未完成的分步布局
+    pending: Option<PendingLayout>,
+    // 最近一次布局的统计信息和未能放置的单词
+    layout_stats: LayoutStats,
+    skipped: Vec<SkippedWord>,
+    // 最近一次 generate_layout 的放置统计，以及本次布局中检查过的候选位置数
+    last_stats: PlacementStats,
+    placement_attempts: Cell<u32>,
+    // 最近一次布局中因字号过小而未放置的单词
+    demoted: Vec<DemotedWord>,
+    // 布局版本号，已放置单词每次变化时递增
+    generation: u64,
+    // 按需重建的空间索引，用于视口查询
+    spatial_index: RefCell<Option<SpatialIndex>>,
+    // 单词中心的排序列表，用于检查最小中心距离
+    center_index: RefCell<Option<CenterIndex>>,
+    // 历次放置尝试的面积统计，用于修正容量估算中的装箱效率
+    packing_history: PackingHistory,
+    // 本次布局的主随机种子，各随机数流由它派生
+    layout_seed: u64,
+    // 连续放置失败的单词数，用于判断画布是否已饱和
+    consecutive_failures: u32,
+    // proportional 跳过策略重新放置时，归一化权重乘以该系数作为接纳概率
+    admission_scale: Option<f64>,
+    // 过滤前输入的权重范围，由 run 在变换之前记录，供 scale_over = "all_input" 使用
+    input_weight_range: Option<(f64, f64)>,
+    // 按字体校准的字宽表
+    glyph_table: Option<GlyphTable>,
+    // 按 (字体, 文本) 缓存的实测宽度
+    measure_cache: RefCell<HashMap<(String, String), f64>>,
+    // pixel 碰撞模式下正在放置的单词的像素精灵，check_collision 和 mark_grid_as_occupied 用它代替包围盒
+    active_sprite: Option<Sprite>,
+    // 对称模式下正在放置的单词有大小相近的配对候选，搜索时优先选择镜像位置也空闲的位置
+    seek_mirror_room: bool,
+    // 最近一次布局的上下文，供之后单独放置的单词使用
+    layout_context: Option<LayoutContext>,
+    // 碰撞包围盒扩展回调的结果缓存
+    footprint_cache: FootprintCache,
+    // 构造时探测到的运行环境能力
+    environment: Environment,
+    // 宿主提供的文本测量、栅格化和回调；原生目标上为 NoHost
+    host: Rc<dyn Host>,
+}
+
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct WordCloud {
+    engine: LayoutEngine,
+    // canvas 上下文和 JS 回调，每次修改后由 sync_host 同步给引擎
+    js_host: JsHost,
+    // 按地址预先加载的背景图片，to_canvas 在它加载完成后绘制
+    background_image: Option<(String, HtmlImageElement)>,
+}
+
+// WordCloud 的导出方法直接读写引擎的状态
+impl Deref for WordCloud {
+    type Target = LayoutEngine;
+
+    fn deref(&self) -> &LayoutEngine {
+        &self.engine
+    }
+}
+
+impl DerefMut for WordCloud {
+    fn deref_mut(&mut self) -> &mut LayoutEngine {
+        &mut self.engine
+    }
+}
+
+impl WordCloud {
+    // 修改 canvas 上下文或 JS 回调后调用，让引擎使用新的宿主
+    pub(crate) fn sync_host(&mut self) {
+        self.engine.host = Rc::new(self.js_host.clone());
+    }
 }
 
-fn default_rotation_range() -> f64 {
-    0.0
-}
+impl LayoutEngine {
+    pub(crate) fn new(
+        width: u32,
+        height: u32,
+        font_family: String,
+        font_weight: String,
+        min_size: f64,
+        max_size: f64,
+        grid_size: usize,
+    ) -> LayoutEngine {
+        // 网格大小为每个单元的像素数，0 没有意义
+        assert!(grid_size > 0, "grid_size must be at least 1 pixel per cell");
+        let grid_size = grid_size.min(MAX_GRID_SIZE);
+
+        let options = CloudOptions {
+            width,
+            height,
+            font_family,
+            font_weight,
+            min_size,
+            max_size,
+            rotation_range: 0.0,
+            spiral: "archimedean".to_string(),
+            background_image_url: None,
+            background_color: None,
+            watermark_text: None,
+            watermark_opacity: default_watermark_opacity(),
+            grid_background: false,
+            grid_background_size_px: default_grid_background_size_px(),
+            alt_text: None,
+            time_decay: None,
+            radial_size_falloff: 0.0,
+            letter_spacing: 0.0,
+            min_legible_size: default_min_legible_size(),
+            strict_mode: false,
+            subpixel_positioning: false,
+            snap_to_grid: false,
+            snap_grid_size: default_snap_grid_size(),
+            grid_size,
+            padding_top: 0.0,
+            padding_right: 0.0,
+            padding_bottom: 0.0,
+            padding_left: 0.0,
+            min_render_size: 0.0,
+            demoted_summary_template: None,
+            demoted_summary_size: default_demoted_summary_size(),
+            rotation_snap: 0.0,
+            yield_strategy: default_yield_strategy(),
+            yield_every: default_yield_every(),
+            yield_threshold_ms: default_yield_threshold_ms(),
+            palette: Vec::new(),
+            palette_mode: default_palette_mode(),
+            category_palettes: BTreeMap::new(),
+            palette_min_contrast: default_palette_min_contrast(),
+            palette_min_distance: default_palette_min_distance(),
+            adjust_palette_contrast: false,
+            responsive_font_scaling: false,
+            reference_width: width,
+            oversized_policy: default_oversized_policy(),
+            rtl_spiral_bias: false,
+            text_direction: default_text_direction(),
+            symmetry: default_symmetry(),
+            min_same_color_distance: None,
+            rotation_unit: default_rotation_unit(),
+            output_precision: None,
+            seed: None,
+            include_guides: false,
+            guide_every_px: default_guide_every_px(),
+            include_legend: false,
+            heatmap_overlay: false,
+            heatmap_cold_color: default_heatmap_cold_color(),
+            heatmap_hot_color: default_heatmap_hot_color(),
+            outline_only: false,
+            stroke_width_ratio: default_stroke_width_ratio(),
+            frequency_rings: None,
+            regression_minor_threshold: default_regression_minor_threshold(),
+            thumbnail_min_height: default_thumbnail_min_height(),
+            thumbnail_merge_small: false,
+            bundle_connections: false,
+            equalize_spiral_density: false,
+            deduplication_case_insensitive: false,
+            casing_strategy: default_casing_strategy(),
+            emit_casing_variants: false,
+            skip_policy: default_skip_policy(),
+            full_threshold: default_full_threshold(),
+            scale_over: default_scale_over(),
+            import_conflict_policy: default_import_conflict_policy(),
+            collision_mode: default_collision_mode(),
+            smart_rotation: false,
+            kerning_pairs: default_kerning_pairs(),
+            animation_mode: default_animation_mode(),
+            animation_stagger_ms: default_animation_stagger_ms(),
+            animation_duration_min_ms: default_animation_duration_min_ms(),
+            animation_duration_max_ms: default_animation_duration_max_ms(),
+            y_axis: default_y_axis(),
+            interactive: false,
+            physical_width_mm: None,
+            physical_height_mm: None,
+            min_print_height_mm: default_min_print_height_mm(),
+            visible_tags: Vec::new(),
+            min_word_center_distance: None,
+            stem: default_stem(),
+            hyphenate: false,
+            center_exclusion_radius: None,
+            enable_audio_support: false,
+            hyphenation_exceptions: BTreeMap::new(),
+        };
+
+        let (grid_width, grid_height, grid_stride) = grid_layout(width, height, grid_size);
+        let grid = vec![0; grid_stride * grid_height];
+
+        // 记录初始化信息
+        log(&format!(
+            "WordCloud initialized: {}x{} with grid {}x{}",
+            width, height, grid_width, grid_height
+        ));
+
+        LayoutEngine {
+            options,
+            grid,
+            grid_columns: grid_width,
+            grid_stride,
+            grid_size,
+            warnings: Vec::new(),
+            placed: Vec::new(),
+            mask: None,
+            center: None,
+            separation_rules: Vec::new(),
+            sections: Vec::new(),
+            pending: None,
+            layout_stats: LayoutStats::default(),
+            last_stats: PlacementStats::default(),
+            placement_attempts: Cell::new(0),
+            skipped: Vec::new(),
+            demoted: Vec::new(),
+            generation: 0,
+            spatial_index: RefCell::new(None),
+            center_index: RefCell::new(None),
+            packing_history: PackingHistory::default(),
+            layout_seed: 0,
+            consecutive_failures: 0,
+            admission_scale: None,
+            input_weight_range: None,
+            glyph_table: None,
+            measure_cache: RefCell::new(HashMap::new()),
+            active_sprite: None,
+            seek_mirror_room: false,
+            layout_context: None,
+            footprint_cache: FootprintCache::default(),
+            environment: Environment::probe(),
+            host: Rc::new(NoHost),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        width: u32,
+        height: u32,
+        font_family: String,
+        font_weight: String,
+        min_size: f64,
+        max_size: f64,
+        grid_size: usize,
+    ) -> WordCloud {
+        // 启用调试功能
+        #[cfg(feature = "console_error_panic_hook")]
+        console_error_panic_hook::set_once();
+
+        WordCloud {
+            engine: LayoutEngine::new(
+                width,
+                height,
+                font_family,
+                font_weight,
+                min_size,
+                max_size,
+                grid_size,
+            ),
+            js_host: JsHost::default(),
+            background_image: None,
+        }
+    }
+
+    // 添加一个重置网格的方法
+    #[wasm_bindgen]
+    pub fn reset_grid(&mut self) -> bool {
+        self.engine.reset_grid()
+    }
+
+    // 设置旋转范围
+    #[wasm_bindgen]
+    pub fn set_rotation_range(&mut self, rotation_range: f64) {
+        self.options.rotation_range = rotation_range;
+    }
+
+    // 设置螺旋类型
+    #[wasm_bindgen]
+    pub fn set_spiral(&mut self, spiral: String) {
+        self.options.spiral = spiral;
+    }
+
+    // 修改画布尺寸并重置网格
+    #[wasm_bindgen]
+    pub fn set_size(&mut self, width: u32, height: u32) -> bool {
+        self.options.width = width;
+        self.options.height = height;
+        // 遮罩按比例覆盖整个画布，质心和最大半径需按新尺寸重新计算
+        if let Some(mask) = &mut self.mask {
+            mask.resize(width as f64, height as f64);
+        }
+        self.placed.clear();
+        self.touch_layout();
+        self.reset_grid()
+    }
+
+    // 设置响应式字号：开启后字号按 width / reference_width 缩放
+    #[wasm_bindgen]
+    pub fn set_responsive_font_scaling(&mut self, enabled: bool) {
+        self.options.responsive_font_scaling = enabled;
+    }
+
+    // 设置字号所参照的画布宽度
+    #[wasm_bindgen]
+    pub fn set_reference_width(&mut self, reference_width: u32) {
+        self.options.reference_width = reference_width;
+    }
+
+    // 设置背景图片，传入 None 表示清除。有 DOM 时立即开始加载，供 to_canvas 使用
+    #[wasm_bindgen]
+    pub fn set_background_image_url(&mut self, url: Option<String>) {
+        self.options.background_image_url = url;
+        self.preload_background_image();
+    }
+
+    // 设置背景填充色，传入 None 表示透明背景
+    #[wasm_bindgen]
+    pub fn set_background_color(&mut self, color: Option<String>) {
+        self.options.background_color = color;
+    }
+
+    // 设置径向字号衰减：离中心越远的单词越小
+    #[wasm_bindgen]
+    pub fn set_radial_size_falloff(&mut self, falloff: f64) {
+        self.options.radial_size_falloff = falloff.clamp(0.0, 1.0);
+    }
+
+    // 设置字间距（像素）
+    #[wasm_bindgen]
+    pub fn set_letter_spacing(&mut self, letter_spacing: f64) {
+        self.options.letter_spacing = letter_spacing;
+    }
+
+    // 设置可读的最小字号
+    #[wasm_bindgen]
+    pub fn set_min_legible_size(&mut self, min_legible_size: f64) {
+        self.options.min_legible_size = min_legible_size;
+    }
+
+    // 设置严格模式
+    #[wasm_bindgen]
+    pub fn set_strict_mode(&mut self, strict_mode: bool) {
+        self.options.strict_mode = strict_mode;
+    }
+
+    // 设置亚像素定位
+    #[wasm_bindgen]
+    pub fn set_subpixel_positioning(&mut self, enabled: bool) {
+        self.options.subpixel_positioning = enabled;
+    }
+
+    // 设置网格对齐及对齐间距（像素）
+    #[wasm_bindgen]
+    pub fn set_snap_to_grid(&mut self, enabled: bool, snap_grid_size: u32) {
+        self.options.snap_to_grid = enabled;
+        self.options.snap_grid_size = snap_grid_size.max(1);
+    }
+
+    // 设置是否在布局前忽略大小写合并重复的单词
+    #[wasm_bindgen]
+    pub fn set_deduplication_case_insensitive(&mut self, enabled: bool) {
+        self.options.deduplication_case_insensitive = enabled;
+    }
+
+    // 设置合并重复单词时选取显示文本的方式，以及是否输出被舍弃的写法
+    #[wasm_bindgen]
+    pub fn set_casing_strategy(&mut self, strategy: String, emit_variants: bool) {
+        self.options.casing_strategy = strategy;
+        self.options.emit_casing_variants = emit_variants;
+    }
+
+    // 设置画布饱和后的跳过策略（"tail" 或 "proportional"）以及判定饱和的连续失败次数
+    #[wasm_bindgen]
+    pub fn set_skip_policy(&mut self, policy: String, full_threshold: u32) {
+        self.options.skip_policy = policy;
+        self.options.full_threshold = full_threshold.max(1);
+    }
+
+    // 设置字号的权重范围计算方式："survivors" 或 "all_input"
+    #[wasm_bindgen]
+    pub fn set_scale_over(&mut self, scale_over: String) {
+        self.options.scale_over = scale_over;
+    }
+
+    // 设置从文本统计词频时的词干提取方式："none"、"plural" 或 "porter"
+    #[wasm_bindgen]
+    pub fn set_stem(&mut self, stem: String) {
+        self.options.stem = stem;
+    }
+
+    // 设置是否用 Halton 序列均匀采样画布代替螺旋搜索
+    #[wasm_bindgen]
+    pub fn set_equalize_spiral_density(&mut self, enabled: bool) {
+        self.options.equalize_spiral_density = enabled;
+    }
+
+    // 设置碰撞检测的旋转吸附容差（度）
+    #[wasm_bindgen]
+    pub fn set_rotation_snap(&mut self, rotation_snap: f64) {
+        self.options.rotation_snap = rotation_snap.max(0.0);
+    }
+
+    // 设置超大单词的处理方式："shrink"、"skip" 或 "error"
+    #[wasm_bindgen]
+    pub fn set_oversized_policy(&mut self, policy: String) {
+        self.options.oversized_policy = policy;
+    }
+
+    // 设置螺旋的从右向左偏好
+    #[wasm_bindgen]
+    pub fn set_rtl_spiral_bias(&mut self, enabled: bool) {
+        self.options.rtl_spiral_bias = enabled;
+    }
+
+    // 设置文字书写方向："ltr" 或 "rtl"
+    #[wasm_bindgen]
+    pub fn set_text_direction(&mut self, direction: String) {
+        self.options.text_direction = direction;
+    }
+
+    // 设置相同颜色单词之间的最小间距，传入 None 取消限制
+    #[wasm_bindgen]
+    pub fn set_min_same_color_distance(&mut self, distance: Option<f64>) {
+        self.options.min_same_color_distance = distance.map(|d| d.max(0.0));
+    }
+
+    // 设置碰撞网格的像素大小，范围 [1, 32]，在下一次布局时生效
+    #[wasm_bindgen]
+    pub fn set_grid_size(&mut self, grid_size: usize) {
+        self.options.grid_size = grid_size.clamp(1, MAX_GRID_SIZE);
+    }
+
+    // 获取当前配置，附带实际生效的网格大小
+    #[wasm_bindgen]
+    pub fn get_options(&self) -> String {
+        let mut value = serde_json::to_value(&self.options).unwrap_or_default();
+        if let Some(object) = value.as_object_mut() {
+            object.insert("effective_grid_size".to_string(), self.grid_size.into());
+        }
+        value.to_string()
+    }
+
+    // 获取最近一次布局产生的警告
+    #[wasm_bindgen]
+    pub fn get_warnings(&self) -> String {
+        serde_json::to_string(&self.warnings).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    // 生成词云布局
+    #[wasm_bindgen]
+    pub fn generate_layout(&mut self, words_json: String) -> String {
+        self.layout_from_json(&words_json);
+
+        // 将结果序列化为JSON
+        self.layout_json()
+    }
+
+    // 生成词云布局，同时返回放不下的单词：{"placed": [...], "skipped": [...]}，
+    // skipped 中每项带有 text、weight、计算出的 size 以及原因
+    #[wasm_bindgen]
+    pub fn generate_layout_detailed(&mut self, words_json: String) -> String {
+        self.layout_from_json(&words_json);
+
+        let result = DetailedLayout {
+            placed: &self.layout_positions(),
+            skipped: &self.skipped,
+        };
+        serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    // 合并另一份布局：与当前网格无碰撞的单词被加入，其余放入 rejected
+    #[wasm_bindgen]
+    pub fn merge_layouts(&mut self, other_layout_json: String) -> String {
+        let others: Vec<WordPosition> = match serde_json::from_str(&other_layout_json) {
+            Ok(w) => w,
+            Err(e) => {
+                log(&format!("解析布局JSON失败: {}", e));
+                Vec::new()
+            }
+        };
+
+        let mut rejected: Vec<WordPosition> = Vec::new();
+        for word in others {
+            let word = self.import_position(word);
+            let (word_width, word_height) = self.word_box(&word);
+            if self.check_collision(word.x, word.y, word_width, word_height, word.rotate) {
+                rejected.push(word);
+            } else {
+                self.mark_grid_as_occupied(word.x, word.y, word_width, word_height, word.rotate);
+                self.placed.push(word);
+                self.touch_layout();
+            }
+        }
+
+        let result = MergeResult {
+            placed: &self.emit_layout(&self.placed),
+            rejected: &self.emit_layout(&rejected),
+        };
+        serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+impl LayoutEngine {
+    pub(crate) fn reset_grid(&mut self) -> bool {
+        // 重新创建网格而不是清空现有网格
+        let (grid_width, grid_height) = self.allocate_grid();
+        self.apply_mask_to_grid();
+        self.apply_center_exclusion();
+
+        // 记录重置信息
+        log(&format!("Grid reset to {}x{}", grid_width, grid_height));
+
+        // 返回成功标志
+        true
+    }
+
+    // 配置的网格大小，通过 run 传入的值也限制在 [1, 32] 内
+    fn configured_grid_size(&self) -> usize {
+        self.options.grid_size.clamp(1, MAX_GRID_SIZE)
+    }
+
+    // 根据已放置单词的字号计算其包围盒尺寸
+    fn word_box(&self, word: &WordPosition) -> (f64, f64) {
+        if let Some(measured_box) = word.measured_box {
+            return measured_box;
+        }
+        let (width, height) = self.text_box(word);
+        let measured = MeasuredWord {
+            size: word.size.unwrap_or(self.options.max_size),
+            width,
+            height,
+            parts: None,
+        };
+        let measured = self.with_phonetic(word.phonetic.as_deref(), measured);
+        let measured = self.with_sparkline(word.trend.is_some(), measured);
+        (measured.width, measured.height)
+    }
+
+    // 单词文字本身（不含注音）的宽和高
+    fn text_box(&self, word: &WordPosition) -> (f64, f64) {
+        let size = word.size.unwrap_or(self.options.max_size);
+        // 断字的单词各行上下排列
+        let line_count = word.lines.as_ref().map_or(1, |lines| lines.len()) as f64;
+        match &word.parts {
+            Some(parts) => (self.compound_width(parts), size * line_count),
+            None => (self.estimate_word_width(&word.text, size), size),
+        }
+    }
+
+    // 布局前的准备：重置网格、解析输入并计算权重范围
+    // 输入无效时返回 None
+    fn prepare_layout(&mut self, words_json: &str) -> Option<(Vec<WordItem>, LayoutContext)> {
+        self.reset_layout_state();
+
+        // 解析输入词语
+        let words = match parse_words_json(words_json) {
+            Ok(w) => w,
+            Err(e) => {
+                log(&format!("解析词语JSON失败: {}", e));
+                return None;
+            }
+        };
+
+        self.prepare_words(words)
+    }
+
+    // 解析 JSON 输入并布局，解析失败时只清空上一次的布局
+    fn layout_from_json(&mut self, words_json: &str) {
+        match parse_words_json(words_json) {
+            Ok(words) => self.layout_words(words),
+            Err(e) => {
+                // 解析失败也是一次（空的）布局，统计信息清零而不是保留上一次的结果
+                let started = now_ms();
+                self.reset_layout_state();
+                log(&format!("解析词语JSON失败: {}", e));
+                self.record_placement_stats(now_ms() - started);
+            }
+        }
+    }
+
+    // 用已解析的单词完成一次完整布局，JSON 和 JsValue 两种接口共用
+    fn layout_words(&mut self, words: Vec<WordItem>) {
+        let started = now_ms();
+        self.reset_layout_state();
+        if let Some((words, context)) = self.prepare_words(words) {
+            self.place_all(words, &context);
+        }
+        self.record_placement_stats(now_ms() - started);
+    }
+
+    // 清空上一次布局的状态
+    fn reset_layout_state(&mut self) {
+        // 记录生成开始
+        log("开始生成词云布局");
+
+        // 重置网格，同时放弃未完成的分步布局
+        self.pending = None;
+        self.warnings.clear();
+        self.grid_size = self.configured_grid_size();
+        self.clear_placements();
+        self.demoted.clear();
+        self.layout_seed = self.next_layout_seed();
+    }
+
+    // 清空网格和已放置的单词，保留随机种子、警告和降级结果，供同一次布局重新开始放置
+    pub(crate) fn clear_placements(&mut self) {
+        let reset_success = self.reset_grid();
+        log(&format!(
+            "网格重置状态: {}",
+            if reset_success { "成功" } else { "失败" }
+        ));
+        self.placed.clear();
+        self.touch_layout();
+        self.skipped.clear();
+        self.layout_stats = LayoutStats::default();
+        self.placement_attempts.set(0);
+        self.consecutive_failures = 0;
+    }
+
+    // 在已清空的状态上准备输入词语：调整顺序、计算权重范围并处理超大单词
+    fn prepare_words(&mut self, words: Vec<WordItem>) -> Option<(Vec<WordItem>, LayoutContext)> {
+        log(&format!("词语数量: {}", words.len()));
+
+        // 过滤前的权重范围：run 记录的变换前范围优先，否则为本次传入的全部单词
+        let input_range = self
+            .input_weight_range
+            .take()
+            .unwrap_or_else(|| weight_range(&words));
+
+        let words: Vec<WordItem> = words
+            .into_iter()
+            .filter(|word| self.tags_visible(&word.tags))
+            .collect();
+        // 过期的单词记为跳过，其余按年龄衰减权重
+        let words = self.apply_time_decay(words);
+        if words.is_empty() {
+            return None;
+        }
+
+        let words = if self.options.deduplication_case_insensitive {
+            dedupe_case_insensitive(
+                words,
+                &self.options.casing_strategy,
+                self.options.emit_casing_variants,
+            )
+        } else {
+            words
+        };
+
+        // 按自定义比较函数调整放置顺序
+        let mut words = self.apply_render_priority(words);
+
+        for word in &mut words {
+            word.normalize_compound();
+        }
+
+        // 找出最大和最小权重（组合词按各部分的权重计算）。
+        // all_input 模式下并入过滤前的范围，合并后的权重仍可能超出原范围。
+        // 过滤前没有单词时 weight_range 返回 (inf, -inf)，此时不合并
+        let (mut min_weight, mut max_weight) = weight_range(&words);
+        if self.options.scale_over == "all_input" && input_range.0 <= input_range.1 {
+            min_weight = min_weight.min(input_range.0);
+            max_weight = max_weight.max(input_range.1);
+        }
+        self.layout_stats.min_weight = min_weight;
+        self.layout_stats.max_weight = max_weight;
+
+        // 检查字号是否可读，并在单词过小时细化网格
+        if let Err(e) = self.check_legibility(&words, min_weight, max_weight) {
+            log(&e);
+            return None;
+        }
+
+        // 计算中心点（有遮罩时为遮罩质心）
+        let (center_x, center_y) = self.layout_center();
+
+        let context = LayoutContext {
+            min_weight,
+            max_weight,
+            center_x,
+            center_y,
+            palette: self.effective_palette(),
+        };
+
+        // 在螺旋搜索之前处理超出可用区域的单词
+        let words = match self.resolve_oversized(words, &context) {
+            Ok(words) => words,
+            Err(e) => {
+                log(&e);
+                self.warnings.push(e);
+                return None;
+            }
+        };
+
+        // 缩放后仍低于最小显示字号的单词不参与放置
+        let words = self.demote_unreadable(words, &context);
+
+        self.layout_context = Some(context.clone());
+        Some((words, context))
+    }
+
+    // 按已放置的单词重建网格，用于移除单词后释放其占用的单元
+    fn rebuild_grid(&mut self) {
+        self.reset_grid();
+        for index in 0..self.placed.len() {
+            self.mark_placed_word(index);
+        }
+    }
+
+    // 放置单个单词，成功时返回 true
+    fn place_word(&mut self, mut word: WordItem, context: &LayoutContext) -> bool {
+        let (measured, rotation) = self.prepare_word(&mut word, context);
+        let size = measured.size;
+        let candidates = self.rotation_candidates(&word, &measured, rotation, context);
+        let rotation = candidates[0];
+        for candidate in candidates {
+            word = match self.place_measured(word, measured.clone(), candidate, context) {
+                Ok(()) => return true,
+                Err(word) => *word,
+            };
+        }
+
+        // 放不下时尝试在最佳断字点断为两行后重试
+        if word.parts.is_none() {
+            if let Some(at) = self.best_hyphen_point(&word.text) {
+                let hyphenated = WordItem {
+                    hyphen_at: Some(at),
+                    ..word.clone()
+                };
+                let factor = hyphenated.size_factor.unwrap_or(1.0);
+                let measured = self.measure_word(&hyphenated, context, factor);
+                if self
+                    .place_measured(hyphenated, measured, rotation, context)
+                    .is_ok()
+                {
+                    return true;
+                }
+            }
+        }
+
+        // 所有角度都放不下只计一次失败，按扩展后的包围盒计入装箱历史
+        let inflation = word.inflation.unwrap_or_default();
+        let search = Footprint::inflate(0.0, 0.0, measured.width, measured.height, 0.0, &inflation);
+        self.record_packing(search.width, search.height, false);
+        self.record_skipped(&word, size, "no_space");
+        false
+    }
+
+    // 按测量结果搜索位置并放置，找不到位置时原样返回单词
+    fn place_measured(
+        &mut self,
+        mut word: WordItem,
+        measured: MeasuredWord,
+        rotation: f64,
+        context: &LayoutContext,
+    ) -> Result<(), Box<WordItem>> {
+        let (center_x, center_y) = (context.center_x, context.center_y);
+        let collision_rotation = self.collision_rotation(rotation);
+
+        // pixel 模式按字形像素检测碰撞，精灵已经贴合字形，不再扩展包围盒
+        self.active_sprite = self.word_sprite(&word, &measured, rotation);
+
+        // 搜索时使用扩展后的碰撞包围盒，找到的是包围盒中心
+        let inflation = if self.active_sprite.is_some() {
+            Inflation::default()
+        } else {
+            self.footprint_inflation(&word.text, measured.size, measured.width, measured.height)
+        };
+        word.inflation = Some(inflation);
+        let search = Footprint::inflate(
+            0.0,
+            0.0,
+            measured.width,
+            measured.height,
+            collision_rotation,
+            &inflation,
+        );
+
+        // 尝试放置单词
+        let (x, y) = match self.find_position_for_word(
+            &word,
+            center_x,
+            center_y,
+            search.width,
+            search.height,
+            collision_rotation,
+        ) {
+            Some(position) => position,
+            None => {
+                self.active_sprite = None;
+                return Err(Box::new(word));
+            }
+        };
+        self.record_packing(search.width, search.height, true);
+
+        // 网格对齐，周围都放不下时保留螺旋找到的位置；否则按需亚像素微调
+        let (x, y) = if self.options.snap_to_grid {
+            self.snap_position(&word, x, y, &search, collision_rotation)
+                .unwrap_or((x, y))
+        } else if self.options.subpixel_positioning {
+            self.refine_subpixel(
+                &word,
+                x,
+                y,
+                center_x,
+                center_y,
+                search.width,
+                search.height,
+                collision_rotation,
+            )
+        } else {
+            (x, y)
+        };
+
+        // 由包围盒中心换算回单词中心
+        let (x, y) = Footprint { x, y, ..search }.word_center(collision_rotation, &inflation);
+
+        self.commit_word(word, measured, x, y, rotation, context);
+        self.active_sprite = None;
+        Ok(())
+    }
+
+    // 放置前的准备：测量单词、确定旋转角度和颜色
+    fn prepare_word(&self, word: &mut WordItem, context: &LayoutContext) -> (MeasuredWord, f64) {
+        // 计算字体大小和大致的宽度和高度 (这部分在真实实现中需要从canvas获取)
+        let factor = word.size_factor.unwrap_or(1.0);
+        let measured = self.measure_word(word, context, factor);
+
+        // 旋转角度
+        let rotation = match word.rotate {
+            Some(r) => self.rotation_to_internal(r),
+            None if self.options.rotation_range > 0.0 => {
+                let range = self.rotation_to_internal(self.options.rotation_range);
+                let random = self.rng(RngStream::Rotation, Some(word.key())).next_f64();
+                (random * 2.0 - 1.0) * range
+            }
+            None => 0.0,
+        };
+
+        // 未指定颜色时按调色板着色；颜色需在寻找位置前确定，以便检查同色间距
+        // random 模式按单词 key 从颜色流中抽取，不受其他单词是否放置成功的影响
+        // 类别有自己的调色板时，在该调色板中按类别内已放置的单词数依次取色
+        let category_palette = word
+            .category
+            .as_ref()
+            .and_then(|category| self.options.category_palettes.get(category))
+            .filter(|palette| !palette.is_empty())
+            .map(|palette| self.order_palette(palette));
+        if word.color.is_none() {
+            let (palette, placed) = match &category_palette {
+                Some(palette) => (
+                    palette.as_slice(),
+                    self.placed
+                        .iter()
+                        .filter(|placed| placed.category == word.category)
+                        .count(),
+                ),
+                None => (context.palette.as_slice(), self.placed.len()),
+            };
+            if !palette.is_empty() {
+                let index = if self.options.palette_mode == "random" {
+                    let random = self.rng(RngStream::Color, Some(word.key())).next_f64();
+                    (random * palette.len() as f64) as usize % palette.len()
+                } else {
+                    placed % palette.len()
+                };
+                let color = &palette[index];
+                word.color = Some(match &self.options.background_color {
+                    Some(background) if self.options.adjust_palette_contrast => {
+                        ensure_contrast(color, background, self.options.palette_min_contrast)
+                    }
+                    _ => color.clone(),
+                });
+            }
+        }
+
+        (measured, rotation)
+    }
+
+    // 把单词放在已确认可用的位置：按径向衰减调整字号，标记网格并记录
+    fn commit_word(
+        &mut self,
+        word: WordItem,
+        measured: MeasuredWord,
+        x: f64,
+        y: f64,
+        rotation: f64,
+        context: &LayoutContext,
+    ) {
+        // 按到中心的距离缩小字号，缩小后的包围盒必然不会产生新的碰撞
+        let factor = word.size_factor.unwrap_or(1.0);
+        let size = measured.size;
+        let scale = self.radial_scale(x - context.center_x, y - context.center_y);
+        // 缩小后的字号也不能低于 min_render_size，否则会绕过可读性降级
+        let mut floor = self.options.min_size;
+        if self.options.min_render_size > 0.0 {
+            floor = floor.max(self.options.min_render_size);
+        }
+        let scaled_size = (size * scale).max(floor.min(size));
+        let measured = if scaled_size < size {
+            self.measure_word(&word, context, factor * scaled_size / size)
+        } else {
+            measured
+        };
+
+        // 标记网格为已占用，有扩展时标记扩展后的包围盒
+        let inflation = word.inflation.unwrap_or_default();
+        let collision_rotation = self.collision_rotation(rotation);
+        let footprint = self.footprint_for(
+            x,
+            y,
+            measured.width,
+            measured.height,
+            collision_rotation,
+            &inflation,
+        );
+        match &footprint {
+            Some(f) => self.mark_grid_as_occupied(f.x, f.y, f.width, f.height, collision_rotation),
+            None => self.mark_grid_as_occupied(
+                x,
+                y,
+                measured.width,
+                measured.height,
+                collision_rotation,
+            ),
+        }
+
+        // 置信区间字号与单词字号按相同比例缩放（径向衰减、超大缩小）
+        let ci_sizes = match (word.weight_ci_low, word.weight_ci_high, &measured.parts) {
+            (Some(low), Some(high), None) => {
+                let base =
+                    self.word_size(word.sizing_weight(), context.min_weight, context.max_weight);
+                let ratio = if base > 0.0 {
+                    measured.size / base
+                } else {
+                    1.0
+                };
+                let size_of =
+                    |weight| self.word_size(weight, context.min_weight, context.max_weight) * ratio;
+                Some((size_of(low.min(high)), size_of(low.max(high))))
+            }
+            _ => None,
+        };
+
+        let lines = word
+            .hyphen_at
+            .map(|at| hyphen_lines(&word.text, at).to_vec());
+        let color = self.decay_color(word.color.clone(), word.decay);
+        let opacity = self.decay_opacity(word.decay);
+
+        // 添加到已放置单词
+        self.placed.push(WordPosition {
+            text: word.text,
+            weight: word.weight,
+            x,
+            y,
+            rotate: rotation,
+            color,
+            size: Some(measured.size),
+            key: word.key,
+            parts: measured.parts,
+            annotation: word.annotation,
+            annotation_direction: word.annotation_direction,
+            gradient_angle: word.gradient_angle,
+            ci_low_size: ci_sizes.map(|(low, _)| low),
+            ci_high_size: ci_sizes.map(|(_, high)| high),
+            category: word.category,
+            mirror: None,
+            footprint,
+            variants: word.variants,
+            animation_start_time: None,
+            animation_duration: None,
+            tags: word.tags,
+            hyphenated: word.hyphen_at.map(|_| true),
+            lines,
+            audio_url: word.audio_url,
+            phonetic: word.phonetic,
+            trend: word.trend,
+            sparkline: None,
+            aria_label: word.aria_label,
+            age_ms: word.decay.map(|decay| decay.age_ms),
+            decay: word.decay.map(|decay| decay.factor),
+            opacity,
+            measured_box: Some((measured.width, measured.height)),
+        });
+        self.layout_stats.placed += 1;
+        self.touch_layout();
+    }
 
-fn default_spiral() -> String {
-    "archimedean".to_string()
-}
+    // 测量单词：计算字号和包围盒，factor 为额外的字号缩放
+    // 组合词的各部分按自身权重排列在同一基线上，整体参与碰撞检测
+    fn measure_word(&self, word: &WordItem, context: &LayoutContext, factor: f64) -> MeasuredWord {
+        let measured = self.measure_text(word, context, factor);
+        let measured = self.with_phonetic(word.phonetic.as_deref(), measured);
+        self.with_sparkline(word.trend.is_some(), measured)
+    }
 
-#[wasm_bindgen]
-pub struct WordCloud {
-    options: CloudOptions,
-    grid: Vec<Vec<bool>>,
-    grid_size: usize,
-}
+    // 测量单词文字本身，不含注音
+    fn measure_text(&self, word: &WordItem, context: &LayoutContext, factor: f64) -> MeasuredWord {
+        // 断字的单词分两行排列，每行以自身中心对齐，整体参与碰撞检测
+        if let Some(at) = word.hyphen_at {
+            let size = self.word_size(word.sizing_weight(), context.min_weight, context.max_weight)
+                * factor;
+            let [first, second] = hyphen_lines(&word.text, at);
+            let width = self
+                .estimate_word_width(&first, size)
+                .max(self.estimate_word_width(&second, size));
+            let line = |text: String, dy: f64| PartPosition {
+                text,
+                size,
+                dx: 0.0,
+                dy,
+                color: None,
+                separator: false,
+            };
+            return MeasuredWord {
+                size,
+                width,
+                height: size * 2.0,
+                parts: Some(vec![line(first, -size / 2.0), line(second, size / 2.0)]),
+            };
+        }
+        match self.layout_compound(word, context, factor) {
+            Some((parts, width, height)) => MeasuredWord {
+                size: height,
+                width,
+                height,
+                parts: Some(parts),
+            },
+            None => {
+                let size =
+                    self.word_size(word.sizing_weight(), context.min_weight, context.max_weight)
+                        * factor;
+                MeasuredWord {
+                    size,
+                    width: self.estimate_word_width(&word.text, size),
+                    height: size,
+                    parts: None,
+                }
+            }
+        }
+    }
 
-#[wasm_bindgen]
-impl WordCloud {
-    #[wasm_bindgen(constructor)]
-    pub fn new(
-        width: u32,
-        height: u32,
-        font_family: String,
-        font_weight: String,
-        min_size: f64,
-        max_size: f64,
-    ) -> WordCloud {
-        // 启用调试功能
-        #[cfg(feature = "console_error_panic_hook")]
-        console_error_panic_hook::set_once();
+    // 可用于放置单词的区域尺寸（扣除安全区内边距）
+    fn usable_area(&self) -> (f64, f64) {
+        let (min_x, min_y, max_x, max_y) = self.safe_bounds();
+        (max_x - min_x, max_y - min_y)
+    }
 
-        let options = CloudOptions {
-            width,
-            height,
-            font_family,
-            font_weight,
-            min_size,
-            max_size,
-            rotation_range: 0.0,
-            spiral: "archimedean".to_string(),
-        };
+    // 检查超出可用区域的单词，并按 oversized_policy 缩小、跳过或报错
+    fn resolve_oversized(
+        &mut self,
+        words: Vec<WordItem>,
+        context: &LayoutContext,
+    ) -> Result<Vec<WordItem>, String> {
+        let (usable_width, usable_height) = self.usable_area();
 
-        // 网格大小 - 调整为更精细以提高精度
-        let grid_size = 4;
-        let grid_width = (width as usize / grid_size) + 1;
-        let grid_height = (height as usize / grid_size) + 1;
+        // 未指定角度的单词在 [-range, range] 内随机旋转
+        let range = if self.options.rotation_range > 0.0 {
+            self.rotation_to_internal(self.options.rotation_range).abs()
+        } else {
+            0.0
+        };
 
-        let grid = vec![vec![false; grid_height]; grid_width];
+        // 旋转后的轴对齐包围盒尺寸；未指定角度时取旋转范围内最坏情况的尺寸。
+        // along * |cos θ| + across * |sin θ| 在 θ = atan(across / along) 之前单调增大，
+        // 之后减小，因此最大值在 θ = min(range, atan(across / along)) 处取得
+        let rotated_extent = |measured: &MeasuredWord, rotation: Option<f64>| match rotation {
+            Some(rotation) => {
+                let (sin, cos) = rotation.sin_cos();
+                (
+                    (measured.width * cos).abs() + (measured.height * sin).abs(),
+                    (measured.width * sin).abs() + (measured.height * cos).abs(),
+                )
+            }
+            None => {
+                let spread = |along: f64, across: f64| {
+                    let theta = range.min(across.atan2(along));
+                    along * theta.cos() + across * theta.sin()
+                };
+                (
+                    spread(measured.width, measured.height),
+                    spread(measured.height, measured.width),
+                )
+            }
+        };
 
-        // 记录初始化信息
-        console::log_1(&JsValue::from_str(&format!(
-            "WordCloud initialized: {}x{} with grid {}x{}",
-            width, height, grid_width, grid_height
-        )));
+        let mut kept = Vec::with_capacity(words.len());
+        for mut word in words {
+            let rotation = word.rotate.map(|r| self.rotation_to_internal(r));
+            let measured = self.measure_word(&word, context, 1.0);
+            let (extent_width, extent_height) = rotated_extent(&measured, rotation);
+            if extent_width <= usable_width && extent_height <= usable_height {
+                kept.push(word);
+                continue;
+            }
 
-        WordCloud {
-            options,
-            grid,
-            grid_size,
+            self.layout_stats.oversized += 1;
+            match self.options.oversized_policy.as_str() {
+                "skip" => self.record_skipped(&word, measured.size, "oversized"),
+                "error" => {
+                    let (dimension, extent, limit) = if extent_width > usable_width {
+                        ("width", extent_width, usable_width)
+                    } else {
+                        ("height", extent_height, usable_height)
+                    };
+                    return Err(format!(
+                        "word \"{}\" is oversized: its {} ({:.1}px) exceeds the usable {} ({:.1}px)",
+                        word.text, dimension, extent, dimension, limit
+                    ));
+                }
+                _ => {
+                    // 二分查找能放入可用区域的最大缩放比例
+                    let (mut low, mut high) = (0.0, 1.0);
+                    for _ in 0..32 {
+                        let mid = (low + high) / 2.0;
+                        let (w, h) =
+                            rotated_extent(&self.measure_word(&word, context, mid), rotation);
+                        if w <= usable_width && h <= usable_height {
+                            low = mid;
+                        } else {
+                            high = mid;
+                        }
+                    }
+                    word.size_factor = Some(low);
+                    kept.push(word);
+                }
+            }
         }
+        Ok(kept)
     }
 
-    // 添加一个重置网格的方法
-    #[wasm_bindgen]
-    pub fn reset_grid(&mut self) -> bool {
-        let width = self.options.width;
-        let height = self.options.height;
+    // 根据权重计算字体大小
+    fn word_size(&self, weight: f64, min_weight: f64, max_weight: f64) -> f64 {
+        let size = if max_weight == min_weight {
+            self.options.max_size
+        } else {
+            self.options.min_size
+                + ((weight - min_weight) / (max_weight - min_weight))
+                    * (self.options.max_size - self.options.min_size)
+        };
+        size * self.responsive_scale()
+    }
 
-        // 重新创建网格而不是清空现有网格
-        let grid_width = (width as usize / self.grid_size) + 1;
-        let grid_height = (height as usize / self.grid_size) + 1;
+    // 响应式字号的缩放比例
+    fn responsive_scale(&self) -> f64 {
+        let reference_width = self.options.reference_width;
+        if self.options.responsive_font_scaling && reference_width > 0 {
+            self.options.width as f64 / reference_width as f64
+        } else {
+            1.0
+        }
+    }
 
-        self.grid = vec![vec![false; grid_height]; grid_width];
+    // 检查字号的可读性：过小时记录警告（严格模式下返回错误），
+    // 并在最小单词跨越不足 2 个网格时细化网格，以限制量化误差
+    fn check_legibility(
+        &mut self,
+        words: &[WordItem],
+        min_weight: f64,
+        max_weight: f64,
+    ) -> Result<(), String> {
+        let legible = self.options.min_legible_size;
+        for (name, value) in [
+            ("min_size", self.options.min_size),
+            ("max_size", self.options.max_size),
+        ] {
+            if value < legible {
+                let message = format!(
+                    "{} ({}) is below min_legible_size ({})",
+                    name, value, legible
+                );
+                if self.options.strict_mode {
+                    return Err(message);
+                }
+                self.warnings.push(message);
+            }
+        }
 
-        // 记录重置信息
-        console::log_1(&JsValue::from_str(&format!(
-            "Grid reset to {}x{}",
-            grid_width, grid_height
-        )));
+        let (min_width, min_height) = words
+            .iter()
+            .map(|w| {
+                let size = self.word_size(w.sizing_weight(), min_weight, max_weight);
+                (self.estimate_word_width(&w.text, size), size)
+            })
+            .fold((f64::INFINITY, f64::INFINITY), |(a, b), (w, h)| {
+                (a.min(w), b.min(h))
+            });
 
-        // 返回成功标志
-        true
+        // 细化到仍能让最小单词跨越 2 个网格的最大网格大小
+        let cell = self.grid_size as f64;
+        if self.grid_size > 1 && (min_width < 2.0 * cell || min_height < 2.0 * cell) {
+            let refined = (min_width.min(min_height) / 2.0).floor();
+            self.grid_size = if refined >= 1.0 { refined as usize } else { 1 };
+            self.reset_grid();
+            self.warnings.push(format!(
+                "smallest word box ({:.1}x{:.1}) spans less than 2 grid cells, grid size reduced to {}",
+                min_width, min_height, self.grid_size
+            ));
+        }
+
+        Ok(())
     }
 
-    // 设置旋转范围
-    #[wasm_bindgen]
-    pub fn set_rotation_range(&mut self, rotation_range: f64) {
-        self.options.rotation_range = rotation_range;
+    // 已放置单词的旋转包围盒
+    fn word_obb(&self, word: &WordPosition) -> Obb {
+        let (word_width, word_height) = self.word_box(word);
+        Obb::new(
+            word.x,
+            word.y,
+            word_width,
+            word_height,
+            self.collision_rotation(word.rotate),
+        )
     }
 
-    // 设置螺旋类型
-    #[wasm_bindgen]
-    pub fn set_spiral(&mut self, spiral: String) {
-        self.options.spiral = spiral;
+    // 碰撞检测使用的旋转角度：与水平或竖直方向相差不超过 rotation_snap 度时吸附到该方向，
+    // 避免微小旋转使标记的包围盒变大；输出的旋转角度不受影响
+    fn collision_rotation(&self, rotation: f64) -> f64 {
+        let snap = self.options.rotation_snap.to_radians();
+        if snap <= 0.0 {
+            return rotation;
+        }
+        let quarter = std::f64::consts::FRAC_PI_2;
+        let nearest = (rotation / quarter).round() * quarter;
+        if (rotation - nearest).abs() <= snap {
+            nearest
+        } else {
+            rotation
+        }
     }
 
-    // 生成词云布局
-    #[wasm_bindgen]
-    pub fn generate_layout(&mut self, words_json: String) -> String {
-        // 记录生成开始
-        console::log_1(&JsValue::from_str("开始生成词云布局"));
+    // 精确碰撞检测：按真实包围盒检查画布边界、遮罩和已放置单词
+    fn check_exact_collision(&self, candidate: &Obb) -> bool {
+        let width = self.options.width as f64;
+        let height = self.options.height as f64;
 
-        // 重置网格
-        let reset_success = self.reset_grid();
-        console::log_1(&JsValue::from_str(&format!(
-            "网格重置状态: {}",
-            if reset_success { "成功" } else { "失败" }
-        )));
+        let (min_x, min_y, max_x, max_y) = candidate.bounds();
+        let (safe_min_x, safe_min_y, safe_max_x, safe_max_y) = self.safe_bounds();
+        if min_x < safe_min_x || max_x > safe_max_x || min_y < safe_min_y || max_y > safe_max_y {
+            return true;
+        }
 
-        // 解析输入词语
-        let words: Vec<WordItem> = match serde_json::from_str(&words_json) {
-            Ok(w) => w,
-            Err(e) => {
-                console::log_1(&JsValue::from_str(&format!("解析词语JSON失败: {}", e)));
-                return "[]".to_string();
+        if let Some(mask) = &self.mask {
+            let outside = candidate
+                .corners()
+                .iter()
+                .any(|&(x, y)| !mask.contains(x, y, width, height));
+            if outside {
+                return true;
             }
-        };
-
-        console::log_1(&JsValue::from_str(&format!("词语数量: {}", words.len())));
+        }
 
-        if words.is_empty() {
-            return "[]".to_string();
+        // 与中心空白区域重叠
+        if let Some(radius) = self.options.center_exclusion_radius {
+            let (center_x, center_y) = self.layout_center();
+            if candidate.distance(&Obb::new(center_x, center_y, 0.0, 0.0, 0.0)) < radius {
+                return true;
+            }
         }
 
-        // 找出最大和最小权重
-        let max_weight = words
+        self.placed
             .iter()
-            .map(|w| w.weight)
-            .fold(f64::NEG_INFINITY, f64::max);
-        let min_weight = words.iter().map(|w| w.weight).fold(f64::INFINITY, f64::min);
+            .any(|word| self.collision_obb(word).intersects(candidate))
+    }
 
-        let mut placed_words: Vec<WordPosition> = Vec::new();
+    // 从网格位置出发，以逐步减半的亚像素步长向中心靠拢，
+    // 每一步都按与网格对齐相同的条件确认候选位置可用
+    #[allow(clippy::too_many_arguments)]
+    fn refine_subpixel(
+        &self,
+        word: &WordItem,
+        x: f64,
+        y: f64,
+        center_x: f64,
+        center_y: f64,
+        word_width: f64,
+        word_height: f64,
+        rotation: f64,
+    ) -> (f64, f64) {
+        let (mut x, mut y) = (x, y);
+        let mut step = self.grid_size as f64;
 
-        // 计算中心点
-        let center_x = self.options.width as f64 / 2.0;
-        let center_y = self.options.height as f64 / 2.0;
+        // 限制迭代次数，保证一定会结束
+        for _ in 0..256 {
+            if step < 0.25 {
+                break;
+            }
 
-        for word in words {
-            // 计算字体大小
-            let size = if max_weight == min_weight {
-                self.options.max_size
-            } else {
-                self.options.min_size
-                    + ((word.weight - min_weight) / (max_weight - min_weight))
-                        * (self.options.max_size - self.options.min_size)
-            };
+            let mut moved = false;
+            let dx = center_x - x;
+            let dy = center_y - y;
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance < step {
+                step /= 2.0;
+                continue;
+            }
 
-            // 计算大致的宽度和高度 (这部分在真实实现中需要从canvas获取)
-            // 这里我们使用一个粗略估计
-            let word_width = size * 0.6 * word.text.len() as f64;
-            let word_height = size;
+            // 依次尝试沿中心方向以及两个坐标轴方向移动
+            let directions = [
+                (dx / distance, dy / distance),
+                (dx.signum(), 0.0),
+                (0.0, dy.signum()),
+            ];
+            for (ux, uy) in directions {
+                let (nx, ny) = (x + ux * step, y + uy * step);
+                let next_distance = ((center_x - nx).powi(2) + (center_y - ny).powi(2)).sqrt();
+                if next_distance >= distance {
+                    continue;
+                }
 
-            // 旋转角度
-            let rotation = match word.rotate {
-                Some(r) => r,
-                None if self.options.rotation_range > 0.0 => {
-                    (js_sys::Math::random() * 2.0 - 1.0) * self.options.rotation_range
+                let candidate = Obb::new(nx, ny, word_width, word_height, rotation);
+                if self.position_free(word, &candidate) {
+                    x = candidate.cx;
+                    y = candidate.cy;
+                    moved = true;
+                    break;
                 }
-                None => 0.0,
-            };
+            }
 
-            // 尝试放置单词
-            if let Some((x, y)) =
-                self.find_position_for_word(center_x, center_y, word_width, word_height, rotation)
-            {
-                // 标记网格为已占用
-                self.mark_grid_as_occupied(x, y, word_width, word_height, rotation);
-
-                // 添加到已放置单词
-                placed_words.push(WordPosition {
-                    text: word.text,
-                    weight: word.weight,
-                    x,
-                    y,
-                    rotate: rotation,
-                    color: word.color,
-                    size: Some(size),
-                });
+            if !moved {
+                step /= 2.0;
             }
         }
 
-        // 将结果序列化为JSON
-        serde_json::to_string(&placed_words).unwrap_or_else(|_| "[]".to_string())
+        (x, y)
+    }
+
+    // 把单词中心对齐到最近的网格点；该点有碰撞时按由近到远的顺序尝试周围几圈网格点。
+    // x、y 与返回值都是碰撞包围盒的中心，search 为以单词中心为原点的包围盒
+    fn snap_position(
+        &self,
+        word: &WordItem,
+        x: f64,
+        y: f64,
+        search: &Footprint,
+        rotation: f64,
+    ) -> Option<(f64, f64)> {
+        let step = self.options.snap_grid_size.max(1) as f64;
+        let (word_x, word_y) = (x - search.x, y - search.y);
+        let (base_i, base_j) = ((word_x / step).round(), (word_y / step).round());
+
+        for ring in 0..=SNAP_SEARCH_RINGS {
+            let mut candidates: Vec<(f64, f64)> = Vec::new();
+            for i in -ring..=ring {
+                for j in -ring..=ring {
+                    if i.abs().max(j.abs()) != ring {
+                        continue;
+                    }
+                    let cx = (base_i + i as f64) * step + search.x;
+                    let cy = (base_j + j as f64) * step + search.y;
+                    candidates.push((cx, cy));
+                }
+            }
+            let distance = |&(cx, cy): &(f64, f64)| (cx - x).hypot(cy - y);
+            candidates.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+
+            let found = candidates.into_iter().find(|&(cx, cy)| {
+                let obb = Obb::new(cx, cy, search.width, search.height, rotation);
+                self.position_free(word, &obb)
+            });
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+
+    // 搜索之外的候选位置（网格对齐、亚像素微调、手动试放）是否可用：
+    // 网格、安全区与中心间距、精确包围盒和单词约束都不能冲突
+    pub(crate) fn position_free(&self, word: &WordItem, obb: &Obb) -> bool {
+        !self.check_collision(
+            obb.cx,
+            obb.cy,
+            obb.half_width * 2.0,
+            obb.half_height * 2.0,
+            obb.rotation,
+        ) && !self.check_exact_collision(obb)
+            && !self.violates_word_constraints(word, obb)
+    }
+
+    // 按 Halton 序列在画布上均匀采样候选位置，尝试次数与螺旋搜索相同
+    fn find_position_halton(
+        &self,
+        word: &WordItem,
+        word_width: f64,
+        word_height: f64,
+        rotation: f64,
+    ) -> Option<(f64, f64)> {
+        let width = self.options.width as f64;
+        let height = self.options.height as f64;
+        (1..=1000).find_map(|index| {
+            let x = rng::halton(index, 2) * width;
+            let y = rng::halton(index, 3) * height;
+            let free = !self.check_collision(x, y, word_width, word_height, rotation)
+                && !self.violates_word_constraints(
+                    word,
+                    &Obb::new(x, y, word_width, word_height, rotation),
+                );
+            free.then_some((x, y))
+        })
+    }
+
+    // 估算单词宽度：校准过字体时逐字符累加实测宽度，否则使用粗略估计，并加上字间距
+    fn estimate_word_width(&self, text: &str, size: f64) -> f64 {
+        let gaps = text.chars().count().saturating_sub(1) as f64;
+        // 设置了 canvas 上下文时使用实测宽度
+        let width = self
+            .measured_width(text, size)
+            .unwrap_or_else(|| size * self.text_advance(text));
+        width + self.options.letter_spacing * gaps
+    }
+
+    // 计算径向衰减后的字号比例
+    fn radial_scale(&self, dx: f64, dy: f64) -> f64 {
+        let falloff = self.options.radial_size_falloff;
+        let r_max = self.layout_r_max();
+        if falloff <= 0.0 || r_max <= 0.0 {
+            return 1.0;
+        }
+
+        let r = (dx * dx + dy * dy).sqrt().min(r_max);
+        1.0 - falloff * (r / r_max)
     }
 
     // 查找单词的放置位置
     fn find_position_for_word(
         &self,
+        word: &WordItem,
+        center_x: f64,
+        center_y: f64,
+        word_width: f64,
+        word_height: f64,
+        rotation: f64,
+    ) -> Option<(f64, f64)> {
+        if self.options.equalize_spiral_density {
+            return self.find_position_halton(word, word_width, word_height, rotation);
+        }
+
+        // 对称模式下先寻找镜像位置也空闲的位置，找不到时再按普通方式搜索
+        if self.seek_mirror_room {
+            let position = self.spiral_search(
+                word,
+                center_x,
+                center_y,
+                word_width,
+                word_height,
+                rotation,
+                true,
+            );
+            if position.is_some() {
+                return position;
+            }
+        }
+        self.spiral_search(
+            word,
+            center_x,
+            center_y,
+            word_width,
+            word_height,
+            rotation,
+            false,
+        )
+    }
+
+    // 沿螺旋搜索第一个可用位置；require_mirror_room 为 true 时镜像位置也必须空闲
+    #[allow(clippy::too_many_arguments)]
+    fn spiral_search(
+        &self,
+        word: &WordItem,
         center_x: f64,
         center_y: f64,
         word_width: f64,
         word_height: f64,
         rotation: f64,
+        require_mirror_room: bool,
     ) -> Option<(f64, f64)> {
         // 开始设置螺旋参数
         let mut a = 0.0; // 角度
-        let mut step = 0.1; // 螺旋步长
+        let step = 0.1; // 螺旋步长
         let dt = match self.options.spiral.as_str() {
             "rectangular" => 2.0,
             _ => step, // archimedean或其他
         };
 
-        let mut t = 0.0; // 螺旋参数
+        let mut t: f64 = 0.0; // 螺旋参数
+
+        // 有中心空白区域时从其边缘开始，跳过必然被占用的位置
+        if let Some(radius) = self.options.center_exclusion_radius {
+            a = radius;
+        }
+
+        // 螺旋半径超过有效区域后不再继续尝试
+        let r_limit = self.layout_r_max() + word_width.max(word_height) / 2.0;
 
         // 尝试最多1000个位置
         for _attempt in 0..1000 {
+            if a > r_limit {
+                break;
+            }
+
             let mut x = center_x;
             let mut y = center_y;
 
             // 计算螺旋位置
             if self.options.spiral == "archimedean" {
-                x += a * Math::cos(t);
-                y += a * Math::sin(t);
+                x += a * t.cos();
+                y += a * t.sin();
                 a += step;
             } else if self.options.spiral == "rectangular" {
                 let sign = |n: f64| -> f64 {
@@ -267,21 +2379,33 @@ impl WordCloud {
                     }
                 };
 
-                let k = Math::floor(t / dt) as i32;
+                let k = (t / dt).floor() as i32;
                 if k % 2 == 0 {
-                    x += sign(Math::cos(t)) * a;
-                    y += sign(Math::sin(t)) * a;
+                    x += sign(t.cos()) * a;
+                    y += sign(t.sin()) * a;
                 } else {
-                    x += sign(Math::sin(t)) * a;
-                    y += sign(Math::cos(t)) * a;
+                    x += sign(t.sin()) * a;
+                    y += sign(t.cos()) * a;
                 }
                 a += step;
             }
 
             t += dt;
 
-            // 检查这个位置是否已占用
-            if !self.check_collision(x, y, word_width, word_height, rotation) {
+            // 从右向左的书写方向：镜像螺旋的水平分量
+            if self.options.rtl_spiral_bias {
+                x = 2.0 * center_x - x;
+            }
+
+            // 检查这个位置是否已占用，以及是否满足单词间的约束
+            if !self.check_collision(x, y, word_width, word_height, rotation)
+                && !self.violates_word_constraints(
+                    word,
+                    &Obb::new(x, y, word_width, word_height, rotation),
+                )
+                && (!require_mirror_room
+                    || self.mirror_room(x, y, word_width, word_height, rotation))
+            {
                 return Some((x, y));
             }
         }
@@ -291,6 +2415,9 @@ impl WordCloud {
 
     // 检查碰撞
     fn check_collision(&self, x: f64, y: f64, width: f64, height: f64, rotation: f64) -> bool {
+        self.placement_attempts
+            .set(self.placement_attempts.get().saturating_add(1));
+
         // 简化的碰撞检测 - 在真实实现中需要更复杂的算法
         // 这里我们检查一个旋转的矩形是否与网格中的任何已占用点重叠
 
@@ -338,35 +2465,54 @@ impl WordCloud {
             .fold(f64::NEG_INFINITY, |a, &b| a.max(b));
 
         // 转换为网格坐标
-        let grid_min_x = (min_x as usize / self.grid_size).max(0);
-        let grid_max_x = ((max_x as usize / self.grid_size) + 1).min(self.grid.len() - 1);
-        let grid_min_y = (min_y as usize / self.grid_size).max(0);
-        let grid_max_y = ((max_y as usize / self.grid_size) + 1).min(self.grid[0].len() - 1);
+        let (columns, rows) = self.grid_dimensions();
+        let grid_min_x = min_x as usize / self.grid_size;
+        let grid_max_x = ((max_x as usize / self.grid_size) + 1).min(columns - 1);
+        let grid_min_y = min_y as usize / self.grid_size;
+        let grid_max_y = ((max_y as usize / self.grid_size) + 1).min(rows - 1);
 
-        // 检查所有覆盖的网格单元是否有碰撞
-        for i in grid_min_x..=grid_max_x {
-            for j in grid_min_y..=grid_max_y {
-                if i < self.grid.len() && j < self.grid[i].len() && self.grid[i][j] {
-                    return true; // 碰撞
+        // 检查所有覆盖的网格单元是否有碰撞，pixel 模式只检查字形像素覆盖的单元
+        if let Some(sprite) = &self.active_sprite {
+            if self
+                .sprite_cells(sprite, x, y)
+                .into_iter()
+                .any(|(i, j)| self.grid_cell(i, j))
+            {
+                return true;
+            }
+        } else {
+            for i in grid_min_x..=grid_max_x {
+                for j in grid_min_y..=grid_max_y {
+                    if self.grid_cell(i, j) {
+                        return true; // 碰撞
+                    }
                 }
             }
         }
 
-        // 检查是否超出边界
-        if min_x < 0.0
-            || max_x > self.options.width as f64
-            || min_y < 0.0
-            || max_y > self.options.height as f64
-        {
+        // 与已放置单词的中心过近
+        if self.too_close_to_placed(x, y) {
+            return true;
+        }
+
+        // 检查是否超出安全区边界
+        let (safe_min_x, safe_min_y, safe_max_x, safe_max_y) = self.safe_bounds();
+        if min_x < safe_min_x || max_x > safe_max_x || min_y < safe_min_y || max_y > safe_max_y {
             return true; // 边界碰撞
         }
 
         false // 没有碰撞
     }
 
-    // 标记网格为已占用
-    fn mark_grid_as_occupied(&mut self, x: f64, y: f64, width: f64, height: f64, rotation: f64) {
-        // 与check_collision类似的逻辑，但是标记为已占用
+    // mark_grid_as_occupied 按包围盒标记的网格范围：(最小列, 最大列, 最小行, 最大行)
+    pub(crate) fn grid_cell_range(
+        &self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        rotation: f64,
+    ) -> (usize, usize, usize, usize) {
         let sin_rot = rotation.sin();
         let cos_rot = rotation.cos();
 
@@ -406,18 +2552,346 @@ impl WordCloud {
             .map(|(_, y)| y)
             .fold(f64::NEG_INFINITY, |a, &b| a.max(b));
 
-        let grid_min_x = (min_x as usize / self.grid_size).max(0);
-        let grid_max_x = ((max_x as usize / self.grid_size) + 1).min(self.grid.len() - 1);
-        let grid_min_y = (min_y as usize / self.grid_size).max(0);
-        let grid_max_y = ((max_y as usize / self.grid_size) + 1).min(self.grid[0].len() - 1);
+        let (columns, rows) = self.grid_dimensions();
+        let grid_min_x = min_x as usize / self.grid_size;
+        let grid_max_x = ((max_x as usize / self.grid_size) + 1).min(columns - 1);
+        let grid_min_y = min_y as usize / self.grid_size;
+        let grid_max_y = ((max_y as usize / self.grid_size) + 1).min(rows - 1);
+        (grid_min_x, grid_max_x, grid_min_y, grid_max_y)
+    }
+
+    // 标记网格为已占用
+    fn mark_grid_as_occupied(&mut self, x: f64, y: f64, width: f64, height: f64, rotation: f64) {
+        // 与check_collision类似的逻辑，但是标记为已占用
+        let (grid_min_x, grid_max_x, grid_min_y, grid_max_y) =
+            self.grid_cell_range(x, y, width, height, rotation);
+
+        if let Some(sprite) = &self.active_sprite {
+            for (i, j) in self.sprite_cells(sprite, x, y) {
+                self.occupy_grid_cell(i, j);
+            }
+            return;
+        }
 
         // 标记所有覆盖的网格单元为已占用
         for i in grid_min_x..=grid_max_x {
             for j in grid_min_y..=grid_max_y {
-                if i < self.grid.len() && j < self.grid[i].len() {
-                    self.grid[i][j] = true;
-                }
+                self.occupy_grid_cell(i, j);
+            }
+        }
+    }
+}
+
+// 测试共用的实例：400x300 画布，字号 10..40，网格 4 像素，固定随机种子
+#[cfg(test)]
+pub(crate) fn test_cloud() -> WordCloud {
+    let mut cloud = WordCloud::new(
+        400,
+        300,
+        "sans-serif".to_string(),
+        "normal".to_string(),
+        10.0,
+        40.0,
+        4,
+    );
+    cloud.set_seed(1);
+    cloud
+}
+
+// 按权重 n..1 排列的 n 个单词
+#[cfg(test)]
+pub(crate) fn weighted_words(n: usize) -> String {
+    let words: Vec<serde_json::Value> = (0..n)
+        .map(|i| serde_json::json!({"text": format!("w{}", i), "weight": (n - i) as f64}))
+        .collect();
+    serde_json::to_string(&words).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // n 个等权重的单词
+    fn equal_words(n: usize) -> String {
+        let words: Vec<serde_json::Value> = (0..n)
+            .map(|i| serde_json::json!({"text": format!("w{}", i), "weight": 1.0}))
+            .collect();
+        serde_json::to_string(&words).unwrap()
+    }
+
+    fn effective_grid_size(cloud: &WordCloud) -> u64 {
+        let options: serde_json::Value = serde_json::from_str(&cloud.get_options()).unwrap();
+        options["effective_grid_size"].as_u64().unwrap()
+    }
+
+    // 已放置单词的精确包围盒两两不相交
+    fn assert_no_overlaps(cloud: &WordCloud) {
+        let boxes: Vec<Obb> = cloud.placed.iter().map(|w| cloud.word_obb(w)).collect();
+        for (i, a) in boxes.iter().enumerate() {
+            for b in &boxes[i + 1..] {
+                assert!(!a.intersects(b));
             }
         }
     }
+
+    #[test]
+    fn tiny_sizes_warn_and_refine_the_grid_without_overlaps() {
+        let mut cloud = WordCloud::new(
+            400,
+            300,
+            "sans-serif".to_string(),
+            "normal".to_string(),
+            2.0,
+            40.0,
+            4,
+        );
+        cloud.set_seed(1);
+        cloud.generate_layout(weighted_words(40));
+
+        assert!(cloud
+            .warnings
+            .iter()
+            .any(|w| w.starts_with("min_size (2) is below min_legible_size")));
+        assert_eq!(effective_grid_size(&cloud), 1);
+        assert!(cloud.placed.len() > 20);
+        assert_no_overlaps(&cloud);
+    }
+
+    #[test]
+    fn grid_refinement_stops_at_the_largest_size_meeting_the_two_cell_rule() {
+        let mut cloud = WordCloud::new(
+            400,
+            300,
+            "sans-serif".to_string(),
+            "normal".to_string(),
+            6.0,
+            40.0,
+            8,
+        );
+        cloud.generate_layout(weighted_words(10));
+        // 最小单词高 6 像素，跨越 2 个单元的最大网格为 3
+        assert_eq!(effective_grid_size(&cloud), 3);
+        assert!(!cloud
+            .warnings
+            .iter()
+            .any(|w| w.contains("min_legible_size")));
+    }
+
+    #[test]
+    fn radial_falloff_shrinks_equal_words_with_distance() {
+        let mut cloud = test_cloud();
+        cloud.set_radial_size_falloff(0.5);
+        cloud.generate_layout(equal_words(20));
+        assert!(cloud.placed.len() > 5);
+
+        let (cx, cy) = cloud.layout_center();
+        let mut by_distance: Vec<(f64, f64)> = cloud
+            .placed
+            .iter()
+            .map(|word| ((word.x - cx).hypot(word.y - cy), word.size.unwrap()))
+            .collect();
+        by_distance.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for pair in by_distance.windows(2) {
+            assert!(pair[1].1 <= pair[0].1 + 1e-9, "{:?}", pair);
+        }
+        // 最外侧的单词明显小于最大字号，但不低于 min_size
+        let (_, outermost) = by_distance[by_distance.len() - 1];
+        assert!(outermost < 40.0);
+        assert!(outermost >= 10.0);
+    }
+
+    // 第一个单词在最大字号下比 400 像素宽的画布还宽
+    const OVERSIZED_WORDS: &str = r#"[
+        {"text": "extraordinarilylongheadline", "weight": 10, "rotate": 0},
+        {"text": "small", "weight": 1}
+    ]"#;
+
+    #[test]
+    fn oversized_shrink_policy_fits_the_word_before_placement() {
+        let mut cloud = test_cloud();
+        cloud.generate_layout(OVERSIZED_WORDS.to_string());
+        assert_eq!(cloud.layout_stats.oversized, 1);
+        assert_eq!(cloud.placed.len(), 2);
+        let word = &cloud.placed[0];
+        assert_eq!(word.text, "extraordinarilylongheadline");
+        assert!(word.size.unwrap() < 40.0);
+        assert!(cloud.word_box(word).0 <= cloud.usable_area().0);
+        // 缩小发生在螺旋搜索之前，第一个候选位置（布局中心）即可放下
+        assert_eq!((word.x, word.y), cloud.layout_center());
+    }
+
+    #[test]
+    fn oversized_skip_policy_records_the_reason() {
+        let mut cloud = test_cloud();
+        cloud.set_oversized_policy("skip".to_string());
+        cloud.generate_layout(OVERSIZED_WORDS.to_string());
+        assert_eq!(cloud.layout_stats.oversized, 1);
+        assert_eq!(cloud.placed.len(), 1);
+        assert_eq!(cloud.placed[0].text, "small");
+        assert_eq!(cloud.skipped.len(), 1);
+        assert_eq!(cloud.skipped[0].text, "extraordinarilylongheadline");
+        assert_eq!(cloud.skipped[0].reason, "oversized");
+    }
+
+    #[test]
+    fn oversized_error_policy_names_the_word_and_dimension() {
+        let mut cloud = test_cloud();
+        cloud.set_oversized_policy("error".to_string());
+        assert_eq!(cloud.generate_layout(OVERSIZED_WORDS.to_string()), "[]");
+        assert_eq!(cloud.layout_stats.oversized, 1);
+        assert!(cloud.placed.is_empty());
+        let warning = cloud.warnings.last().unwrap();
+        assert!(warning.contains("\"extraordinarilylongheadline\""));
+        assert!(warning.contains("usable width"));
+    }
+
+    // 以画布面积归一化的已放置单词包围盒面积
+    fn fill_ratio(cloud: &WordCloud) -> f64 {
+        let area: f64 = cloud
+            .placed
+            .iter()
+            .map(|word| {
+                let (width, height) = cloud.word_box(word);
+                width * height
+            })
+            .sum();
+        area / (cloud.options.width as f64 * cloud.options.height as f64)
+    }
+
+    fn jittered_layout(range: f64, snap: f64) -> WordCloud {
+        let mut cloud = test_cloud();
+        cloud.options.max_size = 16.0;
+        cloud.set_rotation_unit("degrees".to_string());
+        cloud.set_rotation_range(range);
+        cloud.set_rotation_snap(snap);
+        cloud.generate_layout(weighted_words(150));
+        cloud
+    }
+
+    #[test]
+    fn rotation_snap_restores_zero_rotation_packing() {
+        let baseline = jittered_layout(0.0, 0.0);
+        let snapped = jittered_layout(2.0, 5.0);
+
+        let (baseline_fill, snapped_fill) = (fill_ratio(&baseline), fill_ratio(&snapped));
+        assert!(baseline_fill > 0.0);
+        assert!((snapped_fill - baseline_fill).abs() <= baseline_fill * 0.02);
+        assert_eq!(snapped.placed.len(), baseline.placed.len());
+
+        // 吸附只作用于碰撞检测，输出仍保留微小旋转
+        let layout: Vec<serde_json::Value> = serde_json::from_str(&snapped.layout_json()).unwrap();
+        assert!(!layout.is_empty());
+        assert!(layout
+            .iter()
+            .all(|word| word["rotate"].as_f64().unwrap() != 0.0));
+    }
+
+    const CASING_VARIANTS: &str = r#"[
+        {"text": "iphone", "weight": 1},
+        {"text": "iPhone", "weight": 5},
+        {"text": "IPHONE", "weight": 2},
+        {"text": "élan", "weight": 1},
+        {"text": "IPHONE", "weight": 1},
+        {"text": "ÉLAN", "weight": 3}
+    ]"#;
+
+    // 按策略合并后输出的 (文本, 被舍弃的写法, 权重)，按合并后的权重从高到低排列
+    fn merged(strategy: &str) -> Vec<(String, Vec<String>, f64)> {
+        let mut cloud = test_cloud();
+        cloud.set_deduplication_case_insensitive(true);
+        cloud.set_casing_strategy(strategy.to_string(), true);
+        let layout: Vec<serde_json::Value> =
+            serde_json::from_str(&cloud.generate_layout(CASING_VARIANTS.to_string())).unwrap();
+        let mut merged: Vec<(String, Vec<String>, f64)> = layout
+            .iter()
+            .map(|word| {
+                let variants = word["variants"]
+                    .as_array()
+                    .map(|variants| {
+                        variants
+                            .iter()
+                            .map(|v| v.as_str().unwrap().to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (
+                    word["text"].as_str().unwrap().to_string(),
+                    variants,
+                    word["weight"].as_f64().unwrap(),
+                )
+            })
+            .collect();
+        merged.sort_by(|a, b| b.2.total_cmp(&a.2));
+        merged
+    }
+
+    #[test]
+    fn casing_strategies_pick_the_emitted_variant() {
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let expect = |strategy: &str, phone: (&str, &[&str]), elan: (&str, &[&str])| {
+            assert_eq!(
+                merged(strategy),
+                vec![
+                    (phone.0.to_string(), strings(phone.1), 9.0),
+                    (elan.0.to_string(), strings(elan.1), 4.0),
+                ],
+                "strategy {}",
+                strategy
+            );
+        };
+
+        expect(
+            "first",
+            ("iphone", &["iPhone", "IPHONE"]),
+            ("élan", &["ÉLAN"]),
+        );
+        expect(
+            "most_frequent",
+            ("IPHONE", &["iphone", "iPhone"]),
+            ("élan", &["ÉLAN"]),
+        );
+        expect(
+            "preserve_max_weight",
+            ("iPhone", &["iphone", "IPHONE"]),
+            ("ÉLAN", &["élan"]),
+        );
+        expect(
+            "title",
+            ("Iphone", &["iphone", "iPhone", "IPHONE"]),
+            ("Élan", &["élan", "ÉLAN"]),
+        );
+    }
+
+    #[test]
+    fn casing_variants_are_omitted_unless_requested() {
+        let mut cloud = test_cloud();
+        cloud.set_deduplication_case_insensitive(true);
+        cloud.set_casing_strategy("most_frequent".to_string(), false);
+        let layout = cloud.generate_layout(CASING_VARIANTS.to_string());
+        assert!(layout.contains(r#""text":"IPHONE""#));
+        assert!(!layout.contains("variants"));
+    }
+
+    #[test]
+    fn json_words_without_text_are_rejected_with_their_index() {
+        let error = |json: &str| parse_words_json(json).err().unwrap();
+        assert_eq!(
+            error(r#"[{"text": "a", "weight": 1}, {"text": "", "weight": 2}]"#),
+            "empty `text` at index 1"
+        );
+        assert_eq!(
+            error(r#"[{"text": "a", "weight": 1}, {"weight": 2}]"#),
+            "invalid word at index 1: missing field `text`"
+        );
+        // 组合词可以省略 text
+        let compound = r#"[{"parts": [{"text": "salt", "weight": 1}], "weight": 1}]"#;
+        assert_eq!(parse_words_json(compound).unwrap().len(), 1);
+
+        // generate_layout 与无法解析的输入一样返回空布局
+        let mut cloud = test_cloud();
+        cloud.generate_layout(weighted_words(3));
+        let layout = cloud.generate_layout(r#"[{"text": "a", "weight": 1}, {"text": ""}]"#.into());
+        assert_eq!(layout, "[]");
+        assert!(cloud.placed.is_empty());
+    }
 }
@@ -0,0 +1,360 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+use crate::env::log;
+use crate::{LayoutEngine, WordCloud, WordPosition};
+
+// 字形填充布局的结果
+#[derive(Serialize)]
+struct GlyphFillResult<'a> {
+    shape_word: &'a str,
+    words: &'a [WordPosition],
+}
+
+// 形状遮罩：true 表示允许放置单词的区域
+#[derive(Clone)]
+pub(crate) struct Mask {
+    data: Vec<bool>,
+    width: usize,
+    height: usize,
+    // 遮罩内区域的质心（画布坐标）
+    pub(crate) centroid: (f64, f64),
+    // 遮罩内离质心最远的距离（画布坐标）
+    pub(crate) r_max: f64,
+}
+
+impl Mask {
+    // 根据像素数据构建遮罩，并计算质心和最大半径
+    pub(crate) fn new(
+        data: Vec<bool>,
+        width: usize,
+        height: usize,
+        canvas_width: f64,
+        canvas_height: f64,
+    ) -> Mask {
+        let scale_x = canvas_width / width.max(1) as f64;
+        let scale_y = canvas_height / height.max(1) as f64;
+
+        let mut sum_x = 0.0;
+        let mut sum_y = 0.0;
+        let mut count = 0usize;
+        for (index, _) in data.iter().enumerate().filter(|(_, &inside)| inside) {
+            sum_x += ((index % width) as f64 + 0.5) * scale_x;
+            sum_y += ((index / width) as f64 + 0.5) * scale_y;
+            count += 1;
+        }
+
+        let centroid = if count > 0 {
+            (sum_x / count as f64, sum_y / count as f64)
+        } else {
+            (canvas_width / 2.0, canvas_height / 2.0)
+        };
+
+        let r_max = data
+            .iter()
+            .enumerate()
+            .filter(|(_, &inside)| inside)
+            .map(|(index, _)| {
+                let dx = ((index % width) as f64 + 0.5) * scale_x - centroid.0;
+                let dy = ((index / width) as f64 + 0.5) * scale_y - centroid.1;
+                (dx * dx + dy * dy).sqrt()
+            })
+            .fold(0.0, f64::max);
+
+        Mask {
+            data,
+            width,
+            height,
+            centroid,
+            r_max,
+        }
+    }
+
+    // 判断画布坐标 (x, y) 是否位于遮罩内
+    pub(crate) fn contains(&self, x: f64, y: f64, canvas_width: f64, canvas_height: f64) -> bool {
+        if x < 0.0 || y < 0.0 || x >= canvas_width || y >= canvas_height {
+            return false;
+        }
+        let mx = ((x / canvas_width * self.width as f64) as usize).min(self.width - 1);
+        let my = ((y / canvas_height * self.height as f64) as usize).min(self.height - 1);
+        self.data[my * self.width + mx]
+    }
+
+    // 画布尺寸改变后按新尺寸重新计算质心和最大半径
+    pub(crate) fn resize(&mut self, canvas_width: f64, canvas_height: f64) {
+        let data = std::mem::take(&mut self.data);
+        *self = Mask::new(data, self.width, self.height, canvas_width, canvas_height);
+    }
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置形状遮罩，data 为按行排列的像素值，非 0 表示允许放置
+    #[wasm_bindgen]
+    pub fn set_mask(&mut self, data: Vec<u8>, mask_width: u32, mask_height: u32) -> bool {
+        let width = mask_width as usize;
+        let height = mask_height as usize;
+        if width == 0 || height == 0 || data.len() < width * height {
+            return false;
+        }
+
+        let data = data[..width * height].iter().map(|&v| v != 0).collect();
+        self.mask = Some(Mask::new(
+            data,
+            width,
+            height,
+            self.options.width as f64,
+            self.options.height as f64,
+        ));
+        self.reset_grid()
+    }
+
+    // 清除形状遮罩
+    #[wasm_bindgen]
+    pub fn clear_mask(&mut self) -> bool {
+        self.mask = None;
+        self.reset_grid()
+    }
+
+    // 字形填充：把 shape_word 以大号字体绘制到离屏 canvas，
+    // 用其字形像素作为遮罩，再在字形内部布局 fill_words_json。
+    // 没有 DOM canvas 时以错误拒绝
+    #[wasm_bindgen]
+    pub fn generate_glyph_fill_layout(
+        &mut self,
+        shape_word: String,
+        fill_words_json: String,
+        ctx: &CanvasRenderingContext2d,
+    ) -> Result<String, JsValue> {
+        self.require_canvas()?;
+        let glyph_mask = match self.render_glyph_mask(&shape_word, ctx) {
+            Some(mask) => mask,
+            None => {
+                log("无法创建离屏 canvas，字形填充失败");
+                return Ok("{}".to_string());
+            }
+        };
+
+        // 布局期间临时替换遮罩，结束后恢复原有遮罩
+        let previous = self.mask.replace(glyph_mask);
+        self.generate_layout(fill_words_json);
+        self.mask = previous;
+
+        let result = GlyphFillResult {
+            shape_word: &shape_word,
+            words: &self.emit_layout(&self.placed),
+        };
+        Ok(serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string()))
+    }
+
+    // 手动指定螺旋中心，优先于遮罩质心
+    #[wasm_bindgen]
+    pub fn set_center(&mut self, x: f64, y: f64) {
+        self.center = Some((x, y));
+    }
+
+    // 恢复默认的螺旋中心
+    #[wasm_bindgen]
+    pub fn clear_center(&mut self) {
+        self.center = None;
+    }
+
+    // 设置中心空白区域的半径（像素），螺旋中心周围该半径内不放置单词，形成环形词云；
+    // 半径不大于 0 时取消
+    #[wasm_bindgen]
+    pub fn set_center_exclusion_radius(&mut self, radius: f64) {
+        self.options.center_exclusion_radius = Some(radius).filter(|&r| r > 0.0);
+    }
+}
+
+impl WordCloud {
+    // 在与画布同尺寸的离屏 canvas 上绘制单词，按 alpha 通道生成遮罩
+    pub(crate) fn render_glyph_mask(
+        &self,
+        text: &str,
+        ctx: &CanvasRenderingContext2d,
+    ) -> Option<Mask> {
+        let width = self.options.width;
+        let height = self.options.height;
+
+        let document = ctx.canvas()?.owner_document()?;
+        let canvas: HtmlCanvasElement = document.create_element("canvas").ok()?.dyn_into().ok()?;
+        canvas.set_width(width);
+        canvas.set_height(height);
+        let offscreen: CanvasRenderingContext2d =
+            canvas.get_context("2d").ok()??.dyn_into().ok()?;
+
+        // 先以 100px 测量，再缩放到画布的 95%
+        let font = |size: f64| {
+            format!(
+                "{} {}px {}",
+                self.options.font_weight, size, self.options.font_family
+            )
+        };
+        offscreen.set_font(&font(100.0));
+        let measured_width = offscreen.measure_text(text).ok()?.width().max(1.0);
+        let size = (100.0 * width as f64 * 0.95 / measured_width).min(height as f64 * 0.95);
+
+        offscreen.set_font(&font(size));
+        offscreen.set_fill_style_str("#000000");
+        offscreen.set_text_align("center");
+        offscreen.set_text_baseline("middle");
+        offscreen
+            .fill_text(text, width as f64 / 2.0, height as f64 / 2.0)
+            .ok()?;
+
+        let pixels = offscreen
+            .get_image_data(0.0, 0.0, width as f64, height as f64)
+            .ok()?
+            .data();
+        let data = pixels.chunks(4).map(|rgba| rgba[3] > 128).collect();
+
+        Some(Mask::new(
+            data,
+            width as usize,
+            height as usize,
+            width as f64,
+            height as f64,
+        ))
+    }
+}
+
+impl LayoutEngine {
+    // 将遮罩外的网格单元标记为已占用
+    pub(crate) fn apply_mask_to_grid(&mut self) {
+        let mask = match &self.mask {
+            Some(mask) => mask,
+            None => return,
+        };
+
+        let canvas_width = self.options.width as f64;
+        let canvas_height = self.options.height as f64;
+        let grid_size = self.grid_size as f64;
+
+        let (columns, rows) = self.grid_dimensions();
+        let outside: Vec<(usize, usize)> = (0..columns)
+            .flat_map(|i| (0..rows).map(move |j| (i, j)))
+            .filter(|&(i, j)| {
+                let x = (i as f64 + 0.5) * grid_size;
+                let y = (j as f64 + 0.5) * grid_size;
+                !mask.contains(x, y, canvas_width, canvas_height)
+            })
+            .collect();
+        for (i, j) in outside {
+            self.occupy_grid_cell(i, j);
+        }
+    }
+
+    // 把螺旋中心周围 center_exclusion_radius 内的网格单元标记为已占用
+    pub(crate) fn apply_center_exclusion(&mut self) {
+        let radius = match self.options.center_exclusion_radius {
+            Some(radius) => radius,
+            None => return,
+        };
+
+        let (center_x, center_y) = self.layout_center();
+        let grid_size = self.grid_size as f64;
+        let (columns, rows) = self.grid_dimensions();
+        for i in 0..columns {
+            for j in 0..rows {
+                let x = (i as f64 + 0.5) * grid_size;
+                let y = (j as f64 + 0.5) * grid_size;
+                if (x - center_x).hypot(y - center_y) < radius {
+                    self.occupy_grid_cell(i, j);
+                }
+            }
+        }
+    }
+
+    // 螺旋中心：手动指定 > 遮罩质心 > 安全区中心
+    pub(crate) fn layout_center(&self) -> (f64, f64) {
+        if let Some(center) = self.center {
+            return center;
+        }
+        match &self.mask {
+            Some(mask) => mask.centroid,
+            None => {
+                let (min_x, min_y, max_x, max_y) = self.safe_bounds();
+                ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0)
+            }
+        }
+    }
+
+    // 螺旋的最大有效半径，超过后不再继续尝试
+    pub(crate) fn layout_r_max(&self) -> f64 {
+        if let (None, Some(mask)) = (self.center, &self.mask) {
+            return mask.r_max;
+        }
+
+        // 从中心到画布最远角的距离
+        let (center_x, center_y) = self.layout_center();
+        let width = self.options.width as f64;
+        let height = self.options.height as f64;
+        let dx = center_x.max(width - center_x);
+        let dy = center_y.max(height - center_y);
+        (dx * dx + dy * dy).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_cloud;
+
+    // 4x4 的遮罩，只有右上角的 2x2 区域允许放置
+    fn top_right_mask() -> Vec<u8> {
+        let mut data = vec![0; 16];
+        for row in 0..2 {
+            for col in 2..4 {
+                data[row * 4 + col] = 1;
+            }
+        }
+        data
+    }
+
+    fn words(n: usize) -> String {
+        let words: Vec<serde_json::Value> = (0..n)
+            .map(|i| serde_json::json!({"text": format!("w{}", i), "weight": (n - i) as f64}))
+            .collect();
+        serde_json::to_string(&words).unwrap()
+    }
+
+    #[test]
+    fn default_center_lies_inside_an_off_center_mask() {
+        let mut cloud = test_cloud();
+        assert!(cloud.set_mask(top_right_mask(), 4, 4));
+        let (x, y) = cloud.layout_center();
+        assert!((200.0..=400.0).contains(&x));
+        assert!((0.0..=150.0).contains(&y));
+        assert!(cloud.layout_r_max() < 200.0);
+
+        // 画布尺寸改变后质心随之缩放
+        cloud.set_size(800, 600);
+        let (x, y) = cloud.layout_center();
+        assert!((x - 600.0).abs() < 1e-9 && (y - 150.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn mask_centroid_places_far_more_words_than_canvas_center() {
+        let mut cloud = test_cloud();
+        cloud.set_mask(top_right_mask(), 4, 4);
+        cloud.generate_layout(words(30));
+        let centroid = cloud.last_stats.clone();
+
+        cloud.set_center(200.0, 150.0);
+        cloud.generate_layout(words(30));
+        let canvas_center = cloud.last_stats.clone();
+
+        assert!(centroid.placed >= 2 * canvas_center.placed.max(1));
+        let per_word = |stats: &crate::stats::PlacementStats| {
+            stats.total_attempts as f64 / stats.placed.max(1) as f64
+        };
+        assert!(
+            per_word(&centroid) * 2.0 < per_word(&canvas_center),
+            "{} vs {}",
+            per_word(&centroid),
+            per_word(&canvas_center)
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::geometry::Obb;
+use crate::{LayoutEngine, WordCloud};
+
+// 熵计算时每个方向划分的区块数
+const ENTROPY_TILES: usize = 4;
+// 旋转多样性按该数量的不同角度达到满分，旋转角度取整到度后统计
+const ROTATION_DIVERSITY_BUCKETS: usize = 8;
+
+// 密度图数据结构
+#[derive(Serialize)]
+pub(crate) struct DensityMap {
+    cols: usize,
+    rows: usize,
+    // density[row][col] 为该区块内被占用网格的比例
+    pub(crate) density: Vec<Vec<f64>>,
+}
+
+// 单词间距离矩阵（仅上三角）
+#[derive(Serialize)]
+struct PairDistances<'a> {
+    words: Vec<&'a str>,
+    // distances[i][k] 为单词 i 与单词 i + 1 + k 中心点之间的距离
+    distances: Vec<Vec<f64>>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 按区块统计网格占用比例，返回 JSON
+    #[wasm_bindgen]
+    pub fn get_word_density_map_json(&self, cols: u32, rows: u32) -> String {
+        let map = self.density_map(cols.max(1) as usize, rows.max(1) as usize);
+        serde_json::to_string(&map).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    // 计算网格占用分布的归一化香农熵，范围 [0, 1]
+    // 数值越高说明单词分布越均匀，越低说明越集中
+    #[wasm_bindgen]
+    pub fn compute_word_cloud_entropy(&self) -> f64 {
+        let map = self.density_map(ENTROPY_TILES, ENTROPY_TILES);
+        let values: Vec<f64> = map.density.iter().flatten().copied().collect();
+
+        let total: f64 = values.iter().sum();
+        let n_tiles = values.len();
+        if total <= 0.0 || n_tiles < 2 {
+            return 0.0;
+        }
+
+        let entropy: f64 = values
+            .iter()
+            .filter(|&&v| v > 0.0)
+            .map(|&v| {
+                let p = v / total;
+                -p * p.log2()
+            })
+            .sum();
+
+        entropy / (n_tiles as f64).log2()
+    }
+
+    // 视觉复杂度评分，范围 [0, 1]，用于比较不同配置下的效果：
+    // 占用率 * 0.4 + 放置率 * 0.3 + (1 - 归一化字号标准差) * 0.15 + 旋转多样性 * 0.15
+    #[wasm_bindgen]
+    pub fn compute_visual_complexity_score(&self) -> f64 {
+        let (columns, rows) = self.grid_dimensions();
+        let total_cells = columns * rows;
+        let occupied_cells = self.occupied_grid_cells();
+        let occupancy = if total_cells == 0 {
+            0.0
+        } else {
+            occupied_cells as f64 / total_cells as f64
+        };
+
+        let attempted = self.layout_stats.placed + self.layout_stats.skipped;
+        let placement_rate = if attempted == 0 {
+            0.0
+        } else {
+            self.layout_stats.placed as f64 / attempted as f64
+        };
+
+        0.4 * occupancy
+            + 0.3 * placement_rate
+            + 0.15 * (1.0 - self.size_spread())
+            + 0.15 * self.rotation_diversity()
+    }
+
+    // 用分离轴定理两两检查已放置单词的碰撞包围盒，没有任何重叠时返回 true
+    #[wasm_bindgen]
+    pub fn is_overlap_free(&self) -> bool {
+        let boxes: Vec<Obb> = self
+            .placed
+            .iter()
+            .map(|word| self.collision_obb(word))
+            .collect();
+        boxes
+            .iter()
+            .enumerate()
+            .all(|(i, a)| boxes[i + 1..].iter().all(|b| !a.intersects(b)))
+    }
+
+    // 返回所有已放置单词两两之间的中心距离，只包含上三角部分
+    #[wasm_bindgen]
+    pub fn get_word_pair_distances(&self) -> String {
+        let words = self.placed.iter().map(|w| w.text.as_str()).collect();
+        let distances = self
+            .placed
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                self.placed[i + 1..]
+                    .iter()
+                    .map(|b| ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt())
+                    .collect()
+            })
+            .collect();
+
+        let result = PairDistances { words, distances };
+        serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+impl LayoutEngine {
+    // 字号标准差除以 [min_size, max_size] 区间可能的最大标准差（区间长度的一半）
+    fn size_spread(&self) -> f64 {
+        let sizes: Vec<f64> = self.placed.iter().filter_map(|w| w.size).collect();
+        let max_std_dev = (self.options.max_size - self.options.min_size) / 2.0;
+        if sizes.len() < 2 || max_std_dev <= 0.0 {
+            return 0.0;
+        }
+        let mean = sizes.iter().sum::<f64>() / sizes.len() as f64;
+        let variance = sizes.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / sizes.len() as f64;
+        (variance.sqrt() / max_std_dev).min(1.0)
+    }
+
+    // 旋转角度分布的香农熵，以 ROTATION_DIVERSITY_BUCKETS 个均匀分布的角度为满分。
+    // 按角度有序累加，使相同布局得到逐位相同的结果
+    fn rotation_diversity(&self) -> f64 {
+        let mut counts: BTreeMap<i64, usize> = BTreeMap::new();
+        for word in &self.placed {
+            *counts
+                .entry(word.rotate.to_degrees().round() as i64)
+                .or_default() += 1;
+        }
+        let n = self.placed.len().min(ROTATION_DIVERSITY_BUCKETS);
+        if n < 2 {
+            return 0.0;
+        }
+        let total = self.placed.len() as f64;
+        let entropy: f64 = counts
+            .values()
+            .map(|&c| {
+                let p = c as f64 / total;
+                -p * p.log2()
+            })
+            .sum();
+        (entropy / (n as f64).log2()).min(1.0)
+    }
+
+    // 将网格均匀切分为 cols x rows 个区块并计算占用比例
+    pub(crate) fn density_map(&self, cols: usize, rows: usize) -> DensityMap {
+        let (grid_width, grid_height) = self.grid_dimensions();
+
+        let mut occupied = vec![vec![0usize; cols]; rows];
+        let mut cells = vec![vec![0usize; cols]; rows];
+
+        for i in 0..grid_width {
+            let col = (i * cols / grid_width.max(1)).min(cols - 1);
+            for j in 0..grid_height {
+                let row = (j * rows / grid_height.max(1)).min(rows - 1);
+                cells[row][col] += 1;
+                if self.grid_cell(i, j) {
+                    occupied[row][col] += 1;
+                }
+            }
+        }
+
+        let density = occupied
+            .iter()
+            .zip(cells.iter())
+            .map(|(occupied_row, cells_row)| {
+                occupied_row
+                    .iter()
+                    .zip(cells_row.iter())
+                    .map(|(&o, &c)| if c == 0 { 0.0 } else { o as f64 / c as f64 })
+                    .collect()
+            })
+            .collect();
+
+        DensityMap {
+            cols,
+            rows,
+            density,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_4;
+
+    use crate::compare::parse_layout;
+    use crate::{test_cloud, weighted_words, WordCloud};
+
+    // 用给定的 (x, y) 放置两个旋转 45° 的长单词，绕过放置过程直接写入 placed
+    fn diagonal_pair(cloud: &mut WordCloud, second: (f64, f64)) {
+        let layout = serde_json::json!([
+            {"text": "diagonalword", "weight": 1, "x": 100, "y": 100, "rotate": FRAC_PI_4, "size": 10},
+            {"text": "diagonalword", "weight": 1, "x": second.0, "y": second.1, "rotate": FRAC_PI_4, "size": 10}
+        ]);
+        cloud.placed = parse_layout(&layout.to_string())
+            .into_iter()
+            .map(|word| cloud.import_position(word))
+            .collect();
+    }
+
+    #[test]
+    fn generated_layout_is_overlap_free() {
+        let mut cloud = test_cloud();
+        cloud.options.rotation_range = FRAC_PI_4;
+        cloud.generate_layout(weighted_words(60));
+        assert!(cloud.placed.len() > 5);
+        assert!(cloud.is_overlap_free());
+
+        // 把一个单词挪到另一个单词上
+        let mut moved = cloud.placed[1].clone();
+        moved.x = cloud.placed[0].x;
+        moved.y = cloud.placed[0].y;
+        cloud.placed.push(moved);
+        assert!(!cloud.is_overlap_free());
+    }
+
+    #[test]
+    fn rotated_boxes_with_overlapping_bounds_are_separated_by_sat() {
+        let mut cloud = test_cloud();
+        // 沿垂直于单词方向错开 30 像素：轴对齐包围盒重叠，旋转后的矩形不重叠
+        let offset = 30.0 * FRAC_PI_4.sin();
+        diagonal_pair(&mut cloud, (100.0 - offset, 100.0 + offset));
+        let (a, b) = (
+            cloud.collision_obb(&cloud.placed[0]).bounds(),
+            cloud.collision_obb(&cloud.placed[1]).bounds(),
+        );
+        assert!(a.0 < b.2 && b.0 < a.2 && a.1 < b.3 && b.1 < a.3);
+        assert!(cloud.is_overlap_free());
+
+        // 沿单词方向错开同样的距离则互相重叠
+        diagonal_pair(&mut cloud, (100.0 + offset, 100.0 + offset));
+        assert!(!cloud.is_overlap_free());
+    }
+}
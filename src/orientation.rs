@@ -0,0 +1,154 @@
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{LayoutContext, LayoutEngine, MeasuredWord, WordCloud, WordItem};
+
+// 在螺旋中心周围取样的方向数和半径档位（以单词长边为单位）
+const PROBE_DIRECTIONS: usize = 8;
+const PROBE_RADII: [f64; 3] = [0.0, 0.5, 1.0];
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 开启后，未指定角度的单词在搜索前先观察螺旋中心附近空闲区域的形状：
+    // 空闲区域更高时先尝试竖排，更宽时先尝试横排，另一方向作为放不下时的备选。
+    // 另一方向不超出 rotation_range，rotation_range 为 0 时不会改变单词的朝向
+    #[wasm_bindgen]
+    pub fn set_smart_rotation(&mut self, enabled: bool) {
+        self.options.smart_rotation = enabled;
+    }
+}
+
+impl LayoutEngine {
+    // 单词依次尝试的旋转角度。关闭 smart_rotation、单词指定了角度
+    // 或 rotation_range 内没有其他角度时只有原角度
+    pub(crate) fn rotation_candidates(
+        &self,
+        word: &WordItem,
+        measured: &MeasuredWord,
+        rotation: f64,
+        context: &LayoutContext,
+    ) -> Vec<f64> {
+        if !self.options.smart_rotation || word.rotate.is_some() {
+            return vec![rotation];
+        }
+
+        // 另一方向：转 90 度，尽量保持在原角度的同一侧，并限制在 rotation_range 内
+        let range = self.rotation_to_internal(self.options.rotation_range).abs();
+        let turned = if rotation > 0.0 {
+            rotation - FRAC_PI_2
+        } else {
+            rotation + FRAC_PI_2
+        };
+        let turned = turned.clamp(-range, range);
+        if turned == rotation {
+            return vec![rotation];
+        }
+        let mut candidates = vec![rotation, turned];
+
+        let reach = measured.width.max(measured.height);
+        if let Some(anisotropy) =
+            self.free_space_anisotropy(context.center_x, context.center_y, reach)
+        {
+            // 单词长边的朝向与空闲区域的主方向一致时排在前面
+            let wide = measured.width >= measured.height;
+            let prefers_horizontal = anisotropy >= 1.0;
+            candidates.sort_by_key(|&angle| {
+                let lies_flat = angle.cos().abs() >= angle.sin().abs();
+                (lies_flat == wide) != prefers_horizontal
+            });
+        }
+        candidates
+    }
+
+    // (cx, cy) 周围空闲区域的各向异性：取样点处最长水平空闲段与竖直空闲段之比，
+    // 大于 1 表示空闲区域偏宽。所有取样点都被占用时为 None
+    fn free_space_anisotropy(&self, cx: f64, cy: f64, reach: f64) -> Option<f64> {
+        let grid_size = self.grid_size as f64;
+        let (columns, rows) = self.grid_dimensions();
+        let (mut horizontal, mut vertical) = (0usize, 0usize);
+
+        for radius in PROBE_RADII {
+            for step in 0..PROBE_DIRECTIONS {
+                let angle = TAU * step as f64 / PROBE_DIRECTIONS as f64;
+                let x = cx + radius * reach * angle.cos();
+                let y = cy + radius * reach * angle.sin();
+                if x < 0.0 || y < 0.0 {
+                    continue;
+                }
+                let (i, j) = ((x / grid_size) as usize, (y / grid_size) as usize);
+                if i >= columns || j >= rows || self.grid_cell(i, j) {
+                    continue;
+                }
+                horizontal += self.free_run(i, j, 1, 0) + self.free_run(i, j, -1, 0) + 1;
+                vertical += self.free_run(i, j, 0, 1) + self.free_run(i, j, 0, -1) + 1;
+                if radius == 0.0 {
+                    break;
+                }
+            }
+        }
+
+        (vertical > 0).then(|| horizontal as f64 / vertical as f64)
+    }
+
+    // 从单元 (i, j) 出发沿 (di, dj) 方向连续空闲的单元数（不含起点）
+    fn free_run(&self, i: usize, j: usize, di: isize, dj: isize) -> usize {
+        let (columns, rows) = self.grid_dimensions();
+        let mut run = 0;
+        let (mut i, mut j) = (i as isize, j as isize);
+        loop {
+            i += di;
+            j += dj;
+            if i < 0 || j < 0 || i as usize >= columns || j as usize >= rows {
+                return run;
+            }
+            if self.grid_cell(i as usize, j as usize) {
+                return run;
+            }
+            run += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cloud, WordCloud};
+
+    // 遮罩只留下画布中间 40 像素宽、贯穿上下的竖条。"vertical" 的随机角度偏横排
+    // （约 14°，另一方向约 -76°），碰撞检测按 rotation_snap 吸附为 0° 或 90°
+    fn slot_cloud(smart_rotation: bool) -> WordCloud {
+        let mut cloud = test_cloud();
+        cloud.options.min_size = 30.0;
+        cloud.options.max_size = 30.0;
+        cloud.set_rotation_unit("degrees".to_string());
+        cloud.set_rotation_range(90.0);
+        cloud.set_rotation_snap(45.0);
+        let mut data = vec![0; 40 * 30];
+        for row in data.chunks_mut(40) {
+            row[18..22].fill(1);
+        }
+        cloud.set_mask(data, 40, 30);
+        cloud.set_smart_rotation(smart_rotation);
+        cloud.generate_layout(
+            r#"[{"text": "vertical", "weight": 2}, {"text": "a", "weight": 1}]"#.to_string(),
+        );
+        cloud
+    }
+
+    #[test]
+    fn long_word_takes_the_tall_slot_on_the_first_rotation_tried() {
+        let cloud = slot_cloud(true);
+        let word = cloud.placed.iter().find(|w| w.text == "vertical").unwrap();
+        assert!(word.rotate.sin().abs() > word.rotate.cos().abs());
+        // 在螺旋起点第一次尝试就放下，两个单词合计的尝试次数不到一次完整的螺旋搜索
+        assert_eq!((word.x, word.y), (200.0, 150.0));
+        assert!(cloud.skipped.is_empty());
+        assert!(cloud.placement_attempts.get() < 1000);
+
+        // 关闭时只尝试偏横排的原角度，用完一次螺旋搜索后跳过
+        let cloud = slot_cloud(false);
+        assert!(cloud.placed.iter().all(|w| w.text != "vertical"));
+        assert_eq!(cloud.skipped[0].text, "vertical");
+        assert!(cloud.placement_attempts.get() >= 1000);
+    }
+}
@@ -0,0 +1,321 @@
+use std::f64::consts::PI;
+
+use wasm_bindgen::prelude::*;
+
+use crate::compound::PartPosition;
+use crate::env::log;
+use crate::footprint::Footprint;
+use crate::{LayoutEngine, WordCloud, WordPosition};
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置旋转角度的单位："radians" 或 "degrees"
+    // 作用于输入的 rotate、rotation_range 以及输出布局中的 rotate
+    #[wasm_bindgen]
+    pub fn set_rotation_unit(&mut self, unit: String) {
+        self.options.rotation_unit = unit;
+    }
+
+    // 设置 y 轴方向："down"（画布坐标，默认）或 "up"（绘图库常用的坐标系）
+    // up 模式下输入输出的 y 关于画布高度翻转，旋转角度取反，画出的结果与 down 模式一致
+    // 其他取值被忽略并返回 false
+    #[wasm_bindgen]
+    pub fn set_y_axis(&mut self, y_axis: String) -> bool {
+        if y_axis != "down" && y_axis != "up" {
+            log(&format!("未知的 y 轴方向: {}", y_axis));
+            return false;
+        }
+        self.options.y_axis = y_axis;
+        true
+    }
+
+    // 设置输出数值保留的小数位数，传入 None 表示不做舍入
+    #[wasm_bindgen]
+    pub fn set_output_precision(&mut self, precision: Option<u32>) {
+        self.options.output_precision = precision;
+    }
+}
+
+impl LayoutEngine {
+    fn uses_degrees(&self) -> bool {
+        self.options.rotation_unit == "degrees"
+    }
+
+    pub(crate) fn y_up(&self) -> bool {
+        self.options.y_axis == "up"
+    }
+
+    // 在内部坐标与配置的 y 轴方向之间转换，翻转是自身的逆变换
+    pub(crate) fn flip_y(&self, y: f64) -> f64 {
+        if self.y_up() {
+            self.options.height as f64 - y
+        } else {
+            y
+        }
+    }
+
+    // 把配置单位和方向下的角度转换为内部使用的弧度
+    pub(crate) fn rotation_to_internal(&self, rotation: f64) -> f64 {
+        let rotation = if self.y_up() { -rotation } else { rotation };
+        if self.uses_degrees() {
+            rotation.to_radians()
+        } else {
+            rotation
+        }
+    }
+
+    // 按 output_precision 舍入，并把 -0 规范为 0
+    pub(crate) fn round_output(&self, value: f64) -> f64 {
+        let value = match self.options.output_precision {
+            Some(precision) => {
+                let factor = 10f64.powi(precision as i32);
+                (value * factor).round() / factor
+            }
+            None => value,
+        };
+        if value == 0.0 {
+            0.0
+        } else {
+            value
+        }
+    }
+
+    // 把内部弧度转换为输出角度：角度制落在 [-180, 180)，弧度制落在 (-π, π]
+    pub(crate) fn emitted_rotation(&self, rotation: f64) -> f64 {
+        let rotation = if self.y_up() { -rotation } else { rotation };
+        if self.uses_degrees() {
+            let wrap = |d: f64| (d + 180.0).rem_euclid(360.0) - 180.0;
+            // 舍入可能把 179.9999 进到 180，需要再规范一次
+            self.round_output(wrap(self.round_output(wrap(rotation.to_degrees()))))
+        } else {
+            let wrap = |r: f64| {
+                let r = r.rem_euclid(2.0 * PI);
+                if r > PI {
+                    r - 2.0 * PI
+                } else {
+                    r
+                }
+            };
+            self.round_output(wrap(self.round_output(wrap(rotation))))
+        }
+    }
+
+    // SVG 的 rotate() 总是使用角度制，与输出一样规范到 [-180, 180)
+    pub(crate) fn svg_rotation(&self, rotation: f64) -> f64 {
+        let wrap = |d: f64| (d + 180.0).rem_euclid(360.0) - 180.0;
+        self.round_output(wrap(self.round_output(wrap(rotation.to_degrees()))))
+    }
+
+    // 输出前的规范化：旋转角度换算并规范到标准区间，数值按精度舍入
+    // 内部的碰撞检测仍然使用未经处理的精确值
+    pub(crate) fn emit_position(&self, word: &WordPosition) -> WordPosition {
+        WordPosition {
+            x: self.round_output(word.x),
+            y: self.round_output(self.flip_y(word.y)),
+            rotate: self.emitted_rotation(word.rotate),
+            size: word.size.map(|size| self.round_output(size)),
+            parts: word.parts.as_ref().map(|parts| {
+                parts
+                    .iter()
+                    .map(|part| PartPosition {
+                        size: self.round_output(part.size),
+                        dx: self.round_output(part.dx),
+                        dy: self.round_output(self.local_dy(part.dy)),
+                        ..part.clone()
+                    })
+                    .collect()
+            }),
+            ci_low_size: word.ci_low_size.map(|size| self.round_output(size)),
+            ci_high_size: word.ci_high_size.map(|size| self.round_output(size)),
+            footprint: word.footprint.as_ref().map(|footprint| Footprint {
+                x: self.round_output(footprint.x),
+                y: self.round_output(self.flip_y(footprint.y)),
+                width: self.round_output(footprint.width),
+                height: self.round_output(footprint.height),
+            }),
+            ..word.clone()
+        }
+    }
+
+    pub(crate) fn emit_layout(&self, words: &[WordPosition]) -> Vec<WordPosition> {
+        words.iter().map(|word| self.emit_position(word)).collect()
+    }
+
+    // 当前布局的输出 JSON，只包含符合标签筛选的单词
+    pub(crate) fn layout_json(&self) -> String {
+        serde_json::to_string(&self.layout_positions()).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    // 当前可见的已放置单词，已转换为输出坐标
+    pub(crate) fn layout_positions(&self) -> Vec<WordPosition> {
+        self.placed
+            .iter()
+            .filter(|word| self.tags_visible(&word.tags))
+            .map(|word| self.emit_position(word))
+            .collect()
+    }
+
+    // 单词局部坐标系中的纵向偏移：up 模式下方向相反
+    pub(crate) fn local_dy(&self, dy: f64) -> f64 {
+        if self.y_up() {
+            -dy
+        } else {
+            dy
+        }
+    }
+
+    // 读入外部布局时把坐标和旋转角度换算为内部表示
+    pub(crate) fn import_position(&self, word: WordPosition) -> WordPosition {
+        WordPosition {
+            y: self.flip_y(word.y),
+            rotate: self.rotation_to_internal(word.rotate),
+            parts: word.parts.map(|parts| {
+                parts
+                    .into_iter()
+                    .map(|part| PartPosition {
+                        dy: self.local_dy(part.dy),
+                        ..part
+                    })
+                    .collect()
+            }),
+            footprint: word.footprint.map(|footprint| Footprint {
+                y: self.flip_y(footprint.y),
+                ..footprint
+            }),
+            ..word
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::{test_cloud, WordCloud, WordPosition};
+
+    fn rotated_words(rotations: &[f64]) -> String {
+        let words: Vec<serde_json::Value> = rotations
+            .iter()
+            .enumerate()
+            .map(|(i, rotate)| serde_json::json!({"text": format!("w{}", i), "weight": 1, "rotate": rotate}))
+            .collect();
+        serde_json::to_string(&words).unwrap()
+    }
+
+    fn emitted_rotations(cloud: &mut WordCloud, rotations: &[f64]) -> Vec<f64> {
+        let layout: Vec<WordPosition> =
+            serde_json::from_str(&cloud.generate_layout(rotated_words(rotations))).unwrap();
+        layout.iter().map(|word| word.rotate).collect()
+    }
+
+    #[test]
+    fn degrees_are_emitted_in_the_canonical_range_without_negative_zero() {
+        let mut cloud = test_cloud();
+        cloud.set_rotation_unit("degrees".to_string());
+        cloud.set_output_precision(Some(2));
+        let rotations = emitted_rotations(&mut cloud, &[270.0, -450.0, -0.0, 180.0]);
+        assert_eq!(rotations, [-90.0, -90.0, 0.0, -180.0]);
+        assert!(rotations[2].is_sign_positive());
+
+        // 导出器使用同样规范化后的角度
+        let svg = cloud.generate_svg();
+        let css = cloud.generate_css_spans();
+        assert!(svg.contains("rotate(-90 "));
+        assert!(css.contains("rotate(-90deg)"));
+        assert!(!svg.contains("rotate(270") && !css.contains("rotate(270"));
+        assert!(!svg.contains("rotate(-0 ") && !css.contains("rotate(-0deg)"));
+    }
+
+    #[test]
+    fn radians_are_emitted_in_the_canonical_range() {
+        let mut cloud = test_cloud();
+        let rotations = emitted_rotations(&mut cloud, &[1.5 * PI, -PI, -0.0]);
+        assert!((rotations[0] + PI / 2.0).abs() < 1e-12);
+        assert_eq!(rotations[1], PI);
+        assert!(rotations[2] == 0.0 && rotations[2].is_sign_positive());
+    }
+
+    #[test]
+    fn rounding_up_to_180_degrees_wraps_to_minus_180() {
+        let mut cloud = test_cloud();
+        cloud.set_rotation_unit("degrees".to_string());
+        cloud.set_output_precision(Some(2));
+        let almost = (180.0f64 - 1e-6).to_radians();
+        assert_eq!(cloud.emitted_rotation(almost), -180.0);
+        assert_eq!(cloud.svg_rotation(almost), -180.0);
+    }
+
+    #[test]
+    fn normalization_does_not_change_collisions() {
+        let layout = |rotations: &[f64]| {
+            let mut cloud = test_cloud();
+            cloud.set_rotation_unit("degrees".to_string());
+            cloud.generate_layout(rotated_words(rotations));
+            cloud
+        };
+        let raw = layout(&[270.0, -450.0, -0.0]);
+        let canonical = layout(&[-90.0, -90.0, 0.0]);
+        assert_eq!(raw.placed.len(), canonical.placed.len());
+        for (a, b) in raw.placed.iter().zip(&canonical.placed) {
+            assert_eq!((a.x, a.y), (b.x, b.y));
+        }
+        // 内部保留输入的精确角度，碰撞检测的包围盒与规范角度一致
+        assert!((raw.placed[0].rotate - 1.5 * PI).abs() < 1e-12);
+        let (a, b) = (
+            raw.word_obb(&raw.placed[0]),
+            canonical.word_obb(&canonical.placed[0]),
+        );
+        let (a, b) = (a.bounds(), b.bounds());
+        for (u, v) in [(a.0, b.0), (a.1, b.1), (a.2, b.2), (a.3, b.3)] {
+            assert!((u - v).abs() < 1e-9);
+        }
+    }
+
+    // 按输出的中心和旋转角度（输出坐标系中的标准旋转）计算包围盒四角，
+    // flip_dy 为 true 时按相反的顺序给出半高偏移
+    fn emitted_corners(word: &WordPosition, size: (f64, f64), flip_dy: bool) -> Vec<(f64, f64)> {
+        let (half_width, half_height) = (size.0 / 2.0, size.1 / 2.0);
+        let (sin, cos) = word.rotate.sin_cos();
+        [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .iter()
+            .map(|&(sx, sy)| {
+                let sy = if flip_dy { -sy } else { sy };
+                let (dx, dy) = (sx * half_width, sy * half_height);
+                (word.x + dx * cos - dy * sin, word.y + dx * sin + dy * cos)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn y_up_output_maps_corner_for_corner_and_queries_match() {
+        let mut cloud = test_cloud();
+        cloud.generate_layout(rotated_words(&[0.3, -0.7, PI / 2.0, 1.1, 0.0]));
+        let height = cloud.options.height as f64;
+        let down: Vec<WordPosition> = serde_json::from_str(&cloud.layout_json()).unwrap();
+        assert!(cloud.set_y_axis("up".to_string()));
+        let up: Vec<WordPosition> = serde_json::from_str(&cloud.layout_json()).unwrap();
+        assert_eq!(down.len(), 5);
+        assert_eq!(up.len(), down.len());
+
+        for ((down, up), placed) in down.iter().zip(&up).zip(&cloud.placed) {
+            let size = cloud.word_box(placed);
+            let flipped_down = emitted_corners(down, size, false);
+            let up_corners = emitted_corners(up, size, true);
+            for (&(dx, dy), &(ux, uy)) in flipped_down.iter().zip(&up_corners) {
+                assert!((ux - dx).abs() < 1e-9);
+                assert!((uy - (height - dy)).abs() < 1e-9);
+            }
+        }
+
+        // 同一个点在两种坐标系下查询到同一个单词
+        for word in &down {
+            let (x, y) = (word.x, word.y);
+            cloud.set_y_axis("down".to_string());
+            let hit_down = cloud.words_in_rect_keys(x - 0.5, y - 0.5, 1.0, 1.0);
+            cloud.set_y_axis("up".to_string());
+            let hit_up = cloud.words_in_rect_keys(x - 0.5, height - y - 0.5, 1.0, 1.0);
+            assert!(hit_down.contains(&word.text));
+            assert_eq!(hit_down, hit_up);
+        }
+    }
+}
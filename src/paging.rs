@@ -0,0 +1,150 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::stats::{LayoutStats, SkippedWord};
+use crate::{LayoutEngine, WordCloud, WordPosition};
+
+// 单页的布局结果
+#[derive(Serialize)]
+struct LayoutPage {
+    page: u32,
+    placed: Vec<WordPosition>,
+    stats: LayoutStats,
+}
+
+// 单词被分配到的页（从 1 开始）
+#[derive(Serialize)]
+struct PageAssignment {
+    word: String,
+    page: u32,
+}
+
+// 分页布局的结果；skipped 为所有页都放不下的单词
+#[derive(Serialize)]
+struct PagedLayout {
+    pages: Vec<LayoutPage>,
+    assignments: Vec<PageAssignment>,
+    skipped: Vec<SkippedWord>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 分页布局：第一页放不下的单词在清空的画布上继续布局，最多 max_pages 页；
+    // 所有页共用整体的权重范围，同一权重的单词在各页字号一致。
+    // 布局结束后实例保留最后一页的结果
+    #[wasm_bindgen]
+    pub fn generate_layout_paged(&mut self, words_json: String, max_pages: u32) -> JsValue {
+        let result = self.layout_pages(&words_json, max_pages);
+        serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL)
+    }
+}
+
+impl LayoutEngine {
+    // generate_layout_paged 的实现，返回未序列化的结果
+    fn layout_pages(&mut self, words_json: &str, max_pages: u32) -> PagedLayout {
+        let mut result = PagedLayout {
+            pages: Vec::new(),
+            assignments: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        let (mut remaining, context) = match self.prepare_layout(words_json) {
+            Some(prepared) => prepared,
+            None => {
+                result.skipped = self.skipped.clone();
+                return result;
+            }
+        };
+
+        // 超出可用区域而被跳过的单词在任何一页都放不下
+        let mut oversized = std::mem::take(&mut self.skipped);
+
+        for page in 1..=max_pages.max(1) {
+            if page > 1 {
+                self.clear_placements();
+            }
+
+            self.place_all(remaining.clone(), &context);
+
+            // 按 key 从本页输入中移除已放置的单词，剩下的进入下一页
+            for word in &self.placed {
+                if let Some(index) = remaining.iter().position(|w| w.key() == word.key()) {
+                    remaining.remove(index);
+                }
+                result.assignments.push(PageAssignment {
+                    word: word.key().to_string(),
+                    page,
+                });
+            }
+
+            result.pages.push(LayoutPage {
+                page,
+                placed: self.emit_layout(&self.placed),
+                stats: self.layout_stats.clone(),
+            });
+
+            // 全部放完，或本页一个单词都放不下时停止
+            if remaining.is_empty() || self.placed.is_empty() {
+                break;
+            }
+        }
+
+        oversized.extend(self.skipped.iter().cloned());
+        result.skipped = oversized;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{test_cloud, weighted_words};
+
+    #[test]
+    fn pages_cover_the_input_once_with_globally_consistent_sizes() {
+        let mut cloud = test_cloud();
+        let result = cloud.layout_pages(&weighted_words(120), 3);
+        assert!(result.pages.len() >= 2);
+
+        // 各页放置的单词加上最终跳过的单词恰好覆盖输入，没有重复
+        let mut seen = HashSet::new();
+        for page in &result.pages {
+            for word in &page.placed {
+                assert!(seen.insert(word.key().to_string()), "{}", word.key());
+            }
+        }
+        for word in &result.skipped {
+            assert!(seen.insert(word.text.clone()), "{}", word.text);
+        }
+        let expected: HashSet<String> = (0..120).map(|i| format!("w{}", i)).collect();
+        assert_eq!(seen, expected);
+
+        // 分配记录与各页内容一致
+        assert_eq!(
+            result.assignments.len(),
+            result
+                .pages
+                .iter()
+                .map(|page| page.placed.len())
+                .sum::<usize>()
+        );
+        for page in &result.pages {
+            for word in &page.placed {
+                let assignment = result
+                    .assignments
+                    .iter()
+                    .find(|a| a.word == word.key())
+                    .unwrap();
+                assert_eq!(assignment.page, page.page);
+            }
+        }
+
+        // 第二页的字号按整体权重范围计算，与放在第一页时相同
+        assert!(!result.pages[1].placed.is_empty());
+        for word in &result.pages[1].placed {
+            let expected = cloud.word_size(word.weight, 1.0, 120.0);
+            assert!((word.size.unwrap() - expected).abs() < 1e-9);
+        }
+    }
+}
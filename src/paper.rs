@@ -0,0 +1,87 @@
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{LayoutEngine, WordCloud};
+
+// 方格背景的线条颜色和不透明度
+const GRID_BACKGROUND_COLOR: &str = "#000000";
+const GRID_BACKGROUND_OPACITY: f64 = 0.05;
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置方格纸背景及方格边长（像素），仅用于导出，不影响单词的位置
+    #[wasm_bindgen]
+    pub fn set_grid_background(&mut self, enabled: bool, size_px: u32) {
+        self.options.grid_background = enabled;
+        self.options.grid_background_size_px = size_px;
+    }
+
+    // 开启方格纸背景后生成布局，适合设计稿中的示意图
+    #[wasm_bindgen]
+    pub fn generate_layout_with_canvas_grid_background(
+        &mut self,
+        words_json: String,
+        size_px: u32,
+    ) -> String {
+        self.set_grid_background(true, size_px);
+        self.generate_layout(words_json)
+    }
+}
+
+impl LayoutEngine {
+    // 方格边长至少 1 像素；setter 和 run 的 options 都可能给出 0，统一在这里限制
+    fn grid_background_size(&self) -> u32 {
+        self.options.grid_background_size_px.max(1)
+    }
+
+    // 以单个方格的 <pattern> 平铺方格纸，并用覆盖整个画布的 <rect> 引用
+    pub(crate) fn svg_grid_background(&self) -> Option<String> {
+        if !self.options.grid_background {
+            return None;
+        }
+        let size = self.grid_background_size();
+        Some(format!(
+            r#"<defs><pattern id="grid-background" width="{}" height="{}" patternUnits="userSpaceOnUse"><path d="M{} 0H0V{}" fill="none" stroke="{}" stroke-width="1"/></pattern></defs><rect width="{}" height="{}" fill="url(#grid-background)" opacity="{}"/>"#,
+            size,
+            size,
+            size,
+            size,
+            GRID_BACKGROUND_COLOR,
+            self.options.width,
+            self.options.height,
+            GRID_BACKGROUND_OPACITY
+        ))
+    }
+}
+
+impl WordCloud {
+    // 在 canvas 上绘制方格纸的横线和竖线
+    pub(crate) fn draw_grid_background(&self, ctx: &CanvasRenderingContext2d) {
+        if !self.options.grid_background {
+            return;
+        }
+        let size = self.grid_background_size() as f64;
+        let width = self.options.width as f64;
+        let height = self.options.height as f64;
+
+        ctx.save();
+        ctx.set_global_alpha(GRID_BACKGROUND_OPACITY);
+        ctx.set_stroke_style_str(GRID_BACKGROUND_COLOR);
+        ctx.set_line_width(1.0);
+        ctx.begin_path();
+        let mut x = 0.0;
+        while x <= width {
+            ctx.move_to(x, 0.0);
+            ctx.line_to(x, height);
+            x += size;
+        }
+        let mut y = 0.0;
+        while y <= height {
+            ctx.move_to(0.0, y);
+            ctx.line_to(width, y);
+            y += size;
+        }
+        ctx.stroke();
+        ctx.restore();
+    }
+}
@@ -0,0 +1,96 @@
+use wasm_bindgen::prelude::*;
+
+use crate::compound::PartPosition;
+use crate::{LayoutEngine, MeasuredWord, WordCloud, WordPosition};
+
+// 注音文字相对单词字号的比例
+const PHONETIC_SCALE: f64 = 0.4;
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 生成布局，带注音的单词以 parts 输出：第一部分为注音，其余为单词本身，
+    // 外部渲染器按 parts 绘制即可得到与 generate_svg 相同的注音效果（SVG 中的 ruby）
+    #[wasm_bindgen]
+    pub fn generate_layout_with_word_pronunciation_annotations(
+        &mut self,
+        words_json: String,
+    ) -> String {
+        self.generate_layout(words_json);
+
+        let mut words = self.emit_layout(&self.placed);
+        for (word, placed) in words.iter_mut().zip(&self.placed) {
+            if let Some(parts) = self.phonetic_parts(placed) {
+                word.parts = Some(
+                    parts
+                        .into_iter()
+                        .map(|part| PartPosition {
+                            size: self.round_output(part.size),
+                            dx: self.round_output(part.dx),
+                            dy: self.round_output(part.dy),
+                            ..part
+                        })
+                        .collect(),
+                );
+            }
+        }
+        serde_json::to_string(&words).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+impl LayoutEngine {
+    // 注音排在单词上方：整体高度增加注音的高度，宽度取两者中较大者。
+    // 整体仍以 (x, y) 为中心，因此碰撞检测无需其他改动
+    pub(crate) fn with_phonetic(
+        &self,
+        phonetic: Option<&str>,
+        measured: MeasuredWord,
+    ) -> MeasuredWord {
+        match phonetic {
+            Some(phonetic) => {
+                let ruby_size = measured.size * PHONETIC_SCALE;
+                MeasuredWord {
+                    width: measured
+                        .width
+                        .max(self.estimate_word_width(phonetic, ruby_size)),
+                    height: measured.height + ruby_size,
+                    ..measured
+                }
+            }
+            None => measured,
+        }
+    }
+
+    // 带注音单词的绘制部分（相对中心、未旋转）：注音位于单词原包围盒上边缘处，
+    // 单词本身整体下移半个注音高度
+    pub(crate) fn phonetic_parts(&self, word: &WordPosition) -> Option<Vec<PartPosition>> {
+        let phonetic = word.phonetic.as_ref()?;
+        let size = word.size.unwrap_or(self.options.max_size);
+        let ruby_size = size * PHONETIC_SCALE;
+        let (_, text_height) = self.text_box(word);
+        let shift = ruby_size / 2.0;
+
+        let mut parts = vec![PartPosition {
+            text: phonetic.clone(),
+            size: ruby_size,
+            dx: 0.0,
+            dy: -text_height / 2.0,
+            color: None,
+            separator: false,
+        }];
+        match &word.parts {
+            Some(main) => parts.extend(main.iter().map(|part| PartPosition {
+                dy: part.dy + shift,
+                ..part.clone()
+            })),
+            None => parts.push(PartPosition {
+                text: word.text.clone(),
+                size,
+                dx: 0.0,
+                dy: shift,
+                color: None,
+                separator: false,
+            }),
+        }
+        Some(parts)
+    }
+}
@@ -0,0 +1,701 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use wasm_bindgen::prelude::*;
+
+use crate::stem::stem;
+use crate::{weight_range, CloudOptions, LayoutEngine, WordCloud, WordItem};
+
+// 声明式规范中各部分允许的字段
+const SPEC_FIELDS: [&str; 4] = ["options", "input", "transforms", "output"];
+const INPUT_FIELDS: [&str; 2] = ["format", "data"];
+const INPUT_FORMATS: [&str; 3] = ["csv", "words", "text"];
+const OUTPUT_FIELDS: [&str; 1] = ["format"];
+const OUTPUT_FORMATS: [&str; 4] = ["layout", "svg", "html", "draw_commands"];
+// 变换按此固定顺序执行，与规范中的书写顺序无关：
+// 清洗（lowercase）→ 去重 → 过滤（min_weight、max_words），之后才计算权重范围和字号
+const TRANSFORMS: [&str; 4] = ["lowercase", "dedupe", "min_weight", "max_words"];
+
+// 带路径的校验错误，例如 $.input.format
+#[derive(Serialize)]
+struct SpecError {
+    path: String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct SpecErrors {
+    errors: Vec<SpecError>,
+}
+
+// 绘制指令：按顺序执行 fillText 即可复现布局
+#[derive(Serialize)]
+struct DrawCommand<'a> {
+    text: &'a str,
+    x: f64,
+    y: f64,
+    rotate: f64,
+    font: String,
+    fill: &'a str,
+}
+
+// 布局类输出附带本次布局的警告
+#[derive(Serialize)]
+struct PipelineOutput<T: Serialize> {
+    result: T,
+    warnings: Vec<String>,
+}
+
+// 校验过程中收集所有错误，而不是遇到第一个就停止
+struct Validator {
+    errors: Vec<SpecError>,
+}
+
+impl Validator {
+    fn error(&mut self, path: &str, message: impl Into<String>) {
+        self.errors.push(SpecError {
+            path: path.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn object<'a>(&mut self, value: &'a Value, path: &str) -> Option<&'a Map<String, Value>> {
+        let object = value.as_object();
+        if object.is_none() {
+            self.error(path, "expected an object");
+        }
+        object
+    }
+
+    fn known_fields(&mut self, object: &Map<String, Value>, path: &str, fields: &[&str]) {
+        for key in object.keys() {
+            if !fields.contains(&key.as_str()) {
+                self.error(&format!("{}.{}", path, key), "unknown field");
+            }
+        }
+    }
+
+    fn one_of<'a>(
+        &mut self,
+        value: Option<&'a Value>,
+        path: &str,
+        choices: &[&str],
+    ) -> Option<&'a str> {
+        let result = value
+            .and_then(|v| v.as_str())
+            .filter(|v| choices.contains(v));
+        if result.is_none() {
+            self.error(path, format!("expected one of {:?}", choices));
+        }
+        result
+    }
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 一次调用完成 配置 → 读入 → 变换 → 布局 → 导出 的流程
+    // 规范格式：{options, input: {format, data}, transforms: [...], output: {format}}
+    // 校验失败时返回 {"errors": [{path, message}]}
+    #[wasm_bindgen]
+    pub fn run(&mut self, spec_json: String) -> String {
+        let mut validator = Validator { errors: Vec::new() };
+        let spec: Value = match serde_json::from_str(&spec_json) {
+            Ok(spec) => spec,
+            Err(e) => {
+                validator.error("$", format!("invalid JSON: {}", e));
+                return spec_errors(validator);
+            }
+        };
+
+        let spec = match validator.object(&spec, "$") {
+            Some(spec) => spec,
+            None => return spec_errors(validator),
+        };
+        validator.known_fields(spec, "$", &SPEC_FIELDS);
+
+        let options = spec
+            .get("options")
+            .and_then(|options| self.validate_options(options, &mut validator));
+        // 文本输入按规范中的 stem 选项（若有）提取词干
+        let stem = options
+            .as_ref()
+            .map_or(self.options.stem.as_str(), |options| options.stem.as_str());
+        let words = match spec.get("input") {
+            Some(input) => parse_input(input, stem, &mut validator),
+            None => {
+                validator.error("$.input", "missing required field");
+                None
+            }
+        };
+        let transforms = spec
+            .get("transforms")
+            .map(|transforms| validate_transforms(transforms, &mut validator))
+            .unwrap_or_default();
+        let output_format = match spec.get("output") {
+            Some(output) => validator.object(output, "$.output").and_then(|output| {
+                validator.known_fields(output, "$.output", &OUTPUT_FIELDS);
+                validator.one_of(output.get("format"), "$.output.format", &OUTPUT_FORMATS)
+            }),
+            None => Some("layout"),
+        };
+
+        if !validator.errors.is_empty() {
+            return spec_errors(validator);
+        }
+
+        // 校验全部通过后才修改实例状态
+        if let Some(options) = options {
+            let resized =
+                options.width != self.options.width || options.height != self.options.height;
+            let (width, height) = (options.width, options.height);
+            self.options = options;
+            if resized {
+                self.set_size(width, height);
+            }
+        }
+
+        let mut words = words.unwrap_or_default();
+        // scale_over = "all_input" 时字号按变换之前的权重范围计算，没有输入单词时不记录
+        self.input_weight_range = (!words.is_empty()).then(|| weight_range(&words));
+        let mut transforms = transforms;
+        transforms.sort_by_key(|(name, _)| TRANSFORMS.iter().position(|t| t == name));
+        for (name, value) in &transforms {
+            words = apply_transform(words, name, value);
+        }
+        let words_json = serde_json::to_string(&words).unwrap_or_else(|_| "[]".to_string());
+        self.generate_layout(words_json);
+
+        match output_format.unwrap_or("layout") {
+            "svg" => self.generate_svg(),
+            "html" => format!(
+                r#"<!DOCTYPE html><html><head><meta charset="utf-8"><title>Word Cloud</title></head><body>{}</body></html>"#,
+                self.generate_svg()
+            ),
+            "draw_commands" => self.pipeline_output(self.draw_commands()),
+            _ => self.pipeline_output(self.emit_layout(&self.placed)),
+        }
+    }
+
+    // 从纯文本生成布局：按词频作为权重
+    #[wasm_bindgen]
+    pub fn generate_layout_from_text(&mut self, text: String) -> String {
+        let words = count_words(&text, &self.options.stem);
+        let words_json = serde_json::to_string(&words).unwrap_or_else(|_| "[]".to_string());
+        self.generate_layout(words_json)
+    }
+}
+
+impl LayoutEngine {
+    // 把 options 合并到当前配置上：未知字段和类型不符的字段按路径报错
+    fn validate_options(&self, options: &Value, validator: &mut Validator) -> Option<CloudOptions> {
+        let options = validator.object(options, "$.options")?;
+        let mut merged = serde_json::to_value(&self.options).ok()?;
+        let current = merged.as_object_mut()?;
+
+        let before = validator.errors.len();
+        for (key, value) in options {
+            let path = format!("$.options.{}", key);
+            match current.get(key) {
+                None => validator.error(&path, "unknown option"),
+                Some(existing) if !same_kind(existing, value) => {
+                    validator.error(&path, format!("expected {}", kind_name(existing)))
+                }
+                Some(_) => {
+                    current.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        if validator.errors.len() > before {
+            return None;
+        }
+
+        match serde_json::from_value(merged) {
+            Ok(options) => Some(options),
+            Err(e) => {
+                validator.error("$.options", e.to_string());
+                None
+            }
+        }
+    }
+
+    fn pipeline_output<T: Serialize>(&self, result: T) -> String {
+        let output = PipelineOutput {
+            result,
+            warnings: self.warnings.clone(),
+        };
+        serde_json::to_string(&output).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    // 每个文本片段一条绘制指令，组合词的各部分按旋转后的偏移换算为绝对坐标
+    fn draw_commands(&self) -> Vec<DrawCommand<'_>> {
+        let font = |size: f64| {
+            format!(
+                "{} {}px {}",
+                self.options.font_weight,
+                self.round_output(size),
+                self.options.font_family
+            )
+        };
+
+        let mut commands = Vec::new();
+        for word in &self.placed {
+            let fill = word.color.as_deref().unwrap_or("#000000");
+            let rotate = self.emitted_rotation(word.rotate);
+            match &word.parts {
+                Some(parts) => {
+                    let (sin_rot, cos_rot) = word.rotate.sin_cos();
+                    for part in parts {
+                        commands.push(DrawCommand {
+                            text: &part.text,
+                            x: self.round_output(word.x + part.dx * cos_rot - part.dy * sin_rot),
+                            y: self.round_output(
+                                self.flip_y(word.y + part.dx * sin_rot + part.dy * cos_rot),
+                            ),
+                            rotate,
+                            font: font(part.size),
+                            fill: part.color.as_deref().unwrap_or(fill),
+                        });
+                    }
+                }
+                None => commands.push(DrawCommand {
+                    text: &word.text,
+                    x: self.round_output(word.x),
+                    y: self.round_output(self.flip_y(word.y)),
+                    rotate,
+                    font: font(word.size.unwrap_or(self.options.max_size)),
+                    fill,
+                }),
+            }
+        }
+        commands
+    }
+}
+
+fn spec_errors(validator: Validator) -> String {
+    let errors = SpecErrors {
+        errors: validator.errors,
+    };
+    serde_json::to_string(&errors).unwrap_or_else(|_| "{}".to_string())
+}
+
+// null 表示可选字段当前未设置，接受任意类型，交给反序列化检查
+fn same_kind(existing: &Value, value: &Value) -> bool {
+    existing.is_null() || kind_name(existing) == kind_name(value)
+}
+
+fn kind_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+// 读入输入数据并转换为词条
+fn parse_input(input: &Value, stem: &str, validator: &mut Validator) -> Option<Vec<WordItem>> {
+    let input = validator.object(input, "$.input")?;
+    validator.known_fields(input, "$.input", &INPUT_FIELDS);
+    let format = validator.one_of(input.get("format"), "$.input.format", &INPUT_FORMATS)?;
+    let data = match input.get("data") {
+        Some(data) => data,
+        None => {
+            validator.error("$.input.data", "missing required field");
+            return None;
+        }
+    };
+
+    if format == "words" {
+        return match serde_json::from_value(data.clone()) {
+            Ok(words) => Some(words),
+            Err(e) => {
+                validator.error("$.input.data", e.to_string());
+                None
+            }
+        };
+    }
+
+    let text = match data.as_str() {
+        Some(text) => text,
+        None => {
+            validator.error("$.input.data", "expected a string");
+            return None;
+        }
+    };
+    match format {
+        "csv" => parse_csv(text)
+            .map_err(|(line, message)| {
+                validator.error(&format!("$.input.data[line {}]", line), message)
+            })
+            .ok(),
+        _ => Some(count_words(text, stem)),
+    }
+}
+
+fn word_item(text: String, weight: f64, color: Option<String>) -> WordItem {
+    WordItem {
+        text,
+        weight,
+        color,
+        ..WordItem::default()
+    }
+}
+
+// CSV 每行为 text,weight[,color]；第一行的权重不是数字时视为表头。支持双引号包裹的字段
+fn parse_csv(text: &str) -> Result<Vec<WordItem>, (usize, String)> {
+    let mut words = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = split_csv_line(line);
+        let weight = match fields.get(1).map(|w| w.trim().parse::<f64>()) {
+            Some(Ok(weight)) => weight,
+            None if fields.len() == 1 => 1.0,
+            _ if index == 0 => continue,
+            _ => return Err((index + 1, "weight is not a number".to_string())),
+        };
+        let color = fields
+            .get(2)
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        words.push(word_item(fields[0].trim().to_string(), weight, color));
+    }
+    Ok(words)
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+// 按字母数字切分文本并统计词频，按频率降序、首次出现顺序排列
+// stem 不为 "none" 时词干相同的词合并计数，显示出现次数最多的写法
+fn count_words(text: &str, stem_mode: &str) -> Vec<WordItem> {
+    let mut order: Vec<String> = Vec::new();
+    // 每个词干下各写法的出现次数，按首次出现顺序
+    let mut forms: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+    for token in text
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|t| t.trim_matches('\'').to_lowercase())
+        .filter(|t| !t.is_empty())
+    {
+        let key = stem(&token, stem_mode);
+        let entry = forms.entry(key.clone()).or_default();
+        if entry.is_empty() {
+            order.push(key);
+        }
+        match entry.iter_mut().find(|(form, _)| *form == token) {
+            Some((_, count)) => *count += 1,
+            None => entry.push((token, 1)),
+        }
+    }
+
+    let mut words: Vec<WordItem> = order
+        .into_iter()
+        .map(|key| {
+            let entry = &forms[&key];
+            let weight = entry.iter().map(|(_, count)| *count).sum::<usize>() as f64;
+            // 次数相同时取先出现的写法
+            let display = entry
+                .iter()
+                .rev()
+                .max_by_key(|(_, count)| *count)
+                .map_or(key.clone(), |(form, _)| form.clone());
+            word_item(display, weight, None)
+        })
+        .collect();
+    words.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+    words
+}
+
+// 每个变换是只有一个字段的对象，例如 {"max_words": 100}
+fn validate_transforms(transforms: &Value, validator: &mut Validator) -> Vec<(String, Value)> {
+    let transforms = match transforms.as_array() {
+        Some(transforms) => transforms,
+        None => {
+            validator.error("$.transforms", "expected an array");
+            return Vec::new();
+        }
+    };
+
+    let mut result = Vec::new();
+    for (index, transform) in transforms.iter().enumerate() {
+        let path = format!("$.transforms[{}]", index);
+        let object = match validator.object(transform, &path) {
+            Some(object) if object.len() == 1 => object,
+            Some(_) => {
+                validator.error(&path, "expected exactly one transform name");
+                continue;
+            }
+            None => continue,
+        };
+        let (name, value) = object.iter().next().expect("object has one field");
+        let path = format!("{}.{}", path, name);
+        let valid = match name.as_str() {
+            "dedupe" | "lowercase" => value.is_boolean() || value.is_object(),
+            "min_weight" => value.is_number(),
+            "max_words" => value.is_u64(),
+            _ => {
+                validator.error(
+                    &path,
+                    format!("unknown transform, expected one of {:?}", TRANSFORMS),
+                );
+                continue;
+            }
+        };
+        if !valid {
+            validator.error(&path, "invalid transform argument");
+            continue;
+        }
+        result.push((name.clone(), value.clone()));
+    }
+    result
+}
+
+fn apply_transform(mut words: Vec<WordItem>, name: &str, value: &Value) -> Vec<WordItem> {
+    match name {
+        // 合并 key 相同的单词，权重累加，保留第一次出现的其他属性
+        "dedupe" if value != &Value::Bool(false) => {
+            let mut merged: Vec<WordItem> = Vec::new();
+            let mut index: HashMap<String, usize> = HashMap::new();
+            for word in words {
+                match index.get(word.key()) {
+                    Some(&i) => merged[i].weight += word.weight,
+                    None => {
+                        index.insert(word.key().to_string(), merged.len());
+                        merged.push(word);
+                    }
+                }
+            }
+            merged
+        }
+        "lowercase" if value != &Value::Bool(false) => {
+            for word in &mut words {
+                word.text = word.text.to_lowercase();
+            }
+            words
+        }
+        "min_weight" => {
+            let min_weight = value.as_f64().unwrap_or(0.0);
+            words.retain(|word| word.weight >= min_weight);
+            words
+        }
+        // 保留权重最高的前 n 个单词，同权重时保持原顺序
+        "max_words" => {
+            let max_words = value.as_u64().unwrap_or(0) as usize;
+            words.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+            words.truncate(max_words);
+            words
+        }
+        _ => words,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::test_cloud;
+
+    fn error_paths(output: &str) -> Vec<String> {
+        let output: Value = serde_json::from_str(output).unwrap();
+        output["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| error["path"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn csv_spec_runs_through_transforms_to_svg() {
+        let mut cloud = test_cloud();
+        let spec = serde_json::json!({
+            "options": {"width": 500, "seed": 3},
+            "input": {"format": "csv", "data": "text,weight\nRust,10\nrust,4\nWasm,6\nCloud,3\n"},
+            "transforms": [{"max_words": 2}, {"dedupe": true}, {"lowercase": true}],
+            "output": {"format": "svg"}
+        });
+        let svg = cloud.run(spec.to_string());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"width="500""#));
+        assert!(svg.contains(">rust</text>") && svg.contains(">wasm</text>"));
+        assert!(!svg.contains(">cloud</text>"));
+
+        // 变换按固定顺序执行：先转小写，再合并，最后截断
+        let weights: Vec<(&str, f64)> = cloud
+            .placed
+            .iter()
+            .map(|word| (word.text.as_str(), word.weight))
+            .collect();
+        assert_eq!(weights, [("rust", 14.0), ("wasm", 6.0)]);
+    }
+
+    #[test]
+    fn text_spec_returns_the_layout_with_warnings() {
+        let mut cloud = test_cloud();
+        let spec = serde_json::json!({
+            "input": {"format": "text", "data": "The cat and the hat, and the bat."},
+            "output": {"format": "layout"}
+        });
+        let output: Value = serde_json::from_str(&cloud.run(spec.to_string())).unwrap();
+        assert!(output["warnings"].is_array());
+        let words: Vec<(&str, f64)> = output["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|word| {
+                (
+                    word["text"].as_str().unwrap(),
+                    word["weight"].as_f64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            words,
+            [
+                ("the", 3.0),
+                ("and", 2.0),
+                ("cat", 1.0),
+                ("hat", 1.0),
+                ("bat", 1.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn schema_errors_name_the_bad_paths_and_leave_the_instance_unchanged() {
+        let mut cloud = test_cloud();
+        let spec = serde_json::json!({
+            "options": {"width": "wide", "bogus": 1},
+            "input": {"format": "xml", "data": ""},
+            "transforms": [{"max_words": -1}, {"shuffle": true}],
+            "output": {"format": "png"},
+            "extra": true
+        });
+        assert_eq!(
+            error_paths(&cloud.run(spec.to_string())),
+            [
+                "$.extra",
+                "$.options.bogus",
+                "$.options.width",
+                "$.input.format",
+                "$.transforms[0].max_words",
+                "$.transforms[1].shuffle",
+                "$.output.format",
+            ]
+        );
+        assert_eq!(cloud.options.width, 400);
+        assert!(cloud.placed.is_empty());
+
+        let spec = serde_json::json!({
+            "input": {"format": "csv", "data": "text,weight\nrust,10\nwasm,lots\n"}
+        });
+        assert_eq!(
+            error_paths(&cloud.run(spec.to_string())),
+            ["$.input.data[line 3]"]
+        );
+    }
+
+    // (显示文本, 权重)，按 count_words 的输出顺序
+    fn counted(text: &str, stem_mode: &str) -> Vec<(String, f64)> {
+        super::count_words(text, stem_mode)
+            .into_iter()
+            .map(|word| (word.text, word.weight))
+            .collect()
+    }
+
+    const STEM_TEXT: &str = "Clouds clouds cloud's cloud running runs run";
+
+    #[test]
+    fn plural_stemming_merges_plurals_and_possessives() {
+        assert_eq!(counted(STEM_TEXT, "none").len(), 6);
+        // "clouds" 出现两次，是最常见的写法；"runs" 与 "run" 次数相同，取先出现的写法
+        assert_eq!(
+            counted(STEM_TEXT, "plural"),
+            vec![
+                ("clouds".to_string(), 4.0),
+                ("runs".to_string(), 2.0),
+                ("running".to_string(), 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn porter_stemming_also_merges_verb_forms() {
+        assert_eq!(
+            counted(STEM_TEXT, "porter"),
+            vec![("clouds".to_string(), 4.0), ("running".to_string(), 3.0)]
+        );
+    }
+
+    // 经 run 生成布局，返回各单词的字号和 get_layout_stats 报告的权重范围
+    fn sizes_and_range(scale_over: &str, transforms: Value) -> (Vec<(String, f64)>, (f64, f64)) {
+        let mut cloud = test_cloud();
+        let spec = serde_json::json!({
+            "options": {"scale_over": scale_over},
+            "input": {"format": "csv", "data": "text,weight\nbig,10\nmid,5\noutlier,1\n"},
+            "transforms": transforms,
+        });
+        let output: Value = serde_json::from_str(&cloud.run(spec.to_string())).unwrap();
+        let sizes = output["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|word| {
+                let text = word["text"].as_str().unwrap().to_string();
+                (text, word["size"].as_f64().unwrap())
+            })
+            .collect();
+        let stats: Value = serde_json::from_str(&cloud.get_layout_stats()).unwrap();
+        let range = (
+            stats["min_weight"].as_f64().unwrap(),
+            stats["max_weight"].as_f64().unwrap(),
+        );
+        (sizes, range)
+    }
+
+    #[test]
+    fn filtering_an_outlier_rescales_survivors_only_in_survivor_mode() {
+        let size_of =
+            |sizes: &[(String, f64)], text: &str| sizes.iter().find(|(t, _)| t == text).unwrap().1;
+        let filter = serde_json::json!([{"min_weight": 2}]);
+
+        let (all, all_range) = sizes_and_range("survivors", serde_json::json!([]));
+        assert_eq!(all_range, (1.0, 10.0));
+
+        // 按幸存单词计算：范围收窄为 5..10，mid 缩到最小字号
+        let (survivors, range) = sizes_and_range("survivors", filter.clone());
+        assert_eq!(range, (5.0, 10.0));
+        assert_eq!(survivors.len(), 2);
+        assert_eq!(size_of(&survivors, "mid"), 10.0);
+        assert!(size_of(&survivors, "mid") < size_of(&all, "mid"));
+
+        // 按全部输入计算：过滤掉的单词仍参与范围，字号不变
+        let (all_input, range) = sizes_and_range("all_input", filter);
+        assert_eq!(range, (1.0, 10.0));
+        assert_eq!(all_input.len(), 2);
+        for (text, size) in &all_input {
+            assert_eq!(*size, size_of(&all, text), "{}", text);
+        }
+    }
+}
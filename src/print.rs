@@ -0,0 +1,157 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{LayoutEngine, WordCloud};
+
+// 每英寸的毫米数和磅数
+const MM_PER_INCH: f64 = 25.4;
+const PT_PER_INCH: f64 = 72.0;
+
+pub(crate) fn mm_to_pt(mm: f64) -> f64 {
+    mm / MM_PER_INCH * PT_PER_INCH
+}
+
+// 打印信息：有效 DPI、最小单词的物理高度（毫米）及可读性警告
+#[derive(Serialize)]
+struct PrintInfo {
+    dpi: f64,
+    smallest_word: Option<String>,
+    smallest_height_mm: Option<f64>,
+    min_print_height_mm: f64,
+    warning: Option<String>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置打印尺寸（毫米），只影响导出的元数据，布局计算不变
+    #[wasm_bindgen]
+    pub fn set_physical_size(&mut self, width_mm: f64, height_mm: f64) {
+        self.options.physical_width_mm = Some(width_mm).filter(|&mm| mm > 0.0);
+        self.options.physical_height_mm = Some(height_mm).filter(|&mm| mm > 0.0);
+    }
+
+    // 清除打印尺寸，SVG 恢复以像素给出宽高
+    #[wasm_bindgen]
+    pub fn clear_physical_size(&mut self) {
+        self.options.physical_width_mm = None;
+        self.options.physical_height_mm = None;
+    }
+
+    // 设置打印时单词的最小可读高度（毫米）
+    #[wasm_bindgen]
+    pub fn set_min_print_height_mm(&mut self, min_height_mm: f64) {
+        self.options.min_print_height_mm = min_height_mm.max(0.0);
+    }
+
+    // 报告当前打印尺寸下的有效 DPI 和最小单词的物理高度；
+    // 最小单词低于 min_print_height_mm 时附带警告。未设置打印尺寸时返回 "{}"
+    #[wasm_bindgen]
+    pub fn print_info(&self) -> String {
+        let mm = match self.mm_per_px() {
+            Some(mm) => mm,
+            None => return "{}".to_string(),
+        };
+
+        let smallest = self
+            .placed
+            .iter()
+            .map(|word| {
+                let size = match &word.parts {
+                    Some(parts) => parts
+                        .iter()
+                        .map(|part| part.size)
+                        .fold(f64::INFINITY, f64::min),
+                    None => word.size.unwrap_or(self.options.max_size),
+                };
+                (word, size)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        let min_height = self.options.min_print_height_mm;
+        let smallest_height_mm = smallest.map(|(_, size)| size * mm);
+        let warning = smallest
+            .filter(|(_, size)| size * mm < min_height)
+            .map(|(word, size)| {
+                format!(
+                    "smallest word \"{}\" is {:.2}mm tall, below the {}mm legibility threshold",
+                    word.text,
+                    size * mm,
+                    min_height
+                )
+            });
+
+        let info = PrintInfo {
+            dpi: self.round_output(MM_PER_INCH / mm),
+            smallest_word: smallest.map(|(word, _)| word.text.clone()),
+            smallest_height_mm: smallest_height_mm.map(|height| self.round_output(height)),
+            min_print_height_mm: min_height,
+            warning,
+        };
+        serde_json::to_string(&info).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+impl LayoutEngine {
+    // 每个像素对应的毫米数；宽高比例不一致时按较小的比例等比缩放（与 SVG 默认的 meet 一致）
+    pub(crate) fn mm_per_px(&self) -> Option<f64> {
+        let width_mm = self.options.physical_width_mm?;
+        let height_mm = self.options.physical_height_mm?;
+        let (width, height) = (self.options.width as f64, self.options.height as f64);
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        Some((width_mm / width).min(height_mm / height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_cloud;
+
+    const WORDS: &str = r#"[{"text": "large", "weight": 10}, {"text": "tiny", "weight": 1}]"#;
+
+    fn printed_cloud(width_mm: f64, height_mm: f64) -> WordCloud {
+        let mut cloud = test_cloud();
+        cloud.generate_layout(WORDS.to_string());
+        cloud.set_physical_size(width_mm, height_mm);
+        cloud
+    }
+
+    #[test]
+    fn svg_carries_physical_size_and_point_sizes() {
+        // 400x300 像素打印为 200x150 毫米：每像素 0.5 毫米
+        let cloud = printed_cloud(200.0, 150.0);
+        let svg = cloud.generate_svg();
+        assert!(svg.contains(r#"width="200mm" height="150mm" viewBox="0 0 400 300""#));
+        assert!(svg.contains(&format!(r#"data-pt-size="{}""#, mm_to_pt(40.0 * 0.5))));
+        assert!(svg.contains(&format!(r#"data-pt-size="{}""#, mm_to_pt(10.0 * 0.5))));
+
+        // 宽高比例不一致时按较小的比例缩放
+        let cloud = printed_cloud(200.0, 300.0);
+        assert_eq!(cloud.mm_per_px(), Some(0.5));
+
+        let mut cloud = printed_cloud(200.0, 150.0);
+        cloud.clear_physical_size();
+        let svg = cloud.generate_svg();
+        assert!(svg.contains(r#"width="400" height="300""#));
+        assert!(!svg.contains("data-pt-size"));
+        assert_eq!(cloud.print_info(), "{}");
+    }
+
+    #[test]
+    fn print_info_warns_below_the_legibility_threshold() {
+        let mut cloud = printed_cloud(200.0, 150.0);
+        let info: serde_json::Value = serde_json::from_str(&cloud.print_info()).unwrap();
+        assert_eq!(info["dpi"], 50.8);
+        assert_eq!(info["smallest_word"], "tiny");
+        assert_eq!(info["smallest_height_mm"], 5.0);
+        assert!(info["warning"].is_null());
+
+        cloud.set_min_print_height_mm(6.0);
+        let info: serde_json::Value = serde_json::from_str(&cloud.print_info()).unwrap();
+        let warning = info["warning"].as_str().unwrap();
+        assert!(warning.contains("\"tiny\"") && warning.contains("5.00mm"));
+        assert!(warning.contains("6mm legibility threshold"));
+    }
+}
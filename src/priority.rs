@@ -0,0 +1,29 @@
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+use crate::{LayoutEngine, WordCloud, WordItem};
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置放置顺序的比较函数，调用形式为 (word_a_json, word_b_json) => number，
+    // 返回负数、0、正数分别表示 a 在前、顺序不变、b 在前
+    #[wasm_bindgen]
+    pub fn set_render_priority_fn(&mut self, callback: Function) {
+        self.js_host.priority_fn = Some(callback);
+        self.sync_host();
+    }
+
+    // 取消自定义放置顺序，恢复按输入顺序放置
+    #[wasm_bindgen]
+    pub fn clear_render_priority_fn(&mut self) {
+        self.js_host.priority_fn = None;
+        self.sync_host();
+    }
+}
+
+impl LayoutEngine {
+    // 按宿主提供的自定义比较函数对单词排序，没有设置时保持输入顺序
+    pub(crate) fn apply_render_priority(&self, words: Vec<WordItem>) -> Vec<WordItem> {
+        self.host.order_words(words)
+    }
+}
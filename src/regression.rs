@@ -0,0 +1,178 @@
+use crate::compare::parse_layout;
+use crate::{LayoutEngine, WordCloud};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+// 各分类的单词数量
+#[derive(Default, Serialize)]
+struct RegressionCounts {
+    identical: usize,
+    shifted: usize,
+    moved: usize,
+    resized: usize,
+    appeared: usize,
+    disappeared: usize,
+}
+
+// 单个单词的分类结果
+#[derive(Serialize)]
+struct WordChange {
+    word: String,
+    class: &'static str,
+    displacement: f64,
+}
+
+// 新旧布局的回归报告，各分类中的单词均以 key 表示
+#[derive(Default, Serialize)]
+struct RegressionReport {
+    words: Vec<WordChange>,
+    identical: Vec<String>,
+    shifted: Vec<String>,
+    moved: Vec<String>,
+    resized: Vec<String>,
+    appeared: Vec<String>,
+    disappeared: Vec<String>,
+    counts: RegressionCounts,
+    max_displacement: f64,
+}
+
+impl RegressionReport {
+    fn record(&mut self, word: String, class: &'static str, displacement: f64) {
+        let (bucket, count) = match class {
+            "identical" => (&mut self.identical, &mut self.counts.identical),
+            "shifted" => (&mut self.shifted, &mut self.counts.shifted),
+            "moved" => (&mut self.moved, &mut self.counts.moved),
+            "resized" => (&mut self.resized, &mut self.counts.resized),
+            "appeared" => (&mut self.appeared, &mut self.counts.appeared),
+            _ => (&mut self.disappeared, &mut self.counts.disappeared),
+        };
+        bucket.push(word.clone());
+        *count += 1;
+        self.max_displacement = self.max_displacement.max(displacement);
+        self.words.push(WordChange {
+            word,
+            class,
+            displacement,
+        });
+    }
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 比较两份已导出的布局，按 key 匹配单词（与数组顺序无关），给出每个单词的分类：
+    // identical（按 output_precision 取整后完全相同）、shifted（位移小于阈值）、
+    // moved（位移不小于阈值或旋转改变）、resized（字号改变）、appeared、disappeared
+    #[wasm_bindgen]
+    pub fn regression_report(&self, old_layout_json: String, new_layout_json: String) -> JsValue {
+        let report = self.build_regression_report(&old_layout_json, &new_layout_json);
+        serde_wasm_bindgen::to_value(&report).unwrap_or(JsValue::NULL)
+    }
+
+    // 设置回归报告中区分 shifted 与 moved 的位移阈值（像素）
+    #[wasm_bindgen]
+    pub fn set_regression_minor_threshold(&mut self, threshold: f64) {
+        self.options.regression_minor_threshold = threshold.max(0.0);
+    }
+}
+
+impl LayoutEngine {
+    // regression_report 的实现，返回未序列化的报告
+    fn build_regression_report(
+        &self,
+        old_layout_json: &str,
+        new_layout_json: &str,
+    ) -> RegressionReport {
+        let mut old_layout = parse_layout(old_layout_json);
+        let new_layout = parse_layout(new_layout_json);
+        let threshold = self.options.regression_minor_threshold;
+
+        let mut report = RegressionReport::default();
+        for new in &new_layout {
+            let key = new.key().to_string();
+            let old = match old_layout.iter().position(|old| old.key() == new.key()) {
+                Some(index) => old_layout.remove(index),
+                None => {
+                    report.record(key, "appeared", 0.0);
+                    continue;
+                }
+            };
+
+            let same = |a: f64, b: f64| self.round_output(a) == self.round_output(b);
+            let displacement = self.round_output((new.x - old.x).hypot(new.y - old.y));
+            let class = if !same(old.size.unwrap_or(0.0), new.size.unwrap_or(0.0)) {
+                "resized"
+            } else if same(old.x, new.x) && same(old.y, new.y) && same(old.rotate, new.rotate) {
+                "identical"
+            } else if displacement < threshold && same(old.rotate, new.rotate) {
+                "shifted"
+            } else {
+                "moved"
+            };
+            let displacement = if class == "identical" {
+                0.0
+            } else {
+                displacement
+            };
+            report.record(key, class, displacement);
+        }
+
+        for old in old_layout {
+            report.record(old.key().to_string(), "disappeared", 0.0);
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::test_cloud;
+
+    fn word(text: &str, x: f64, y: f64, rotate: f64, size: f64) -> Value {
+        json!({"text": text, "weight": 1, "x": x, "y": y, "rotate": rotate, "size": size})
+    }
+
+    #[test]
+    fn each_bucket_contains_exactly_the_expected_words() {
+        let mut cloud = test_cloud();
+        cloud.set_output_precision(Some(2));
+        cloud.set_regression_minor_threshold(5.0);
+
+        let old = json!([
+            word("same", 100.0, 100.0, 0.0, 20.0),
+            word("rounding", 50.0, 50.0, 0.0, 20.0),
+            word("nudged", 200.0, 100.0, 0.0, 20.0),
+            word("jumped", 300.0, 200.0, 0.0, 20.0),
+            word("turned", 120.0, 220.0, 0.0, 20.0),
+            word("grown", 60.0, 260.0, 0.0, 20.0),
+            word("gone", 10.0, 10.0, 0.0, 20.0),
+            word("edge", 250.0, 250.0, 0.0, 20.0),
+        ]);
+        // 顺序与旧布局不同，按 key 匹配
+        let new = json!([
+            word("fresh", 380.0, 20.0, 0.0, 20.0),
+            word("grown", 60.0, 260.0, 0.0, 24.0),
+            word("turned", 120.0, 220.0, 1.5, 20.0),
+            word("jumped", 310.0, 200.0, 0.0, 20.0),
+            word("nudged", 202.0, 102.0, 0.0, 20.0),
+            word("rounding", 50.001, 49.999, 0.0, 20.0),
+            word("same", 100.0, 100.0, 0.0, 20.0),
+            // 位移恰好等于阈值时算作 moved
+            word("edge", 253.0, 254.0, 0.0, 20.0),
+        ]);
+        let report = cloud.build_regression_report(&old.to_string(), &new.to_string());
+
+        assert_eq!(report.identical, ["rounding", "same"]);
+        assert_eq!(report.shifted, ["nudged"]);
+        assert_eq!(report.moved, ["turned", "jumped", "edge"]);
+        assert_eq!(report.resized, ["grown"]);
+        assert_eq!(report.appeared, ["fresh"]);
+        assert_eq!(report.disappeared, ["gone"]);
+        assert_eq!(report.counts.identical, 2);
+        assert_eq!(report.counts.moved, 3);
+        assert_eq!(report.words.len(), 9);
+        assert_eq!(report.max_displacement, 10.0);
+    }
+}
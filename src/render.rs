@@ -0,0 +1,826 @@
+use js_sys::Reflect;
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, HtmlImageElement};
+
+use crate::color::{tint_color, Rgb};
+use crate::print::mm_to_pt;
+use crate::sparkline::draw_sparkline;
+use crate::{LayoutEngine, WordCloud, WordPosition};
+
+// 标注箭头的长度和标注文字的字号
+const ANNOTATION_LENGTH: f64 = 30.0;
+const ANNOTATION_FONT_SIZE: f64 = 12.0;
+const ANNOTATION_COLOR: &str = "#555555";
+
+// 图例中的示例文字、示例数量、标签字号和内边距
+const LEGEND_SAMPLE: &str = "Abc";
+const LEGEND_STEPS: usize = 4;
+const LEGEND_LABEL_SIZE: f64 = 12.0;
+const LEGEND_PADDING: f64 = 10.0;
+
+// 渐变填充的终点颜色向白色混合的比例
+const GRADIENT_TINT: f64 = 0.6;
+
+// 热力图每个方向的区块数及透明度
+const HEATMAP_TILES: usize = 16;
+const HEATMAP_OPACITY: f64 = 0.35;
+
+// 交互模式下悬停放大的样式
+const INTERACTIVE_STYLE: &str =
+    "<style>.word{transition:transform 0.3s}.word:hover{transform:scale(1.1)}</style>";
+
+// 置信区间色带的填充不透明度
+const CI_BAND_OPACITY: f64 = 0.2;
+
+// 点击带发音的单词时播放 data-audio-src 指向的音频
+const AUDIO_SCRIPT: &str = r#"<script><![CDATA[document.querySelectorAll(".word-audio").forEach(function(el){el.addEventListener("click",function(e){e.preventDefault();new Audio(el.getAttribute("data-audio-src")).play();});});]]></script>"#;
+
+// 频次环的描边不透明度
+const RING_OPACITY: f64 = 0.15;
+
+// 转义 XML 特殊字符
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 将最近一次布局导出为 SVG，include_guides 开启时附带参考线
+    #[wasm_bindgen]
+    pub fn generate_svg(&self) -> String {
+        let guide_every = self
+            .options
+            .include_guides
+            .then_some(self.options.guide_every_px);
+        self.svg_document(guide_every)
+    }
+
+    // 导出带参考线的 SVG：每隔 guide_every_px 像素绘制一条横向和纵向的虚线
+    #[wasm_bindgen]
+    pub fn generate_svg_with_guides(&self, guide_every_px: u32) -> String {
+        self.svg_document(Some(guide_every_px))
+    }
+
+    // 设置 generate_svg 是否附带参考线及其间距
+    #[wasm_bindgen]
+    pub fn set_include_guides(&mut self, include_guides: bool, guide_every_px: u32) {
+        self.options.include_guides = include_guides;
+        self.options.guide_every_px = guide_every_px;
+    }
+
+    // 设置 generate_svg 是否让带 audio_url 的单词可点击播放发音
+    #[wasm_bindgen]
+    pub fn set_enable_audio_support(&mut self, enabled: bool) {
+        self.options.enable_audio_support = enabled;
+    }
+
+    // 设置 generate_svg 是否在底部附加字号图例
+    #[wasm_bindgen]
+    pub fn set_include_legend(&mut self, include_legend: bool) {
+        self.options.include_legend = include_legend;
+    }
+
+    // 设置镂空模式：只绘制单词轮廓，描边宽度为字号乘以 stroke_width_ratio
+    #[wasm_bindgen]
+    pub fn set_outline_only(&mut self, outline_only: bool, stroke_width_ratio: f64) {
+        self.options.outline_only = outline_only;
+        self.options.stroke_width_ratio = stroke_width_ratio.max(0.0);
+    }
+
+    // 设置交互模式：generate_svg 中的单词在鼠标悬停时放大到 110%
+    #[wasm_bindgen]
+    pub fn set_interactive(&mut self, interactive: bool) {
+        self.options.interactive = interactive;
+    }
+
+    // 设置是否在单词下方绘制密度热力图
+    #[wasm_bindgen]
+    pub fn set_heatmap_overlay(&mut self, enabled: bool) {
+        self.options.heatmap_overlay = enabled;
+    }
+
+    // 设置热力图的冷色（低密度）和暖色（高密度）
+    #[wasm_bindgen]
+    pub fn set_heatmap_colors(&mut self, cold_color: String, hot_color: String) {
+        self.options.heatmap_cold_color = cold_color;
+        self.options.heatmap_hot_color = hot_color;
+    }
+
+    // 设置频次环：rings 中的每个 n 对应一个圆，半径为第 n 个放置的单词中心到画布中心的距离
+    #[wasm_bindgen]
+    pub fn set_frequency_rings(&mut self, rings: Vec<u32>) {
+        self.options.frequency_rings = Some(rings);
+    }
+
+    // 清除频次环
+    #[wasm_bindgen]
+    pub fn clear_frequency_rings(&mut self) {
+        self.options.frequency_rings = None;
+    }
+
+    // 将最近一次布局绘制到 canvas 上
+    #[wasm_bindgen]
+    pub fn to_canvas(&mut self, ctx: &CanvasRenderingContext2d) {
+        let width = self.options.width as f64;
+        let height = self.options.height as f64;
+
+        ctx.clear_rect(0.0, 0.0, width, height);
+
+        if let Some(color) = &self.options.background_color {
+            ctx.set_fill_style_str(color);
+            ctx.fill_rect(0.0, 0.0, width, height);
+        }
+
+        // 图片尚未加载完成时跳过，并在 warnings 中说明，加载完成后再次调用即可
+        if let Some(url) = self.options.background_image_url.clone() {
+            if !self.draw_background_image(ctx) {
+                let warning = format!(
+                    "background image \"{}\" was not drawn because it has not finished loading",
+                    url
+                );
+                if !self.warnings.contains(&warning) {
+                    self.warnings.push(warning);
+                }
+            }
+        }
+
+        self.draw_grid_background(ctx);
+        self.draw_watermark(ctx);
+
+        for word in &self.placed {
+            draw_word(self, ctx, word);
+        }
+    }
+}
+
+impl LayoutEngine {
+    // 完整的 SVG 文档
+    fn svg_document(&self, guide_every: Option<u32>) -> String {
+        let width = self.options.width;
+        let height = self.options.height;
+
+        // 图例位于词云区域下方，需要相应增加 SVG 的高度
+        let legend = self.svg_legend();
+        let total_height = height as f64 + legend.as_ref().map_or(0.0, |(_, h)| *h);
+
+        // 设置了打印尺寸时宽高以毫米给出，viewBox 仍为像素
+        let (width_attr, height_attr) = match self.mm_per_px() {
+            Some(mm) => (
+                format!("{}mm", self.round_output(width as f64 * mm)),
+                format!("{}mm", self.round_output(total_height * mm)),
+            ),
+            None => (width.to_string(), total_height.to_string()),
+        };
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}"{}>"#,
+            width_attr,
+            height_attr,
+            width,
+            total_height,
+            self.svg_root_accessibility()
+        );
+        svg.push_str(&self.svg_description());
+
+        // 背景色和背景图片必须是最先的子元素，位于所有单词之下
+        if let Some(color) = &self.options.background_color {
+            svg.push_str(&format!(
+                r#"<rect width="{}" height="{}" fill="{}"/>"#,
+                width,
+                total_height,
+                escape_xml(color)
+            ));
+        }
+        if let Some(url) = &self.options.background_image_url {
+            svg.push_str(&format!(
+                r#"<image href="{}" width="{}" height="{}" preserveAspectRatio="xMidYMid slice"/>"#,
+                escape_xml(url),
+                width,
+                height
+            ));
+        }
+
+        if let Some(grid_background) = self.svg_grid_background() {
+            svg.push_str(&grid_background);
+        }
+
+        if let Some(watermark) = self.svg_watermark() {
+            svg.push_str(&watermark);
+        }
+
+        if self.options.heatmap_overlay {
+            svg.push_str(&self.svg_heatmap());
+        }
+
+        // 参考线位于背景之上、单词之下
+        if let Some(every) = guide_every.filter(|&every| every > 0) {
+            svg.push_str(&self.svg_guides(every));
+        }
+
+        if let Some(rings) = &self.options.frequency_rings {
+            svg.push_str(&self.svg_frequency_rings(rings));
+        }
+
+        svg.push_str(&self.svg_defs());
+
+        // 置信区间色带位于单词之下
+        for word in &self.placed {
+            if let (Some(low), Some(high)) = (word.ci_low_size, word.ci_high_size) {
+                svg.push_str(&self.svg_ci_band(word, low, high));
+            }
+        }
+
+        // 交互模式：每个单词包在以自身中心为变换原点的 <g class="word"> 中，悬停时放大
+        if self.options.interactive {
+            svg.push_str(INTERACTIVE_STYLE);
+        }
+        for (index, word) in self.placed.iter().enumerate() {
+            let element = if self.options.interactive {
+                format!(
+                    r#"<g class="word" style="transform-origin:{}px {}px">{}</g>"#,
+                    word.x,
+                    word.y,
+                    self.svg_word(index, word)
+                )
+            } else {
+                self.svg_word(index, word)
+            };
+            // 带发音的单词包在可点击的 <a> 中，由下方的脚本播放
+            match word
+                .audio_url
+                .as_deref()
+                .filter(|_| self.options.enable_audio_support)
+            {
+                Some(url) => svg.push_str(&format!(
+                    r#"<a href="javascript:void(0)" class="word-audio" data-audio-src="{}">{}</a>"#,
+                    escape_xml(url),
+                    element
+                )),
+                None => svg.push_str(&element),
+            }
+        }
+        let has_audio = self.placed.iter().any(|word| word.audio_url.is_some());
+        if self.options.enable_audio_support && has_audio {
+            svg.push_str(AUDIO_SCRIPT);
+        }
+
+        for word in &self.placed {
+            if let Some(annotation) = &word.annotation {
+                svg.push_str(&self.svg_annotation(word, annotation));
+            }
+        }
+
+        if let Some((legend, _)) = legend {
+            svg.push_str(&legend);
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+
+    // 字号图例：在最小和最大权重之间等距取若干权重，绘制对应字号的示例文字和权重标签
+    // 返回图例元素及其高度；未开启或尚未布局时返回 None
+    fn svg_legend(&self) -> Option<(String, f64)> {
+        if !self.options.include_legend {
+            return None;
+        }
+        let context = self.layout_context.as_ref()?;
+        let (min_weight, max_weight) = (context.min_weight, context.max_weight);
+
+        let steps = if max_weight > min_weight {
+            LEGEND_STEPS
+        } else {
+            1
+        };
+        let samples: Vec<(f64, f64)> = (0..steps)
+            .map(|i| {
+                let t = if steps > 1 {
+                    i as f64 / (steps - 1) as f64
+                } else {
+                    0.0
+                };
+                let weight = min_weight + (max_weight - min_weight) * t;
+                (weight, self.word_size(weight, min_weight, max_weight))
+            })
+            .collect();
+        let tallest = samples.iter().map(|(_, size)| *size).fold(0.0, f64::max);
+        let legend_height = LEGEND_PADDING * 3.0 + tallest + LEGEND_LABEL_SIZE;
+
+        let column_width = self.options.width as f64 / samples.len() as f64;
+        let sample_y = LEGEND_PADDING + tallest / 2.0;
+        let label_y = LEGEND_PADDING * 2.0 + tallest + LEGEND_LABEL_SIZE / 2.0;
+        let mut legend = format!(
+            r#"<g id="legend" transform="translate(0 {})">"#,
+            self.options.height
+        );
+        for (i, (weight, size)) in samples.iter().enumerate() {
+            let x = column_width * (i as f64 + 0.5);
+            legend.push_str(&format!(
+                r##"<text x="{}" y="{}" font-family="{}" font-weight="{}" font-size="{}" fill="#333333" text-anchor="middle" dominant-baseline="middle">{}</text>"##,
+                x,
+                sample_y,
+                escape_xml(&self.options.font_family),
+                escape_xml(&self.options.font_weight),
+                size,
+                LEGEND_SAMPLE
+            ));
+            legend.push_str(&format!(
+                r##"<text x="{}" y="{}" font-family="{}" font-size="{}" fill="#666666" text-anchor="middle" dominant-baseline="middle">{}</text>"##,
+                x,
+                label_y,
+                escape_xml(&self.options.font_family),
+                LEGEND_LABEL_SIZE,
+                format_weight(*weight)
+            ));
+        }
+        legend.push_str("</g>");
+        Some((legend, legend_height))
+    }
+
+    // <defs>：标注箭头和渐变填充，没有需要定义的内容时返回空字符串
+    pub(crate) fn svg_defs(&self) -> String {
+        let mut defs = String::new();
+
+        // 箭头标记只在存在标注时定义一次
+        if self.placed.iter().any(|word| word.annotation.is_some()) {
+            defs.push_str(&format!(
+                r#"<marker id="annotation-arrow" viewBox="0 0 10 10" refX="9" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse"><path d="M0,0 L10,5 L0,10 z" fill="{}"/></marker>"#,
+                ANNOTATION_COLOR
+            ));
+        }
+
+        for (index, word) in self.placed.iter().enumerate() {
+            if let Some(angle) = word.gradient_angle {
+                let color = word.color.as_deref().unwrap_or("#000000");
+                defs.push_str(&format!(
+                    r#"<linearGradient id="grad_{}" gradientTransform="rotate({} 0.5 0.5)"><stop offset="0" stop-color="{}"/><stop offset="1" stop-color="{}"/></linearGradient>"#,
+                    index,
+                    angle,
+                    escape_xml(color),
+                    escape_xml(&tint_color(color, GRADIENT_TINT))
+                ));
+            }
+        }
+
+        if defs.is_empty() {
+            defs
+        } else {
+            format!("<defs>{}</defs>", defs)
+        }
+    }
+
+    // 热力图：按区块占用密度着色，密度相对最密的区块归一化
+    fn svg_heatmap(&self) -> String {
+        let map = self.density_map(HEATMAP_TILES, HEATMAP_TILES);
+        let max_density = map.density.iter().flatten().copied().fold(0.0, f64::max);
+        let black = Rgb {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+        };
+        let cold = Rgb::parse(&self.options.heatmap_cold_color).unwrap_or(black);
+        let hot = Rgb::parse(&self.options.heatmap_hot_color).unwrap_or(black);
+
+        let tile_width = self.options.width as f64 / HEATMAP_TILES as f64;
+        let tile_height = self.options.height as f64 / HEATMAP_TILES as f64;
+        let mut heatmap = format!(r#"<g id="heatmap" fill-opacity="{}">"#, HEATMAP_OPACITY);
+        for (row, densities) in map.density.iter().enumerate() {
+            for (col, &density) in densities.iter().enumerate() {
+                let t = if max_density > 0.0 {
+                    density / max_density
+                } else {
+                    0.0
+                };
+                heatmap.push_str(&format!(
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                    col as f64 * tile_width,
+                    row as f64 * tile_height,
+                    tile_width,
+                    tile_height,
+                    cold.mix(hot, t).to_hex()
+                ));
+            }
+        }
+        heatmap.push_str("</g>");
+        heatmap
+    }
+
+    // 参考线：浅色虚线，不遮挡单词
+    fn svg_guides(&self, every: u32) -> String {
+        let width = self.options.width;
+        let height = self.options.height;
+        let mut guides = String::from(
+            r##"<g stroke="#000000" stroke-opacity="0.1" stroke-width="1" stroke-dasharray="4 4">"##,
+        );
+        for x in (every..width).step_by(every as usize) {
+            guides.push_str(&format!(
+                r#"<line x1="{}" y1="0" x2="{}" y2="{}"/>"#,
+                x, x, height
+            ));
+        }
+        for y in (every..height).step_by(every as usize) {
+            guides.push_str(&format!(
+                r#"<line x1="0" y1="{}" x2="{}" y2="{}"/>"#,
+                y, width, y
+            ));
+        }
+        guides.push_str("</g>");
+        guides
+    }
+
+    // 频次环：以画布中心为圆心的虚线圆，显示各距离内能容纳多少单词；
+    // n 为 0 或超过已放置单词数时跳过
+    fn svg_frequency_rings(&self, rings: &[u32]) -> String {
+        let center_x = self.options.width as f64 / 2.0;
+        let center_y = self.options.height as f64 / 2.0;
+        let mut svg = format!(
+            r##"<g id="frequency-rings" fill="none" stroke="#000000" stroke-opacity="{}" stroke-width="1" stroke-dasharray="6 4">"##,
+            RING_OPACITY
+        );
+        for &n in rings {
+            let word = match (n as usize).checked_sub(1).and_then(|i| self.placed.get(i)) {
+                Some(word) => word,
+                None => continue,
+            };
+            let radius = (word.x - center_x).hypot(word.y - center_y);
+            svg.push_str(&format!(
+                r#"<circle cx="{}" cy="{}" r="{:.2}" data-words="{}"/>"#,
+                center_x, center_y, radius, n
+            ));
+        }
+        svg.push_str("</g>");
+        svg
+    }
+
+    // 单个 <text> 元素
+    fn svg_text(&self, x: f64, y: f64, size: f64, color: &str, text: &str, extra: &str) -> String {
+        // 镂空模式只描边不填充，描边宽度随字号缩放
+        let paint = if self.options.outline_only {
+            format!(
+                r#"fill="none" stroke="{}" stroke-width="{}""#,
+                escape_xml(color),
+                size * self.options.stroke_width_ratio
+            )
+        } else {
+            format!(r#"fill="{}""#, escape_xml(color))
+        };
+        // 交互模式下记录原始字号，供脚本读取
+        let mut data_size = if self.options.interactive {
+            format!(r#" data-size="{}""#, size)
+        } else {
+            String::new()
+        };
+        // 打印模式下附带以磅为单位的字号
+        if let Some(mm) = self.mm_per_px() {
+            data_size.push_str(&format!(
+                r#" data-pt-size="{}""#,
+                self.round_output(mm_to_pt(size * mm))
+            ));
+        }
+        format!(
+            r#"<text x="{}" y="{}" font-family="{}" font-weight="{}" font-size="{}" letter-spacing="{}" {} text-anchor="middle" dominant-baseline="middle" direction="{}"{}{}>{}</text>"#,
+            x,
+            y,
+            escape_xml(&self.options.font_family),
+            escape_xml(&self.options.font_weight),
+            size,
+            self.options.letter_spacing,
+            paint,
+            escape_xml(&self.options.text_direction),
+            data_size,
+            extra,
+            escape_xml(text)
+        )
+    }
+
+    // 单词对应的 SVG 元素，组合词的各部分放在同一个旋转的 <g> 中
+    pub(crate) fn svg_word(&self, index: usize, word: &WordPosition) -> String {
+        let size = word.size.unwrap_or(self.options.max_size);
+        let gradient = format!("url(#grad_{})", index);
+        let color = match word.gradient_angle {
+            Some(_) => gradient.as_str(),
+            None => word.color.as_deref().unwrap_or("#000000"),
+        };
+        let transform = format!(
+            r#" transform="rotate({} {} {})""#,
+            self.svg_rotation(word.rotate),
+            word.x,
+            word.y
+        );
+
+        // 带注音的单词按注音和单词本身两部分绘制；带趋势线的单词文字左移，趋势线画在右侧
+        let phonetic = self.phonetic_parts(word);
+        let sparkline = self.sparkline(word);
+        let text_x = word.x + sparkline.as_ref().map_or(0.0, |s| s.text_dx);
+        // 由多个 <text> 组成的单词只在外层 <g> 上给出一次标签和不透明度
+        let mut aria_label = self.svg_aria_label(word);
+        if let Some(opacity) = word.opacity {
+            aria_label.push_str(&format!(r#" opacity="{}""#, opacity));
+        }
+        let mut svg = match phonetic.as_ref().or(word.parts.as_ref()) {
+            Some(parts) => {
+                let mut group = format!("<g{}{}>", transform, aria_label);
+                for part in parts {
+                    group.push_str(&self.svg_text(
+                        text_x + part.dx,
+                        word.y + part.dy,
+                        part.size,
+                        part.color.as_deref().unwrap_or(color),
+                        &part.text,
+                        "",
+                    ));
+                }
+                group.push_str("</g>");
+                group
+            }
+            None => self.svg_text(
+                text_x,
+                word.y,
+                size,
+                color,
+                &word.text,
+                &format!("{}{}", transform, aria_label),
+            ),
+        };
+        if let Some(sparkline) = &sparkline {
+            svg.push_str(&self.svg_sparkline(word, sparkline));
+        }
+        svg
+    }
+
+    // 置信区间色带：外框为上限字号的包围盒，内框为下限字号的包围盒，
+    // 以 evenodd 填充只绘制两者之间的环带
+    fn svg_ci_band(&self, word: &WordPosition, low_size: f64, high_size: f64) -> String {
+        let rect = |size: f64| {
+            let half_width = self.estimate_word_width(&word.text, size) / 2.0;
+            let half_height = size / 2.0;
+            format!(
+                "M{} {}H{}V{}H{}Z",
+                word.x - half_width,
+                word.y - half_height,
+                word.x + half_width,
+                word.y + half_height,
+                word.x - half_width
+            )
+        };
+        format!(
+            r#"<path d="{}{}" fill="{}" fill-opacity="{}" fill-rule="evenodd" transform="rotate({} {} {})"/>"#,
+            rect(high_size),
+            rect(low_size),
+            escape_xml(word.color.as_deref().unwrap_or("#000000")),
+            CI_BAND_OPACITY,
+            self.svg_rotation(word.rotate),
+            word.x,
+            word.y
+        )
+    }
+
+    // 标注：从单词包围盒边缘沿指定方向引出箭头线，末端放置标注文字
+    fn svg_annotation(&self, word: &WordPosition, annotation: &str) -> String {
+        let direction = word.annotation_direction.as_deref().unwrap_or("top-right");
+        let (dir_x, dir_y) = annotation_direction_vector(direction);
+
+        let (min_x, min_y, max_x, max_y) = self.word_obb(word).bounds();
+        let start_x = word.x + dir_x * (max_x - min_x) / 2.0;
+        let start_y = word.y + dir_y * (max_y - min_y) / 2.0;
+        let length = (dir_x * dir_x + dir_y * dir_y).sqrt();
+        let end_x = start_x + dir_x / length * ANNOTATION_LENGTH;
+        let end_y = start_y + dir_y / length * ANNOTATION_LENGTH;
+
+        let anchor = match dir_x {
+            d if d > 0.0 => "start",
+            d if d < 0.0 => "end",
+            _ => "middle",
+        };
+        let baseline = match dir_y {
+            d if d > 0.0 => "hanging",
+            d if d < 0.0 => "auto",
+            _ => "middle",
+        };
+
+        format!(
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="1" marker-start="url(#annotation-arrow)"/><text x="{}" y="{}" font-family="{}" font-size="{}" fill="{}" text-anchor="{}" dominant-baseline="{}">{}</text>"#,
+            start_x,
+            start_y,
+            end_x,
+            end_y,
+            ANNOTATION_COLOR,
+            end_x,
+            end_y,
+            escape_xml(&self.options.font_family),
+            ANNOTATION_FONT_SIZE,
+            ANNOTATION_COLOR,
+            anchor,
+            baseline,
+            escape_xml(annotation)
+        )
+    }
+}
+
+// 在 canvas 上绘制单个单词，to_canvas 和像素碰撞模式的栅格化共用
+pub(crate) fn draw_word(
+    engine: &LayoutEngine,
+    ctx: &CanvasRenderingContext2d,
+    word: &WordPosition,
+) {
+    let size = word.size.unwrap_or(engine.options.max_size);
+    let color = word.color.as_deref().unwrap_or("#000000");
+
+    ctx.save();
+    if let Some(opacity) = word.opacity {
+        ctx.set_global_alpha(opacity);
+    }
+    // letterSpacing 和 direction 在 web-sys 中尚未提供，直接设置 JS 属性
+    let _ = Reflect::set(
+        ctx,
+        &JsValue::from_str("letterSpacing"),
+        &JsValue::from_str(&format!("{}px", engine.options.letter_spacing)),
+    );
+    let _ = Reflect::set(
+        ctx,
+        &JsValue::from_str("direction"),
+        &JsValue::from_str(&engine.options.text_direction),
+    );
+    let _ = ctx.translate(word.x, word.y);
+    let _ = ctx.rotate(word.rotate);
+    ctx.set_text_align("center");
+    ctx.set_text_baseline("middle");
+
+    let single = [(word.text.as_str(), size, color, 0.0, 0.0)];
+    let phonetic = engine.phonetic_parts(word);
+    let pieces: Vec<(&str, f64, &str, f64, f64)> = match phonetic.as_ref().or(word.parts.as_ref()) {
+        Some(parts) => parts
+            .iter()
+            .map(|part| {
+                let part_color = part.color.as_deref().unwrap_or(color);
+                (part.text.as_str(), part.size, part_color, part.dx, part.dy)
+            })
+            .collect(),
+        None => single.to_vec(),
+    };
+
+    let sparkline = engine.sparkline(word);
+    let text_dx = sparkline.as_ref().map_or(0.0, |s| s.text_dx);
+    for (text, size, color, dx, dy) in pieces {
+        let dx = dx + text_dx;
+        ctx.set_font(&format!(
+            "{} {}px {}",
+            engine.options.font_weight, size, engine.options.font_family
+        ));
+        if engine.options.outline_only {
+            ctx.set_stroke_style_str(color);
+            ctx.set_line_width(size * engine.options.stroke_width_ratio);
+            let _ = ctx.stroke_text(text, dx, dy);
+        } else {
+            ctx.set_fill_style_str(color);
+            let _ = ctx.fill_text(text, dx, dy);
+        }
+    }
+    if let Some(sparkline) = &sparkline {
+        draw_sparkline(ctx, sparkline, color);
+    }
+    ctx.restore();
+}
+
+impl WordCloud {
+    // 按 background_image_url 开始加载背景图片；没有 DOM 时不加载，to_canvas 会跳过背景
+    pub(crate) fn preload_background_image(&mut self) {
+        let url = match &self.options.background_image_url {
+            Some(url) if self.require_canvas().is_ok() => url.clone(),
+            _ => {
+                self.background_image = None;
+                return;
+            }
+        };
+        if self
+            .background_image
+            .as_ref()
+            .is_some_and(|(loaded, _)| *loaded == url)
+        {
+            return;
+        }
+        self.background_image = HtmlImageElement::new().ok().map(|image| {
+            image.set_src(&url);
+            (url, image)
+        });
+    }
+
+    // 绘制预先加载的背景图片，按 xMidYMid slice 的方式裁剪以铺满画布。
+    // 图片还没有加载完成时返回 false
+    fn draw_background_image(&mut self, ctx: &CanvasRenderingContext2d) -> bool {
+        // 背景地址可能经由 set_options 等途径修改，绘制前按当前地址补上加载
+        self.preload_background_image();
+        let image = match &self.background_image {
+            Some((_, image)) => image,
+            None => return false,
+        };
+
+        let natural_width = image.natural_width() as f64;
+        let natural_height = image.natural_height() as f64;
+        if !image.complete() || natural_width <= 0.0 || natural_height <= 0.0 {
+            return false;
+        }
+
+        let width = self.options.width as f64;
+        let height = self.options.height as f64;
+
+        // 以较大的缩放比例覆盖画布，再居中裁剪
+        let scale = (width / natural_width).max(height / natural_height);
+        let source_width = width / scale;
+        let source_height = height / scale;
+        let source_x = (natural_width - source_width) / 2.0;
+        let source_y = (natural_height - source_height) / 2.0;
+
+        let _ = ctx.draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+            image,
+            source_x,
+            source_y,
+            source_width,
+            source_height,
+            0.0,
+            0.0,
+            width,
+            height,
+        );
+        true
+    }
+}
+
+// 标注方向对应的方向向量（y 轴向下），未知方向按 "top-right" 处理
+fn annotation_direction_vector(direction: &str) -> (f64, f64) {
+    match direction {
+        "top" => (0.0, -1.0),
+        "bottom" => (0.0, 1.0),
+        "left" => (-1.0, 0.0),
+        "right" => (1.0, 0.0),
+        "top-left" => (-1.0, -1.0),
+        "bottom-left" => (-1.0, 1.0),
+        "bottom-right" => (1.0, 1.0),
+        _ => (1.0, -1.0),
+    }
+}
+
+// 图例中的权重标签：最多保留两位小数并去掉末尾的 0
+fn format_weight(weight: f64) -> String {
+    let formatted = format!("{:.2}", weight);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+// 在 Node 中运行：没有 DOM，背景图片无法加载
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use js_sys::{Function, Reflect};
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+    use web_sys::CanvasRenderingContext2d;
+
+    use crate::test_cloud;
+
+    // 记录 drawImage 调用次数、其余方法什么也不做的假上下文
+    fn recording_context() -> JsValue {
+        Function::new_no_args(
+            r#"
+            const ctx = { images: 0, drawImage() { this.images += 1; } };
+            return new Proxy(ctx, {
+                get: (target, name) => (name in target ? target[name] : () => {}),
+                set: (target, name, value) => ((target[name] = value), true),
+            });
+            "#,
+        )
+        .call0(&JsValue::NULL)
+        .unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn unloaded_background_image_is_skipped_with_a_single_warning() {
+        let mut cloud = test_cloud();
+        cloud.set_background_image_url(Some("background.png".to_string()));
+        cloud.generate_layout(crate::weighted_words(3));
+        let ctx = recording_context();
+        cloud.to_canvas(ctx.unchecked_ref::<CanvasRenderingContext2d>());
+        cloud.to_canvas(ctx.unchecked_ref::<CanvasRenderingContext2d>());
+
+        let images = Reflect::get(&ctx, &"images".into()).unwrap();
+        assert_eq!(images.as_f64(), Some(0.0));
+        let warnings: Vec<String> = serde_json::from_str(&cloud.get_warnings()).unwrap();
+        let skipped: Vec<&String> = warnings
+            .iter()
+            .filter(|warning| warning.contains("background.png"))
+            .collect();
+        assert_eq!(skipped.len(), 1);
+    }
+}
@@ -0,0 +1,306 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{LayoutEngine, WordCloud};
+
+// 相互独立的随机数流：每个流由主种子、流编号以及（可选的）单词 key 派生，
+// 某个流多消耗的随机数不会影响其他流，也不会影响其他单词。
+// 流编号参与派生种子，改动已有编号会改变相同种子下的布局
+#[derive(Clone, Copy)]
+pub(crate) enum RngStream {
+    Rotation = 1,
+    Color = 2,
+    Animation = 5,
+    Admission = 6,
+}
+
+// SplitMix64 伪随机数生成器
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        mix(self.state)
+    }
+
+    // [0, 1) 区间内的均匀分布
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// Halton 低差异序列中第 index 个数在给定基数下的值，位于 [0, 1)
+pub(crate) fn halton(mut index: u32, base: u32) -> f64 {
+    let mut result = 0.0;
+    let mut fraction = 1.0;
+    while index > 0 {
+        fraction /= base as f64;
+        result += fraction * (index % base) as f64;
+        index /= base;
+    }
+    result
+}
+
+// SplitMix64 的混合函数
+fn mix(value: u64) -> u64 {
+    let mut z = value;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// 未设置种子时的随机主种子：wasm 中取自 Math.random，原生目标上取自标准库的随机哈希键。
+// 只取 u32 范围，get_layout_seed 返回的种子总能传回 set_seed
+#[cfg(target_arch = "wasm32")]
+fn random_seed() -> u64 {
+    (js_sys::Math::random() * (1u64 << 32) as f64) as u64
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn random_seed() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    RandomState::new().build_hasher().finish() >> 32
+}
+
+// FNV-1a 字符串哈希
+fn hash_key(key: &str) -> u64 {
+    key.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置随机种子，相同种子和输入会得到相同的布局。
+    // 参数为 u32，JS 中直接传普通数字即可；u64 会要求传 BigInt，set_seed(42) 会抛出异常。
+    // 内部仍按 u64 保存，run 的配置和 LayoutBuilder 可以给出完整的 u64 种子
+    #[wasm_bindgen]
+    pub fn set_seed(&mut self, seed: u32) {
+        self.options.seed = Some(seed as u64);
+    }
+
+    // 清除随机种子，每次布局使用新的随机种子
+    #[wasm_bindgen]
+    pub fn clear_seed(&mut self) {
+        self.options.seed = None;
+    }
+
+    // 最近一次布局实际使用的主种子，以 JS 数字返回。未设置种子时传给 set_seed 即可复现这次布局；
+    // 通过 run 配置的超过 2^53 的种子只能得到近似值
+    #[wasm_bindgen]
+    pub fn get_layout_seed(&self) -> f64 {
+        self.layout_seed as f64
+    }
+}
+
+impl LayoutEngine {
+    // 本次布局的主种子：未设置种子时随机生成
+    pub(crate) fn next_layout_seed(&self) -> u64 {
+        self.options.seed.unwrap_or_else(random_seed)
+    }
+
+    // 派生指定流的随机数生成器，key 通常为单词的 key
+    pub(crate) fn rng(&self, stream: RngStream, key: Option<&str>) -> Rng {
+        let mut seed = mix(self.layout_seed ^ mix(stream as u64));
+        if let Some(key) = key {
+            seed = mix(seed ^ hash_key(key));
+        }
+        Rng::new(seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::test_cloud;
+
+    // 十二个单词，weight_of 可以替换个别单词的权重，text_of 可以替换个别单词的文字
+    fn words(weight_of: impl Fn(usize) -> f64, text_of: impl Fn(usize) -> String) -> String {
+        let words: Vec<serde_json::Value> = (0..12)
+            .map(|i| {
+                serde_json::json!({"key": format!("w{}", i), "text": text_of(i), "weight": weight_of(i)})
+            })
+            .collect();
+        serde_json::to_string(&words).unwrap()
+    }
+
+    fn default_weight(i: usize) -> f64 {
+        (12 - i) as f64
+    }
+
+    fn default_text(i: usize) -> String {
+        format!("w{}", i)
+    }
+
+    fn random_cloud() -> WordCloud {
+        let mut cloud = test_cloud();
+        cloud.set_rotation_range(45.0);
+        cloud.set_rotation_unit("degrees".to_string());
+        cloud.set_palette(
+            ["#1b9e77", "#d95f02", "#7570b3", "#e7298a", "#66a61e"]
+                .iter()
+                .map(|color| color.to_string())
+                .collect(),
+        );
+        cloud.set_palette_mode("random".to_string());
+        cloud
+    }
+
+    // 每个已放置单词的 (x, y, 旋转角度, 颜色)，按 key 索引
+    fn placements(cloud: &WordCloud) -> HashMap<String, (f64, f64, f64, String)> {
+        cloud
+            .placed
+            .iter()
+            .map(|word| {
+                let color = word.color.clone().unwrap();
+                (word.key().to_string(), (word.x, word.y, word.rotate, color))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn streams_are_independent_per_stream_and_key() {
+        let mut cloud = test_cloud();
+        cloud.layout_seed = 7;
+        let first = cloud.rng(RngStream::Color, Some("a")).next_u64();
+
+        // 在其他流或其他单词上多消耗随机数，不影响 (Color, "a") 的序列
+        let mut rotation = cloud.rng(RngStream::Rotation, Some("a"));
+        let mut other = cloud.rng(RngStream::Color, Some("b"));
+        for _ in 0..100 {
+            rotation.next_u64();
+            other.next_u64();
+        }
+        assert_eq!(cloud.rng(RngStream::Color, Some("a")).next_u64(), first);
+        assert_ne!(rotation.next_u64(), first);
+        assert_ne!(cloud.rng(RngStream::Color, Some("b")).next_u64(), first);
+    }
+
+    #[test]
+    fn shrinking_one_word_leaves_other_rotations_and_colors_unchanged() {
+        let mut baseline = random_cloud();
+        baseline.generate_layout(words(default_weight, default_text));
+
+        // w2 的文字超出画布宽度，布局时需要二分查找缩小后再放置
+        let mut retried = random_cloud();
+        retried.generate_layout(words(default_weight, |i| {
+            if i == 2 {
+                "extraordinarilylongheadline".to_string()
+            } else {
+                default_text(i)
+            }
+        }));
+        assert_eq!(baseline.layout_stats.oversized, 0);
+        assert_eq!(retried.layout_stats.oversized, 1);
+
+        // 缩小后的 w2 占据更多空间，个别小单词可能放不下；放下的单词旋转和颜色不变
+        let (before, after) = (placements(&baseline), placements(&retried));
+        assert!(after.len() >= 10);
+        for (key, (_, _, rotate, color)) in &after {
+            let (_, _, old_rotate, old_color) = &before[key];
+            assert_eq!((rotate, color), (old_rotate, old_color), "{}", key);
+        }
+    }
+
+    #[test]
+    fn changing_one_weight_only_changes_the_layout_locally() {
+        let mut baseline = random_cloud();
+        baseline.generate_layout(words(default_weight, default_text));
+
+        // 只改变 w10 的权重，权重范围和放置顺序都不变
+        let mut changed = random_cloud();
+        changed.generate_layout(words(
+            |i| if i == 10 { 1.5 } else { default_weight(i) },
+            default_text,
+        ));
+
+        // 在 w10 之前放置的单词位置完全不变，之后的单词只可能移动位置
+        let (before, after) = (placements(&baseline), placements(&changed));
+        assert_eq!(before.len(), after.len());
+        for (key, placement) in &before {
+            let new_placement = &after[key];
+            if key == "w10" || key == "w11" {
+                assert_eq!(placement.2, new_placement.2);
+                assert_eq!(placement.3, new_placement.3);
+            } else {
+                assert_eq!(placement, new_placement, "{}", key);
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_and_input_give_byte_identical_output() {
+        let input = words(default_weight, default_text);
+        let mut cloud = random_cloud();
+        cloud.set_seed(7);
+        let first = cloud.generate_layout(input.clone());
+        let first_score = cloud.compute_visual_complexity_score();
+        let second = cloud.generate_layout(input.clone());
+        assert_eq!(first, second);
+        // 旋转多样性的熵按固定顺序累加，分数逐位相同
+        assert_eq!(
+            first_score.to_bits(),
+            cloud.compute_visual_complexity_score().to_bits()
+        );
+
+        // 新实例使用同一种子得到相同输出，换一个种子则旋转和颜色不同
+        let mut other = random_cloud();
+        other.set_seed(7);
+        assert_eq!(other.generate_layout(input.clone()), first);
+        other.set_seed(8);
+        assert_ne!(other.generate_layout(input.clone()), first);
+
+        // 未设置种子时，把实际使用的种子传给 set_seed 即可复现
+        other.clear_seed();
+        let unseeded = other.generate_layout(input.clone());
+        other.set_seed(other.get_layout_seed() as u32);
+        assert_eq!(other.generate_layout(input), unseeded);
+    }
+
+    #[test]
+    fn unseeded_layout_is_reproduced_pixel_for_pixel_from_its_seed() {
+        let input = words(default_weight, default_text);
+        let mut first = random_cloud();
+        first.clear_seed();
+        first.generate_layout(input.clone());
+        let mut second = random_cloud();
+        second.clear_seed();
+        second.generate_layout(input.clone());
+        assert_ne!(first.get_layout_seed(), second.get_layout_seed());
+
+        // 另一个实例用记录的种子重新布局，SVG 输出逐字节相同
+        for cloud in [&first, &second] {
+            let mut replay = random_cloud();
+            replay.set_seed(cloud.get_layout_seed() as u32);
+            replay.generate_layout(input.clone());
+            assert_eq!(replay.get_layout_seed(), cloud.get_layout_seed());
+            assert_eq!(replay.generate_svg(), cloud.generate_svg());
+        }
+        assert_ne!(first.generate_svg(), second.generate_svg());
+    }
+
+    #[test]
+    fn layout_seeds_round_trip_through_js_numbers() {
+        let input = words(default_weight, default_text);
+        let mut cloud = random_cloud();
+        cloud.set_seed(u32::MAX);
+        cloud.generate_layout(input.clone());
+        assert_eq!(cloud.get_layout_seed(), u32::MAX as f64);
+
+        // 随机生成的种子都在 set_seed 能接受的范围内
+        cloud.clear_seed();
+        for _ in 0..16 {
+            cloud.generate_layout(input.clone());
+            assert!(cloud.layout_seed <= u32::MAX as u64);
+            assert_eq!(cloud.get_layout_seed() as u64, cloud.layout_seed);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use wasm_bindgen::prelude::*;
+
+use crate::render::escape_xml;
+use crate::WordCloud;
+
+// 区域边框和标签的样式
+const SECTION_STROKE: &str = "#E67E22";
+const SECTION_DASH: &str = "6 4";
+const SECTION_LABEL_SIZE: f64 = 12.0;
+const SECTION_LABEL_INSET: f64 = 4.0;
+
+// 画布上的命名区域（内部坐标），用于调试视图中显示区域边界
+#[derive(Clone)]
+pub(crate) struct Section {
+    name: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 定义命名区域：(x, y) 为按配置的 y 轴方向给出的角点，同名区域会被覆盖
+    #[wasm_bindgen]
+    pub fn define_section(&mut self, name: String, x: f64, y: f64, width: f64, height: f64) {
+        // 与 words_in_rect 相同，翻转后高度方向相反，统一换算为左上角和正的尺寸
+        let (y, height) = (self.flip_y(y), self.flip_y(y + height) - self.flip_y(y));
+        let section = Section {
+            name,
+            x: x.min(x + width),
+            y: y.min(y + height),
+            width: width.abs(),
+            height: height.abs(),
+        };
+        self.sections.retain(|s| s.name != section.name);
+        self.sections.push(section);
+    }
+
+    // 移除命名区域
+    #[wasm_bindgen]
+    pub fn remove_section(&mut self, name: String) -> bool {
+        let before = self.sections.len();
+        self.sections.retain(|s| s.name != name);
+        self.sections.len() != before
+    }
+
+    // 清除所有命名区域
+    #[wasm_bindgen]
+    pub fn clear_sections(&mut self) {
+        self.sections.clear();
+    }
+
+    // 返回 <g id="sections"> SVG 片段：每个区域一个虚线边框，左上角标注区域名称，
+    // 可叠加在词云 SVG 上查看区域边界
+    #[wasm_bindgen]
+    pub fn generate_sections_overlay_svg(&self) -> String {
+        let mut svg = String::from(r#"<g id="sections">"#);
+        for section in &self.sections {
+            svg.push_str(&format!(
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="{}" stroke-dasharray="{}"/>"#,
+                self.round_output(section.x),
+                self.round_output(section.y),
+                self.round_output(section.width),
+                self.round_output(section.height),
+                SECTION_STROKE,
+                SECTION_DASH
+            ));
+            svg.push_str(&format!(
+                r#"<text x="{}" y="{}" font-family="{}" font-size="{}" fill="{}" dominant-baseline="hanging">{}</text>"#,
+                self.round_output(section.x + SECTION_LABEL_INSET),
+                self.round_output(section.y + SECTION_LABEL_INSET),
+                escape_xml(&self.options.font_family),
+                SECTION_LABEL_SIZE,
+                SECTION_STROKE,
+                escape_xml(&section.name)
+            ));
+        }
+        svg.push_str("</g>");
+        svg
+    }
+}
@@ -0,0 +1,125 @@
+use crate::geometry::Obb;
+use crate::{LayoutContext, LayoutEngine, WordItem};
+
+impl LayoutEngine {
+    // 只有一个单词时不走螺旋搜索：在不超过 max_size 的前提下放到最大，并精确居中。
+    // 缩到 min_size 仍放不下时才记为跳过；居中位置被遮罩挡住时退回普通流程
+    pub(crate) fn place_single_word(&mut self, mut word: WordItem, context: &LayoutContext) {
+        self.layout_stats.single_word = true;
+        let (measured, rotation) = self.prepare_word(&mut word, context);
+        let (center_x, center_y) = self.layout_center();
+        let (safe_min_x, safe_min_y, safe_max_x, safe_max_y) = self.safe_bounds();
+
+        // 以中心为原点，旋转后的包围盒需完整落在安全区内
+        let fits = |width: f64, height: f64| {
+            let (min_x, min_y, max_x, max_y) =
+                Obb::new(center_x, center_y, width, height, rotation).bounds();
+            min_x >= safe_min_x && min_y >= safe_min_y && max_x <= safe_max_x && max_y <= safe_max_y
+        };
+
+        // 二分查找不超过当前字号的最大缩放比例
+        let base = word.size_factor.unwrap_or(1.0);
+        let measured = if fits(measured.width, measured.height) {
+            measured
+        } else {
+            let (mut low, mut high) = (0.0, 1.0);
+            for _ in 0..32 {
+                let mid = (low + high) / 2.0;
+                let candidate = self.measure_word(&word, context, base * mid);
+                if fits(candidate.width, candidate.height) {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            word.size_factor = Some(base * low);
+            self.measure_word(&word, context, base * low)
+        };
+
+        if measured.size < self.options.min_size {
+            self.record_skipped(&word, measured.size, "no_space");
+            return;
+        }
+
+        let obb = Obb::new(
+            center_x,
+            center_y,
+            measured.width,
+            measured.height,
+            rotation,
+        );
+        if self.check_exact_collision(&obb) {
+            self.place_word(word, context);
+            return;
+        }
+
+        self.commit_word(word, measured, center_x, center_y, rotation, context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use crate::{test_cloud, WordCloud};
+
+    fn small_cloud() -> WordCloud {
+        let mut cloud = WordCloud::new(
+            200,
+            100,
+            "sans-serif".to_string(),
+            "normal".to_string(),
+            10.0,
+            40.0,
+            4,
+        );
+        cloud.set_canvas_insets(10.0, 10.0, 10.0, 10.0);
+        cloud
+    }
+
+    fn single(text: &str, rotate: f64) -> String {
+        serde_json::json!([{"text": text, "weight": 1, "rotate": rotate}]).to_string()
+    }
+
+    #[test]
+    fn long_word_on_a_small_canvas_fits_exactly_within_the_insets() {
+        let cases = [
+            ("extraordinarilylongheadline", 0.0, true),
+            ("headlines", FRAC_PI_2, false),
+        ];
+        for (text, rotate, limited_by_width) in cases {
+            let mut cloud = small_cloud();
+            cloud.generate_layout(single(text, rotate));
+            assert_eq!(cloud.placed.len(), 1);
+            assert!(cloud.skipped.is_empty());
+            assert!(cloud.layout_stats.single_word);
+
+            let word = &cloud.placed[0];
+            assert_eq!((word.x, word.y), (100.0, 50.0));
+            let size = word.size.unwrap();
+            assert!((10.0..40.0).contains(&size));
+
+            // 旋转后的包围盒落在安全区内，并在受限的方向上贴住安全区边缘
+            let (min_x, min_y, max_x, max_y) = cloud.word_obb(word).bounds();
+            assert!(min_x >= 10.0 && min_y >= 10.0 && max_x <= 190.0 && max_y <= 90.0);
+            let span = if limited_by_width {
+                max_x - min_x
+            } else {
+                max_y - min_y
+            };
+            let available = if limited_by_width { 180.0 } else { 80.0 };
+            assert!(available - span < 0.01);
+        }
+    }
+
+    #[test]
+    fn single_word_on_a_large_canvas_caps_at_max_size() {
+        let mut cloud = test_cloud();
+        cloud.generate_layout(single("hi", 0.0));
+        assert_eq!(cloud.placed.len(), 1);
+        let word = &cloud.placed[0];
+        assert_eq!(word.size, Some(40.0));
+        assert_eq!((word.x, word.y), cloud.layout_center());
+        assert!(cloud.get_layout_stats().contains(r#""single_word":true"#));
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+
+use crate::rng::RngStream;
+use crate::{LayoutContext, LayoutEngine, WordItem};
+
+// 单词权重在本次布局权重范围内的位置，权重全部相同时为 1
+fn normalized_weight(word: &WordItem, context: &LayoutContext) -> f64 {
+    let range = context.max_weight - context.min_weight;
+    if range > 0.0 {
+        (word.weight - context.min_weight) / range
+    } else {
+        1.0
+    }
+}
+
+impl LayoutEngine {
+    // 按顺序放置全部单词
+    pub(crate) fn place_all(&mut self, words: Vec<WordItem>, context: &LayoutContext) {
+        if words.len() == 1 && self.placed.is_empty() {
+            if let Some(word) = words.into_iter().next() {
+                self.place_single_word(word, context);
+            }
+            return;
+        }
+
+        if self.options.skip_policy == "proportional" && self.placed.is_empty() {
+            self.place_proportional(words, context);
+            return;
+        }
+
+        let mut words = VecDeque::from(words);
+        while !words.is_empty() {
+            self.place_next(&mut words, context);
+        }
+    }
+
+    // proportional 跳过策略：先按顺序放置，画布饱和时以已放下的单词数作为容量，
+    // 然后重新开始，每个单词以缩放后的归一化权重为概率被接纳，
+    // 使期望接纳数与容量相当，被跳过的单词分布在各个权重段；权重最高的单词总会尝试放置。
+    //
+    // 这里必须从空画布重新开始，不能只对饱和后剩余的单词做接纳：
+    // 输入通常按权重从高到低排列，饱和时画布已被前面的高权重单词占满，
+    // 保留它们的话剩余单词只能争抢零星的空隙，结果和 tail 策略一样丢掉整个低权重段。
+    // 第一遍只用来测量容量，一旦饱和就停止，不会放完全部单词；
+    // 画布没有饱和时第一遍就是最终结果，不会重新开始。
+    // 分步布局无法重新开始，由 admit_word 只对饱和后剩余的单词做接纳
+    fn place_proportional(&mut self, words: Vec<WordItem>, context: &LayoutContext) {
+        // 放置前已记录的跳过单词、统计和警告，重新开始时恢复
+        let skipped = self.skipped.clone();
+        let stats = self.layout_stats.clone();
+        let warnings = self.warnings.len();
+
+        let mut queue = VecDeque::from(words.clone());
+        while !queue.is_empty() {
+            self.place_next(&mut queue, context);
+            if self.consecutive_failures >= self.options.full_threshold {
+                break;
+            }
+        }
+        if self.consecutive_failures < self.options.full_threshold {
+            return;
+        }
+
+        let capacity = self.placed.len() as f64;
+        let total: f64 = words
+            .iter()
+            .map(|word| normalized_weight(word, context))
+            .sum();
+        self.clear_placements();
+        self.skipped = skipped;
+        self.layout_stats = stats;
+        self.warnings.truncate(warnings);
+
+        self.admission_scale = Some(if total > 0.0 { capacity / total } else { 1.0 });
+        let mut queue = VecDeque::from(words);
+        while !queue.is_empty() {
+            self.place_next(&mut queue, context);
+        }
+        self.admission_scale = None;
+    }
+
+    // proportional 跳过策略下是否接纳单词：重新放置时按缩放后的归一化权重随机接纳；
+    // 分步布局无法重新开始，画布饱和后剩余单词直接以归一化权重为概率被接纳
+    pub(crate) fn admit_word(&self, word: &WordItem, context: &LayoutContext) -> bool {
+        if self.options.skip_policy != "proportional" {
+            return true;
+        }
+        let weight = normalized_weight(word, context);
+        let probability = match self.admission_scale {
+            Some(scale) => weight * scale,
+            None if self.consecutive_failures >= self.options.full_threshold => weight,
+            None => return true,
+        };
+        weight >= 1.0
+            || probability >= 1.0
+            || self.rng(RngStream::Admission, Some(word.key())).next_f64() < probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cloud, weighted_words, WordCloud};
+
+    // 各权重四分位段中已放置的单词数量（weighted_words 按权重从高到低编号）
+    fn placed_per_quartile(policy: &str, n: usize) -> (WordCloud, [usize; 4]) {
+        let mut cloud = test_cloud();
+        cloud.set_skip_policy(policy.to_string(), 3);
+        cloud.generate_layout(weighted_words(n));
+        let mut quartiles = [0; 4];
+        for word in &cloud.placed {
+            let index: usize = word.text[1..].parse().unwrap();
+            quartiles[index * 4 / n] += 1;
+        }
+        (cloud, quartiles)
+    }
+
+    #[test]
+    fn proportional_skipping_keeps_words_from_every_weight_quartile() {
+        let (tail, tail_quartiles) = placed_per_quartile("tail", 400);
+        let (proportional, proportional_quartiles) = placed_per_quartile("proportional", 400);
+        assert!(tail.skipped.len() > 300);
+        assert!(tail_quartiles.contains(&0));
+        assert!(proportional_quartiles.iter().all(|&count| count > 0));
+
+        // 权重最高的单词总会放下；被跳过的单词分布在各个权重段
+        assert_eq!(proportional.placed[0].text, "w0");
+        assert!(proportional
+            .skipped
+            .iter()
+            .any(|word| word.reason == "skip_policy"));
+        assert_eq!(proportional.placed.len() + proportional.skipped.len(), 400);
+    }
+
+    #[test]
+    fn proportional_skipping_keeps_the_first_pass_when_the_canvas_never_fills() {
+        let mut tail = test_cloud();
+        let tail_layout = tail.generate_layout(weighted_words(5));
+
+        let mut proportional = test_cloud();
+        proportional.set_skip_policy("proportional".to_string(), 3);
+        assert_eq!(proportional.generate_layout(weighted_words(5)), tail_layout);
+        assert!(proportional.skipped.is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::render::escape_xml;
+use crate::{LayoutEngine, MeasuredWord, WordCloud, WordPosition};
+
+// 迷你趋势线的宽度与字号之比、与单词之间的间距与字号之比，高度等于字号
+const SPARKLINE_ASPECT: f64 = 3.0;
+const SPARKLINE_GAP: f64 = 0.25;
+// 趋势线的线宽与字号之比
+const SPARKLINE_STROKE_RATIO: f64 = 0.06;
+
+// 单词右侧的迷你趋势线，坐标相对单词中心、未旋转。
+// 单词文字整体左移 text_dx，为右侧的趋势线让出位置
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Sparkline {
+    pub(crate) text_dx: f64,
+    pub(crate) dx: f64,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    pub(crate) points: Vec<[f64; 2]>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 生成布局，带 trend 的单词附带 sparkline 字段：趋势线的位置、尺寸和折线各点
+    // （相对单词中心、未旋转），供外部渲染器绘制与 generate_svg 相同的迷你趋势线
+    #[wasm_bindgen]
+    pub fn generate_layout_with_word_sparklines(&mut self, words_json: String) -> String {
+        self.generate_layout(words_json);
+
+        let mut words = self.emit_layout(&self.placed);
+        for (word, placed) in words.iter_mut().zip(&self.placed) {
+            word.sparkline = self.sparkline(placed).map(|sparkline| Sparkline {
+                text_dx: self.round_output(sparkline.text_dx),
+                dx: self.round_output(sparkline.dx),
+                width: self.round_output(sparkline.width),
+                height: self.round_output(sparkline.height),
+                points: sparkline
+                    .points
+                    .iter()
+                    .map(|&[x, y]| [self.round_output(x), self.round_output(self.local_dy(y))])
+                    .collect(),
+            });
+        }
+        serde_json::to_string(&words).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+impl LayoutEngine {
+    // 带趋势数据的单词在右侧增加间距和趋势线的宽度，高度至少为一个字号
+    pub(crate) fn with_sparkline(&self, has_trend: bool, measured: MeasuredWord) -> MeasuredWord {
+        if !has_trend {
+            return measured;
+        }
+        MeasuredWord {
+            width: measured.width + measured.size * (SPARKLINE_GAP + SPARKLINE_ASPECT),
+            height: measured.height.max(measured.size),
+            ..measured
+        }
+    }
+
+    // 计算单词的趋势线：数值按最小、最大值归一化到趋势线高度，数值越大越靠上
+    pub(crate) fn sparkline(&self, word: &WordPosition) -> Option<Sparkline> {
+        let trend = word.trend.as_ref()?;
+        let size = word.size.unwrap_or(self.options.max_size);
+        let (total_width, _) = self.word_box(word);
+        let gap = size * SPARKLINE_GAP;
+        let width = size * SPARKLINE_ASPECT;
+        let height = size;
+        let left = total_width / 2.0 - width;
+
+        let min = trend.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = trend.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let y_of = |value: f64| {
+            if max > min {
+                height / 2.0 - (value - min) / (max - min) * height
+            } else {
+                0.0
+            }
+        };
+        let points = match trend.len() {
+            0 => Vec::new(),
+            1 => vec![[left, y_of(trend[0])], [left + width, y_of(trend[0])]],
+            n => trend
+                .iter()
+                .enumerate()
+                .map(|(i, &value)| [left + width * i as f64 / (n - 1) as f64, y_of(value)])
+                .collect(),
+        };
+
+        Some(Sparkline {
+            text_dx: -(gap + width) / 2.0,
+            dx: left + width / 2.0,
+            width,
+            height,
+            points,
+        })
+    }
+
+    // SVG 中的趋势线：与单词使用相同的旋转，颜色取单词颜色
+    pub(crate) fn svg_sparkline(&self, word: &WordPosition, sparkline: &Sparkline) -> String {
+        let points: Vec<String> = sparkline
+            .points
+            .iter()
+            .map(|[x, y]| format!("{},{}", word.x + x, word.y + y))
+            .collect();
+        format!(
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round" transform="rotate({} {} {})"/>"#,
+            points.join(" "),
+            escape_xml(word.color.as_deref().unwrap_or("#000000")),
+            sparkline.height * SPARKLINE_STROKE_RATIO,
+            self.svg_rotation(word.rotate),
+            word.x,
+            word.y
+        )
+    }
+}
+
+// 在已平移、旋转到单词中心的 canvas 上绘制趋势线
+pub(crate) fn draw_sparkline(ctx: &CanvasRenderingContext2d, sparkline: &Sparkline, color: &str) {
+    let mut points = sparkline.points.iter();
+    let first = match points.next() {
+        Some(first) => first,
+        None => return,
+    };
+    ctx.set_stroke_style_str(color);
+    ctx.set_line_width(sparkline.height * SPARKLINE_STROKE_RATIO);
+    ctx.begin_path();
+    ctx.move_to(first[0], first[1]);
+    for point in points {
+        ctx.line_to(point[0], point[1]);
+    }
+    ctx.stroke();
+}
@@ -0,0 +1,219 @@
+use std::collections::BTreeSet;
+
+use wasm_bindgen::prelude::*;
+
+use crate::geometry::Obb;
+use crate::{LayoutEngine, WordCloud};
+
+// 空间索引的桶大小（像素）
+const BUCKET_SIZE: f64 = 64.0;
+
+// 已放置单词的粗粒度空间索引
+#[derive(Clone)]
+pub(crate) struct SpatialIndex {
+    // 建立索引时的布局版本，版本不一致时需要重建
+    generation: u64,
+    cols: usize,
+    rows: usize,
+    buckets: Vec<Vec<usize>>,
+}
+
+impl SpatialIndex {
+    fn bucket_range(&self, min: f64, max: f64, count: usize) -> (usize, usize) {
+        let clamp = |v: f64| ((v / BUCKET_SIZE).floor().max(0.0) as usize).min(count - 1);
+        (clamp(min), clamp(max))
+    }
+
+    // 与矩形范围相交的桶中的所有单词下标
+    fn candidates(&self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> BTreeSet<usize> {
+        let (col_start, col_end) = self.bucket_range(min_x, max_x, self.cols);
+        let (row_start, row_end) = self.bucket_range(min_y, max_y, self.rows);
+        let mut result = BTreeSet::new();
+        for row in row_start..=row_end {
+            for col in col_start..=col_end {
+                result.extend(self.buckets[row * self.cols + col].iter().copied());
+            }
+        }
+        result
+    }
+}
+
+// 已放置单词中心按 x 排序的列表，用于二分查找给定范围内的中心
+#[derive(Clone)]
+pub(crate) struct CenterIndex {
+    generation: u64,
+    centers: Vec<(f64, f64)>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置单词中心之间的最小距离（像素），传入 None 表示不限制；
+    // 即使包围盒不重叠，中心过近的位置也视为碰撞，避免短小的单词挤成一团
+    #[wasm_bindgen]
+    pub fn set_min_word_center_distance(&mut self, distance: Option<f64>) {
+        self.options.min_word_center_distance = distance.filter(|&d| d > 0.0);
+    }
+
+    // 返回旋转包围盒与给定矩形相交的已放置单词
+    #[wasm_bindgen]
+    pub fn words_in_rect(&self, x: f64, y: f64, w: f64, h: f64) -> JsValue {
+        let words: Vec<_> = self
+            .query_rect(x, y, w, h)
+            .into_iter()
+            .map(|index| self.emit_position(&self.placed[index]))
+            .collect();
+        serde_wasm_bindgen::to_value(&words).unwrap_or(JsValue::NULL)
+    }
+
+    // 与 words_in_rect 相同，但只返回单词的 key，传输开销更小
+    #[wasm_bindgen]
+    pub fn words_in_rect_keys(&self, x: f64, y: f64, w: f64, h: f64) -> Vec<String> {
+        self.query_rect(x, y, w, h)
+            .into_iter()
+            .map(|index| self.placed[index].key().to_string())
+            .collect()
+    }
+}
+
+impl LayoutEngine {
+    // 已放置单词发生变化时调用，使依赖布局的缓存失效
+    pub(crate) fn touch_layout(&mut self) {
+        self.generation += 1;
+    }
+
+    // 查询与矩形相交的单词下标（按放置顺序）
+    fn query_rect(&self, x: f64, y: f64, w: f64, h: f64) -> Vec<usize> {
+        // 矩形按配置的 y 轴方向给出，翻转后高度方向相反
+        let (y, h) = (self.flip_y(y), self.flip_y(y + h) - self.flip_y(y));
+        let (min_x, max_x) = (x.min(x + w), x.max(x + w));
+        let (min_y, max_y) = (y.min(y + h), y.max(y + h));
+        let rect = Obb::new(
+            (min_x + max_x) / 2.0,
+            (min_y + max_y) / 2.0,
+            max_x - min_x,
+            max_y - min_y,
+            0.0,
+        );
+
+        let stale = self
+            .spatial_index
+            .borrow()
+            .as_ref()
+            .is_none_or(|index| index.generation != self.generation);
+        if stale {
+            *self.spatial_index.borrow_mut() = Some(self.build_spatial_index());
+        }
+
+        let index = self.spatial_index.borrow();
+        let index = match index.as_ref() {
+            Some(index) => index,
+            None => return Vec::new(),
+        };
+
+        index
+            .candidates(min_x, min_y, max_x, max_y)
+            .into_iter()
+            .filter(|&i| self.word_obb(&self.placed[i]).intersects(&rect))
+            .collect()
+    }
+
+    // 候选中心与某个已放置单词的中心距离小于 min_word_center_distance 时返回 true
+    pub(crate) fn too_close_to_placed(&self, x: f64, y: f64) -> bool {
+        let distance = match self.options.min_word_center_distance {
+            Some(distance) => distance,
+            None => return false,
+        };
+
+        let stale = self
+            .center_index
+            .borrow()
+            .as_ref()
+            .is_none_or(|index| index.generation != self.generation);
+        if stale {
+            let mut centers: Vec<(f64, f64)> =
+                self.placed.iter().map(|word| (word.x, word.y)).collect();
+            centers.sort_by(|a, b| a.0.total_cmp(&b.0));
+            *self.center_index.borrow_mut() = Some(CenterIndex {
+                generation: self.generation,
+                centers,
+            });
+        }
+
+        let index = self.center_index.borrow();
+        let centers = match index.as_ref() {
+            Some(index) => &index.centers,
+            None => return false,
+        };
+        // 只需检查 x 坐标落在 [x - distance, x + distance] 内的中心
+        let start = centers.partition_point(|&(cx, _)| cx < x - distance);
+        centers[start..]
+            .iter()
+            .take_while(|&&(cx, _)| cx <= x + distance)
+            .any(|&(cx, cy)| (cx - x).hypot(cy - y) < distance)
+    }
+
+    // 按旋转后的轴对齐包围盒把单词放入所有覆盖的桶
+    fn build_spatial_index(&self) -> SpatialIndex {
+        let cols = ((self.options.width as f64 / BUCKET_SIZE).ceil() as usize).max(1);
+        let rows = ((self.options.height as f64 / BUCKET_SIZE).ceil() as usize).max(1);
+        let mut index = SpatialIndex {
+            generation: self.generation,
+            cols,
+            rows,
+            buckets: vec![Vec::new(); cols * rows],
+        };
+
+        for (i, word) in self.placed.iter().enumerate() {
+            let (min_x, min_y, max_x, max_y) = self.word_obb(word).bounds();
+            let (col_start, col_end) = index.bucket_range(min_x, max_x, cols);
+            let (row_start, row_end) = index.bucket_range(min_y, max_y, rows);
+            for row in row_start..=row_end {
+                for col in col_start..=col_end {
+                    index.buckets[row * cols + col].push(i);
+                }
+            }
+        }
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::Obb;
+    use crate::{test_cloud, weighted_words};
+
+    #[test]
+    fn left_half_query_matches_brute_force() {
+        let mut cloud = test_cloud();
+        cloud.generate_layout(weighted_words(40));
+        let left_half = Obb::new(100.0, 150.0, 200.0, 300.0, 0.0);
+        let expected: Vec<String> = cloud
+            .placed
+            .iter()
+            .filter(|word| cloud.word_obb(word).intersects(&left_half))
+            .map(|word| word.key().to_string())
+            .collect();
+        assert!(!expected.is_empty() && expected.len() < cloud.placed.len());
+        assert_eq!(cloud.words_in_rect_keys(0.0, 0.0, 200.0, 300.0), expected);
+    }
+
+    // 只含一个单词的布局
+    fn layout(text: &str, x: f64) -> String {
+        serde_json::json!([{"text": text, "weight": 1, "x": x, "y": 150, "rotate": 0, "size": 20}])
+            .to_string()
+    }
+
+    #[test]
+    fn index_is_rebuilt_after_the_layout_changes() {
+        let mut cloud = test_cloud();
+        cloud.load_layout(layout("right", 300.0));
+        assert_eq!(
+            cloud.words_in_rect_keys(200.0, 0.0, 200.0, 300.0),
+            ["right"]
+        );
+        assert!(cloud.words_in_rect_keys(0.0, 0.0, 200.0, 300.0).is_empty());
+
+        cloud.load_layout(layout("left", 100.0));
+        assert_eq!(cloud.words_in_rect_keys(0.0, 0.0, 200.0, 300.0), ["left"]);
+    }
+}
@@ -0,0 +1,205 @@
+use wasm_bindgen::prelude::*;
+
+use crate::env::log;
+use crate::geometry::Obb;
+use crate::{LayoutEngine, MeasuredWord, WordCloud, WordItem, WordPosition};
+
+// 像素碰撞模式缺少可用 canvas 时的提示
+const PIXEL_MODE_WARNING: &str =
+    "pixel collision mode needs a canvas context (measure_with_context), falling back to boxes";
+
+// 单词的像素精灵：按网格分辨率记录旋转后字形覆盖的单元。
+// 精灵原点为相对单词中心的偏移，单元 (u, v) 覆盖 [origin + u * cell, origin + (u + 1) * cell)
+#[derive(Clone)]
+pub(crate) struct Sprite {
+    origin_x: f64,
+    origin_y: f64,
+    cell: f64,
+    cols: usize,
+    rows: usize,
+    cells: Vec<bool>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置碰撞模式："box" 按旋转包围盒占用网格（默认），
+    // "pixel" 把单词栅格化后只占用有字形像素的网格，单词可以嵌进彼此的空白处。
+    // pixel 模式需要先通过 measure_with_context 提供 canvas 上下文
+    #[wasm_bindgen]
+    pub fn set_collision_mode(&mut self, mode: String) {
+        self.options.collision_mode = mode;
+    }
+}
+
+impl LayoutEngine {
+    // pixel 模式下为即将放置的单词生成精灵，box 模式或无法栅格化时为 None
+    pub(crate) fn word_sprite(
+        &mut self,
+        word: &WordItem,
+        measured: &MeasuredWord,
+        rotation: f64,
+    ) -> Option<Sprite> {
+        if self.options.collision_mode != "pixel" {
+            return None;
+        }
+        let sprite = self.rasterize_sprite(word, measured, rotation);
+        if sprite.is_none() && !self.warnings.iter().any(|w| w == PIXEL_MODE_WARNING) {
+            log(PIXEL_MODE_WARNING);
+            self.warnings.push(PIXEL_MODE_WARNING.to_string());
+        }
+        sprite
+    }
+
+    // 由宿主按旋转后的样子绘制单词，按网格单元读取 alpha 通道
+    fn rasterize_sprite(
+        &self,
+        word: &WordItem,
+        measured: &MeasuredWord,
+        rotation: f64,
+    ) -> Option<Sprite> {
+        let (min_x, min_y, max_x, max_y) =
+            Obb::new(0.0, 0.0, measured.width, measured.height, rotation).bounds();
+        // 四周各留 1 像素，避免抗锯齿边缘被裁掉
+        let width = (max_x - min_x).ceil() as u32 + 2;
+        let height = (max_y - min_y).ceil() as u32 + 2;
+        let (origin_x, origin_y) = (min_x - 1.0, min_y - 1.0);
+
+        let position = WordPosition {
+            text: word.text.clone(),
+            x: -origin_x,
+            y: -origin_y,
+            rotate: rotation,
+            color: Some("#000000".to_string()),
+            size: Some(measured.size),
+            parts: measured.parts.clone(),
+            phonetic: word.phonetic.clone(),
+            trend: word.trend.clone(),
+            ..WordPosition::default()
+        };
+        let pixels = self.host.rasterize_word(self, &position, width, height)?;
+
+        let cell = self.grid_size as f64;
+        let cols = (width as f64 / cell).ceil() as usize;
+        let rows = (height as f64 / cell).ceil() as usize;
+        let mut cells = vec![false; cols * rows];
+        for py in 0..height as usize {
+            for px in 0..width as usize {
+                if pixels[(py * width as usize + px) * 4 + 3] > 0 {
+                    cells[(py / self.grid_size) * cols + px / self.grid_size] = true;
+                }
+            }
+        }
+
+        Some(Sprite {
+            origin_x,
+            origin_y,
+            cell,
+            cols,
+            rows,
+            cells,
+        })
+    }
+
+    // 精灵放在 (x, y) 时覆盖的网格单元。精灵单元与网格不对齐时会跨越相邻单元，
+    // 此时全部计入，保证不会漏检
+    pub(crate) fn sprite_cells(&self, sprite: &Sprite, x: f64, y: f64) -> Vec<(usize, usize)> {
+        let grid_size = self.grid_size as f64;
+        let (columns, rows) = self.grid_dimensions();
+        let mut covered = Vec::new();
+        for v in 0..sprite.rows {
+            for u in 0..sprite.cols {
+                if !sprite.cells[v * sprite.cols + u] {
+                    continue;
+                }
+                let left = x + sprite.origin_x + u as f64 * sprite.cell;
+                let top = y + sprite.origin_y + v as f64 * sprite.cell;
+                let (i0, i1) = span(left, sprite.cell, grid_size);
+                let (j0, j1) = span(top, sprite.cell, grid_size);
+                for i in i0..=i1.min(columns.saturating_sub(1)) {
+                    for j in j0..=j1.min(rows.saturating_sub(1)) {
+                        covered.push((i, j));
+                    }
+                }
+            }
+        }
+        covered
+    }
+}
+
+// 区间 [start, start + length) 覆盖的网格下标范围，负坐标截断为 0
+fn span(start: f64, length: f64, grid_size: f64) -> (usize, usize) {
+    let first = (start / grid_size).floor().max(0.0) as usize;
+    let last = ((start + length) / grid_size).ceil().max(1.0) as usize - 1;
+    (first, last.max(first))
+}
+
+// 需要真实的 canvas 栅格化字形，在浏览器中运行（wasm-pack test --headless --firefox）
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+    use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+    use crate::{test_cloud, MeasuredWord, WordCloud, WordItem};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn context() -> CanvasRenderingContext2d {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let canvas: HtmlCanvasElement = document
+            .create_element("canvas")
+            .unwrap()
+            .dyn_into()
+            .unwrap();
+        canvas
+            .get_context("2d")
+            .unwrap()
+            .unwrap()
+            .dyn_into()
+            .unwrap()
+    }
+
+    fn measured(cloud: &WordCloud, text: &str, size: f64) -> MeasuredWord {
+        MeasuredWord {
+            size,
+            width: cloud.estimate_word_width(text, size),
+            height: size,
+            parts: None,
+        }
+    }
+
+    // 在画布中央放一个大号 "L"，检查小号 "T" 放进 L 右上方的空白处时是否碰撞
+    fn t_collides_inside_l(mode: &str) -> bool {
+        let mut cloud = test_cloud();
+        cloud.measure_with_context(&context());
+        cloud.set_collision_mode(mode.to_string());
+        cloud.grid_size = 1;
+        cloud.reset_grid();
+
+        let (cx, cy) = (200.0, 150.0);
+        let l = WordItem {
+            text: "L".to_string(),
+            ..WordItem::default()
+        };
+        let l_measured = measured(&cloud, "L", 200.0);
+        cloud.active_sprite = cloud.word_sprite(&l, &l_measured, 0.0);
+        cloud.mark_grid_as_occupied(cx, cy, l_measured.width, l_measured.height, 0.0);
+
+        let t = WordItem {
+            text: "T".to_string(),
+            ..WordItem::default()
+        };
+        let t_measured = measured(&cloud, "T", 50.0);
+        cloud.active_sprite = cloud.word_sprite(&t, &t_measured, 0.0);
+        let (x, y) = (cx + l_measured.width / 4.0, cy - l_measured.height / 4.0);
+        let collides = cloud.check_collision(x, y, t_measured.width, t_measured.height, 0.0);
+        cloud.active_sprite = None;
+        collides
+    }
+
+    #[wasm_bindgen_test]
+    fn l_and_t_nest_in_pixel_mode_but_not_in_box_mode() {
+        assert!(!t_collides_inside_l("pixel"));
+        assert!(t_collides_inside_l("box"));
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{LayoutEngine, WordCloud, WordItem};
+
+// 最近一次布局的统计信息
+#[derive(Clone, Default, Serialize)]
+pub(crate) struct LayoutStats {
+    pub(crate) placed: u32,
+    pub(crate) skipped: u32,
+    // 超出可用区域的单词数量（无论最终被缩小、跳过还是报错）
+    pub(crate) oversized: u32,
+    // 输入只有一个单词，走了居中放置的快速路径
+    pub(crate) single_word: bool,
+    // 因字号低于 min_render_size 而未放置的单词数量
+    pub(crate) demoted: u32,
+    // 计算字号时实际使用的权重范围
+    pub(crate) min_weight: f64,
+    pub(crate) max_weight: f64,
+}
+
+// 最近一次 generate_layout 的放置结果，供调用方据此减少单词或调小字号
+#[derive(Clone, Default, Serialize)]
+pub(crate) struct PlacementStats {
+    pub(crate) placed: u32,
+    pub(crate) skipped: u32,
+    // 螺旋搜索中检查过的候选位置总数
+    pub(crate) total_attempts: u32,
+    pub(crate) elapsed_ms: f64,
+}
+
+// 未能放置的单词及原因
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SkippedWord {
+    pub(crate) text: String,
+    pub(crate) weight: f64,
+    pub(crate) size: f64,
+    pub(crate) reason: String,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 获取最近一次布局的统计信息
+    #[wasm_bindgen]
+    pub fn get_layout_stats(&self) -> String {
+        serde_json::to_string(&self.layout_stats).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    // 获取最近一次 generate_layout 的放置统计：放置数、跳过数、尝试位置数和耗时
+    #[wasm_bindgen]
+    pub fn last_placement_stats(&self) -> String {
+        serde_json::to_string(&self.last_stats).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    // 获取最近一次布局中未能放置的单词
+    #[wasm_bindgen]
+    pub fn get_skipped(&self) -> String {
+        serde_json::to_string(&self.skipped).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+impl LayoutEngine {
+    // 布局结束时汇总放置统计
+    pub(crate) fn record_placement_stats(&mut self, elapsed_ms: f64) {
+        self.last_stats = PlacementStats {
+            placed: self.layout_stats.placed,
+            skipped: self.layout_stats.skipped,
+            total_attempts: self.placement_attempts.get(),
+            elapsed_ms,
+        };
+    }
+
+    // 记录一个未能放置的单词
+    pub(crate) fn record_skipped(&mut self, word: &WordItem, size: f64, reason: &str) {
+        self.layout_stats.skipped += 1;
+        self.skipped.push(SkippedWord {
+            text: word.text.clone(),
+            weight: word.weight,
+            size,
+            reason: reason.to_string(),
+        });
+    }
+}
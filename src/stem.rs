@@ -0,0 +1,282 @@
+// 英文词干提取：用于合并同一个词的不同形式（例如 cloud 和 clouds）
+
+// 以 s 结尾但不是复数的常见词，复数模式下保持不变
+const PLURAL_EXCEPTIONS: [&str; 24] = [
+    "always",
+    "analysis",
+    "basis",
+    "bias",
+    "bus",
+    "crisis",
+    "does",
+    "gas",
+    "has",
+    "his",
+    "is",
+    "its",
+    "lens",
+    "mathematics",
+    "news",
+    "perhaps",
+    "physics",
+    "series",
+    "species",
+    "status",
+    "this",
+    "thus",
+    "was",
+    "yes",
+];
+
+// 按模式提取词干："plural" 只去掉复数和所有格，"porter" 使用 Porter 词干算法，
+// 其他值保持原样
+pub(crate) fn stem(token: &str, mode: &str) -> String {
+    match mode {
+        "plural" => strip_plural(token),
+        "porter" => porter(&strip_possessive(token)),
+        _ => token.to_string(),
+    }
+}
+
+// 去掉所有格：cloud's -> cloud，clouds' -> clouds
+fn strip_possessive(token: &str) -> String {
+    if let Some(stripped) = token.strip_suffix("'s") {
+        stripped.to_string()
+    } else {
+        token.trim_end_matches('\'').to_string()
+    }
+}
+
+// 保守的复数还原：只处理 s / es / ies，过短的词和例外词保持不变
+fn strip_plural(token: &str) -> String {
+    let token = strip_possessive(token);
+    if token.chars().count() <= 3
+        || !token.is_ascii()
+        || PLURAL_EXCEPTIONS.contains(&token.as_str())
+        || token.ends_with("ss")
+        || token.ends_with("us")
+        || token.ends_with("is")
+    {
+        return token;
+    }
+    if let Some(stem) = token.strip_suffix("ies") {
+        if stem.len() >= 2 {
+            return format!("{}y", stem);
+        }
+    }
+    if let Some(stem) = token.strip_suffix("es") {
+        if ["sh", "ch", "ss", "x", "z"]
+            .iter()
+            .any(|end| stem.ends_with(end))
+        {
+            return stem.to_string();
+        }
+    }
+    match token.strip_suffix('s') {
+        Some(stem) => stem.to_string(),
+        None => token,
+    }
+}
+
+// Porter (1980) 词干算法，只处理 ASCII 小写字母组成的词
+fn porter(token: &str) -> String {
+    if token.len() <= 2 || !token.bytes().all(|b| b.is_ascii_lowercase()) {
+        return token.to_string();
+    }
+    let mut w: Vec<u8> = token.bytes().collect();
+    step1a(&mut w);
+    step1b(&mut w);
+    step1c(&mut w);
+    step2(&mut w);
+    step3(&mut w);
+    step4(&mut w);
+    step5(&mut w);
+    String::from_utf8(w).unwrap_or_else(|_| token.to_string())
+}
+
+// 第 i 个字母是否为辅音；y 在辅音之后视为元音
+fn is_consonant(w: &[u8], i: usize) -> bool {
+    match w[i] {
+        b'a' | b'e' | b'i' | b'o' | b'u' => false,
+        b'y' => i == 0 || !is_consonant(w, i - 1),
+        _ => true,
+    }
+}
+
+// 词干 [C](VC)^m[V] 中的 m
+fn measure(w: &[u8]) -> usize {
+    let mut m = 0;
+    let mut i = 0;
+    while i < w.len() && is_consonant(w, i) {
+        i += 1;
+    }
+    while i < w.len() {
+        while i < w.len() && !is_consonant(w, i) {
+            i += 1;
+        }
+        if i >= w.len() {
+            break;
+        }
+        while i < w.len() && is_consonant(w, i) {
+            i += 1;
+        }
+        m += 1;
+    }
+    m
+}
+
+fn has_vowel(w: &[u8]) -> bool {
+    (0..w.len()).any(|i| !is_consonant(w, i))
+}
+
+// 以两个相同的辅音结尾
+fn ends_double_consonant(w: &[u8]) -> bool {
+    let n = w.len();
+    n >= 2 && w[n - 1] == w[n - 2] && is_consonant(w, n - 1)
+}
+
+// 以 辅音-元音-辅音 结尾，且最后的辅音不是 w、x、y
+fn ends_cvc(w: &[u8]) -> bool {
+    let n = w.len();
+    n >= 3
+        && is_consonant(w, n - 3)
+        && !is_consonant(w, n - 2)
+        && is_consonant(w, n - 1)
+        && !matches!(w[n - 1], b'w' | b'x' | b'y')
+}
+
+// 若以 suffix 结尾且去掉后的词干满足条件，则替换为 replacement
+fn replace_suffix(
+    w: &mut Vec<u8>,
+    suffix: &str,
+    replacement: &str,
+    condition: impl Fn(&[u8]) -> bool,
+) -> bool {
+    if !w.ends_with(suffix.as_bytes()) {
+        return false;
+    }
+    let stem_len = w.len() - suffix.len();
+    if condition(&w[..stem_len]) {
+        w.truncate(stem_len);
+        w.extend_from_slice(replacement.as_bytes());
+    }
+    true
+}
+
+// 按顺序尝试规则表，第一条匹配后缀的规则生效（无论条件是否满足）
+fn apply_rules(w: &mut Vec<u8>, rules: &[(&str, &str)], min_measure: usize) {
+    for (suffix, replacement) in rules {
+        if replace_suffix(w, suffix, replacement, |stem| measure(stem) >= min_measure) {
+            return;
+        }
+    }
+}
+
+fn step1a(w: &mut Vec<u8>) {
+    for (suffix, replacement) in [("sses", "ss"), ("ies", "i"), ("ss", "ss"), ("s", "")] {
+        if replace_suffix(w, suffix, replacement, |_| true) {
+            return;
+        }
+    }
+}
+
+fn step1b(w: &mut Vec<u8>) {
+    if w.ends_with(b"eed") {
+        replace_suffix(w, "eed", "ee", |stem| measure(stem) > 0);
+        return;
+    }
+    let removed = ["ed", "ing"].iter().any(|suffix| {
+        w.ends_with(suffix.as_bytes()) && has_vowel(&w[..w.len() - suffix.len()]) && {
+            w.truncate(w.len() - suffix.len());
+            true
+        }
+    });
+    if !removed {
+        return;
+    }
+    if w.ends_with(b"at") || w.ends_with(b"bl") || w.ends_with(b"iz") {
+        w.push(b'e');
+    } else if ends_double_consonant(w) && !matches!(w[w.len() - 1], b'l' | b's' | b'z') {
+        w.pop();
+    } else if measure(w) == 1 && ends_cvc(w) {
+        w.push(b'e');
+    }
+}
+
+fn step1c(w: &mut [u8]) {
+    let n = w.len();
+    if w[n - 1] == b'y' && has_vowel(&w[..n - 1]) {
+        w[n - 1] = b'i';
+    }
+}
+
+fn step2(w: &mut Vec<u8>) {
+    const RULES: [(&str, &str); 20] = [
+        ("ational", "ate"),
+        ("tional", "tion"),
+        ("enci", "ence"),
+        ("anci", "ance"),
+        ("izer", "ize"),
+        ("abli", "able"),
+        ("alli", "al"),
+        ("entli", "ent"),
+        ("eli", "e"),
+        ("ousli", "ous"),
+        ("ization", "ize"),
+        ("ation", "ate"),
+        ("ator", "ate"),
+        ("alism", "al"),
+        ("iveness", "ive"),
+        ("fulness", "ful"),
+        ("ousness", "ous"),
+        ("aliti", "al"),
+        ("iviti", "ive"),
+        ("biliti", "ble"),
+    ];
+    apply_rules(w, &RULES, 1);
+}
+
+fn step3(w: &mut Vec<u8>) {
+    const RULES: [(&str, &str); 7] = [
+        ("icate", "ic"),
+        ("ative", ""),
+        ("alize", "al"),
+        ("iciti", "ic"),
+        ("ical", "ic"),
+        ("ful", ""),
+        ("ness", ""),
+    ];
+    apply_rules(w, &RULES, 1);
+}
+
+fn step4(w: &mut Vec<u8>) {
+    const SUFFIXES: [&str; 19] = [
+        "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ion",
+        "ou", "ism", "ate", "iti", "ous", "ive", "ize",
+    ];
+    // 按最长匹配选择后缀
+    let suffix = SUFFIXES
+        .iter()
+        .filter(|suffix| w.ends_with(suffix.as_bytes()))
+        .max_by_key(|suffix| suffix.len());
+    if let Some(suffix) = suffix {
+        let stem = &w[..w.len() - suffix.len()];
+        let allowed = *suffix != "ion" || matches!(stem.last(), Some(b's') | Some(b't'));
+        if allowed && measure(stem) > 1 {
+            w.truncate(stem.len());
+        }
+    }
+}
+
+fn step5(w: &mut Vec<u8>) {
+    if w.ends_with(b"e") {
+        let stem = &w[..w.len() - 1];
+        let m = measure(stem);
+        if m > 1 || (m == 1 && !ends_cvc(stem)) {
+            w.pop();
+        }
+    }
+    if measure(w) > 1 && ends_double_consonant(w) && w.ends_with(b"l") {
+        w.pop();
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::VecDeque;
+
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+use crate::{LayoutContext, WordCloud, WordItem};
+
+// 分步布局的中间状态
+#[derive(Clone)]
+pub(crate) struct PendingLayout {
+    words: VecDeque<WordItem>,
+    context: LayoutContext,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置让出策略："count" 按单词数量让出，"deadline" 按剩余时间让出
+    #[wasm_bindgen]
+    pub fn set_yield_strategy(&mut self, strategy: String) {
+        self.options.yield_strategy = strategy;
+    }
+
+    // count 模式下每一步放置的单词数量
+    #[wasm_bindgen]
+    pub fn set_yield_every(&mut self, count: u32) {
+        self.options.yield_every = count.max(1);
+    }
+
+    // deadline 模式下剩余时间低于该阈值（毫秒）时让出
+    #[wasm_bindgen]
+    pub fn set_yield_threshold_ms(&mut self, threshold_ms: f64) {
+        self.options.yield_threshold_ms = threshold_ms.max(0.0);
+    }
+
+    // 开始分步布局，之后反复调用 step_layout 直到返回 true。
+    // 这里不提供 async 的 generate_layout_async：wasm 上的 async 方法会在每次让出期间
+    // 一直持有实例的可变借用，期间 JS 对实例的任何其他调用都会抛出借用错误。
+    // 需要 await 时由 JS 驱动这些步骤，见 web/wordcloud.js 中的 generateCooperative
+    #[wasm_bindgen]
+    pub fn begin_layout(&mut self, words_json: String) -> bool {
+        match self.prepare_layout(&words_json) {
+            Some((words, context)) => {
+                self.pending = Some(PendingLayout {
+                    words: words.into(),
+                    context,
+                });
+                true
+            }
+            None => {
+                self.pending = None;
+                false
+            }
+        }
+    }
+
+    // 执行一步布局，全部单词处理完毕时返回 true
+    // deadline 为返回剩余毫秒数的 JS 函数（例如 IdleDeadline.timeRemaining），
+    // 仅在 deadline 模式下使用；每一步至少放置一个单词以保证进度
+    #[wasm_bindgen]
+    pub fn step_layout(&mut self, deadline: Option<Function>) -> bool {
+        let mut pending = match self.pending.take() {
+            Some(pending) => pending,
+            None => return true,
+        };
+
+        let use_deadline = self.options.yield_strategy == "deadline" && deadline.is_some();
+        let mut processed = 0u32;
+
+        while !pending.words.is_empty() {
+            self.place_next(&mut pending.words, &pending.context);
+            processed += 1;
+
+            let should_yield = if use_deadline {
+                let remaining = deadline
+                    .as_ref()
+                    .and_then(|f| f.call0(&JsValue::NULL).ok())
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.0);
+                remaining < self.options.yield_threshold_ms
+            } else {
+                processed >= self.options.yield_every
+            };
+
+            if should_yield {
+                break;
+            }
+        }
+
+        let finished = pending.words.is_empty();
+        if !finished {
+            self.pending = Some(pending);
+        }
+        finished
+    }
+
+    // 同步完成剩余的单词并返回布局 JSON
+    #[wasm_bindgen]
+    pub fn finish_layout(&mut self) -> String {
+        if let Some(mut pending) = self.pending.take() {
+            while !pending.words.is_empty() {
+                self.place_next(&mut pending.words, &pending.context);
+            }
+        }
+        self.layout_json()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cloud, weighted_words};
+
+    #[test]
+    fn count_mode_yields_every_n_words_and_matches_the_sync_layout() {
+        let expected = test_cloud().generate_layout(weighted_words(10));
+
+        let mut cloud = test_cloud();
+        cloud.set_yield_every(3);
+        assert!(cloud.begin_layout(weighted_words(10)));
+        let mut steps = 1;
+        while !cloud.step_layout(None) {
+            steps += 1;
+        }
+        assert_eq!(steps, 4);
+        assert_eq!(cloud.finish_layout(), expected);
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use js_sys::Function;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+
+    use crate::{test_cloud, weighted_words};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn deadline_mode_yields_on_decreasing_budgets_and_completes() {
+        let expected = test_cloud().generate_layout(weighted_words(10));
+
+        // 模拟 IdleDeadline.timeRemaining：每次调用剩余时间减少 4ms，耗尽后下一次空闲期重新开始
+        let deadline: Function = Function::new_no_args(
+            "let budget = 12; return () => { budget -= 4; if (budget > 0) return budget; budget = 12; return 0; };",
+        )
+        .call0(&wasm_bindgen::JsValue::NULL)
+        .unwrap()
+        .unchecked_into();
+
+        let mut cloud = test_cloud();
+        cloud.set_yield_strategy("deadline".to_string());
+        cloud.set_yield_threshold_ms(1.0);
+        assert!(cloud.begin_layout(weighted_words(10)));
+        let mut steps = 1;
+        while !cloud.step_layout(Some(deadline.clone())) {
+            steps += 1;
+        }
+        // 每个空闲期放置 3 个单词
+        assert_eq!(steps, 4);
+        assert_eq!(cloud.finish_layout(), expected);
+    }
+}
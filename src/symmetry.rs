@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+
+use crate::geometry::Obb;
+use crate::{LayoutContext, LayoutEngine, WordCloud, WordItem};
+
+// 寻找镜像配对单词时向后查看的单词数量
+const SYMMETRY_LOOKAHEAD: usize = 8;
+// 字号相差不超过该比例时视为大小相近
+const SYMMETRY_SIZE_TOLERANCE: f64 = 0.1;
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置对称布局："none"、"vertical"（关于竖直中线左右镜像）或 "horizontal"（上下镜像）
+    #[wasm_bindgen]
+    pub fn set_symmetry(&mut self, symmetry: String) {
+        self.options.symmetry = symmetry;
+    }
+}
+
+impl LayoutEngine {
+    // 放置队列中的下一个单词；对称模式下成功后尝试在镜像位置放置一个大小相近的单词
+    pub(crate) fn place_next(&mut self, words: &mut VecDeque<WordItem>, context: &LayoutContext) {
+        let word = match words.pop_front() {
+            Some(word) => word,
+            None => return,
+        };
+        if !self.admit_word(&word, context) {
+            let size = self.word_size(word.weight, context.min_weight, context.max_weight);
+            self.record_skipped(&word, size, "skip_policy");
+            return;
+        }
+        self.seek_mirror_room =
+            self.options.symmetry != "none" && self.has_mirror_candidate(&word, words, context);
+        let placed = self.place_word(word, context);
+        self.seek_mirror_room = false;
+        if placed {
+            self.consecutive_failures = 0;
+            if self.options.symmetry != "none" {
+                self.place_mirror_partner(words, context);
+            }
+        } else {
+            self.consecutive_failures += 1;
+        }
+    }
+
+    // 队列前部是否有与 word 大小相近、可以放在其镜像位置的单词
+    fn has_mirror_candidate(
+        &self,
+        word: &WordItem,
+        words: &VecDeque<WordItem>,
+        context: &LayoutContext,
+    ) -> bool {
+        let size = self.prepare_word(&mut word.clone(), context).0.size;
+        words.iter().take(SYMMETRY_LOOKAHEAD).any(|candidate| {
+            let (measured, _) = self.prepare_word(&mut candidate.clone(), context);
+            (measured.size - size).abs() <= size * SYMMETRY_SIZE_TOLERANCE
+        })
+    }
+
+    // 候选包围盒与其镜像互不妨碍且镜像位置空闲，为之后的配对单词留出位置
+    pub(crate) fn mirror_room(
+        &self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        rotation: f64,
+    ) -> bool {
+        let (mirror_x, mirror_y) = match self.options.symmetry.as_str() {
+            "vertical" => (self.options.width as f64 - x, y),
+            "horizontal" => (x, self.options.height as f64 - y),
+            _ => return true,
+        };
+        let own = Obb::new(x, y, width, height, rotation);
+        let mirrored = Obb::new(mirror_x, mirror_y, width, height, -rotation);
+        // 网格标记会向外多占一个单元，两者之间至少留出两个单元才不会互相阻挡
+        own.distance(&mirrored) > 2.0 * self.grid_size as f64
+            && !self.check_collision(mirror_x, mirror_y, width, height, -rotation)
+    }
+
+    // 镜像位置与旋转角度；镜像后仍与自身重叠（跨越对称轴）时返回 None
+    fn mirror_of(&self, index: usize) -> Option<(f64, f64, f64)> {
+        let word = &self.placed[index];
+        let (x, y) = match self.options.symmetry.as_str() {
+            "vertical" => (self.options.width as f64 - word.x, word.y),
+            "horizontal" => (word.x, self.options.height as f64 - word.y),
+            _ => return None,
+        };
+        let obb = self.word_obb(word);
+        let mirrored = Obb {
+            cx: x,
+            cy: y,
+            rotation: -word.rotate,
+            ..obb
+        };
+        (!mirrored.intersects(&obb)).then_some((x, y, -word.rotate))
+    }
+
+    fn place_mirror_partner(&mut self, words: &mut VecDeque<WordItem>, context: &LayoutContext) {
+        let anchor = self.placed.len() - 1;
+        let (x, y, rotation) = match self.mirror_of(anchor) {
+            Some(mirror) => mirror,
+            None => return,
+        };
+        let anchor_size = self.placed[anchor].size.unwrap_or(self.options.max_size);
+
+        for index in 0..words.len().min(SYMMETRY_LOOKAHEAD) {
+            // 在副本上准备，放置失败时队列中的单词保持原样
+            let mut candidate = words[index].clone();
+            let (measured, own_rotation) = self.prepare_word(&mut candidate, context);
+            if (measured.size - anchor_size).abs() > anchor_size * SYMMETRY_SIZE_TOLERANCE {
+                continue;
+            }
+
+            // 显式指定了旋转角度的单词保留自己的角度
+            let rotation = if candidate.rotate.is_some() {
+                own_rotation
+            } else {
+                rotation
+            };
+            let collision_rotation = self.collision_rotation(rotation);
+            let obb = Obb::new(x, y, measured.width, measured.height, collision_rotation);
+            if self.check_collision(x, y, measured.width, measured.height, collision_rotation)
+                || self.violates_word_constraints(&candidate, &obb)
+            {
+                continue;
+            }
+
+            words.remove(index);
+            let anchor_key = self.placed[anchor].key().to_string();
+            let partner_key = candidate.key().to_string();
+            self.commit_word(candidate, measured, x, y, rotation, context);
+            self.placed[anchor].mirror = Some(partner_key);
+            if let Some(partner) = self.placed.last_mut() {
+                partner.mirror = Some(anchor_key);
+            }
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::Obb;
+    use crate::{test_cloud, WordCloud};
+
+    fn equal_words(n: usize) -> String {
+        let words: Vec<serde_json::Value> = (0..n)
+            .map(|i| serde_json::json!({"text": format!("word{}", i), "weight": 1}))
+            .collect();
+        serde_json::to_string(&words).unwrap()
+    }
+
+    fn symmetric_layout(symmetry: &str) -> WordCloud {
+        let mut cloud = test_cloud();
+        cloud.set_symmetry(symmetry.to_string());
+        cloud.options.max_size = 20.0;
+        cloud.generate_layout(equal_words(20));
+        let boxes: Vec<Obb> = cloud.placed.iter().map(|w| cloud.word_obb(w)).collect();
+        for (i, a) in boxes.iter().enumerate() {
+            for b in &boxes[i + 1..] {
+                assert!(!a.intersects(b));
+            }
+        }
+        cloud
+    }
+
+    // 检查配对关系互相指向且位置关于对称轴镜像，返回配对单词的比例
+    fn paired_fraction(cloud: &WordCloud, mirrored: impl Fn(f64, f64, f64, f64) -> f64) -> f64 {
+        let mut paired = 0;
+        for word in &cloud.placed {
+            let partner_key = match &word.mirror {
+                Some(key) => key,
+                None => continue,
+            };
+            let partner = cloud
+                .placed
+                .iter()
+                .find(|w| w.key() == partner_key)
+                .unwrap();
+            assert_eq!(partner.mirror.as_deref(), Some(word.key()));
+            assert!(mirrored(word.x, word.y, partner.x, partner.y) < 1e-6);
+            paired += 1;
+        }
+        paired as f64 / cloud.placed.len() as f64
+    }
+
+    #[test]
+    fn vertical_symmetry_pairs_most_equal_words_about_the_centre_line() {
+        let cloud = symmetric_layout("vertical");
+        let width = cloud.options.width as f64;
+        let fraction = paired_fraction(&cloud, |xa, ya, xb, yb| {
+            (xa + xb - width).abs() + (ya - yb).abs()
+        });
+        assert!(fraction >= 0.8, "{}", fraction);
+    }
+
+    #[test]
+    fn horizontal_symmetry_pairs_words_about_the_middle() {
+        let cloud = symmetric_layout("horizontal");
+        let height = cloud.options.height as f64;
+        let fraction = paired_fraction(&cloud, |xa, ya, xb, yb| {
+            (xa - xb).abs() + (ya + yb - height).abs()
+        });
+        assert!(fraction >= 0.8, "{}", fraction);
+    }
+
+    #[test]
+    fn no_symmetry_pairs_nothing() {
+        let cloud = symmetric_layout("none");
+        assert!(cloud.placed.iter().all(|word| word.mirror.is_none()));
+    }
+}
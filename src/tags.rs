@@ -0,0 +1,47 @@
+use wasm_bindgen::prelude::*;
+
+use crate::env::log;
+use crate::{LayoutEngine, WordCloud};
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置显示的标签（JSON 字符串数组）：之后的布局跳过没有任何一个标签的单词，
+    // 输出的布局也只包含这些单词。传入空数组等同于清除筛选
+    #[wasm_bindgen]
+    pub fn set_visible_tags(&mut self, tags_json: String) -> bool {
+        match serde_json::from_str::<Vec<String>>(&tags_json) {
+            Ok(tags) => {
+                self.options.visible_tags = tags;
+                true
+            }
+            Err(e) => {
+                log(&format!("解析标签JSON失败: {}", e));
+                false
+            }
+        }
+    }
+
+    // 清除标签筛选，恢复显示所有单词
+    #[wasm_bindgen]
+    pub fn clear_tag_filter(&mut self) {
+        self.options.visible_tags.clear();
+    }
+
+    // 获取当前布局的 JSON，只包含符合标签筛选的单词
+    #[wasm_bindgen]
+    pub fn get_layout_json(&self) -> String {
+        self.layout_json()
+    }
+}
+
+impl LayoutEngine {
+    // 没有筛选时所有单词都可见，否则单词的标签需与筛选集合有交集
+    pub(crate) fn tags_visible(&self, tags: &Option<Vec<String>>) -> bool {
+        let visible = &self.options.visible_tags;
+        visible.is_empty()
+            || tags
+                .iter()
+                .flatten()
+                .any(|tag| visible.iter().any(|v| v == tag))
+    }
+}
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::color::{contrast_ratio, worst_case_distance, Rgb};
+use crate::env::log;
+use crate::rng::Rng;
+use crate::{LayoutEngine, WordCloud};
+
+// 主题调色板的颜色数量
+const THEME_PALETTE_SIZE: usize = 5;
+// 调色板不满足色差要求时重新选取基础色相的次数
+const THEME_ATTEMPTS: usize = 64;
+// 调整亮度以满足对比度时的步长和最大步数
+const LIGHTNESS_STEP: f64 = 0.02;
+const LIGHTNESS_STEPS: usize = 40;
+// 各颜色在满足对比度后再远离背景的亮度偏移，拉开颜色之间的亮度差
+const LIGHTNESS_SPREAD: [f64; THEME_PALETTE_SIZE] = [0.0, 0.12, 0.06, 0.18, 0.03];
+
+// 可保存和复用的样式主题
+#[derive(Serialize, Deserialize)]
+pub struct Theme {
+    background: String,
+    palette: Vec<String>,
+    // 旋转方式："none"、"right_angles"（0° 或 90°）或 "free"（±45° 内任意角度）
+    rotation: String,
+    // 字号范围："even"、"balanced" 或 "dramatic"
+    scale: String,
+}
+
+// 在 [0, 1) 的随机数上选取列表中的一项
+fn pick<'a>(rng: &mut Rng, items: &[&'a str]) -> &'a str {
+    items[(rng.next_f64() * items.len() as f64) as usize % items.len()]
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 由种子确定性地生成主题：背景色、与背景对比度足够且彼此可区分的 5 色调色板、
+    // 旋转方式和字号范围。对比度和色差阈值与 validate_palette 相同
+    #[wasm_bindgen]
+    pub fn random_theme(&self, seed: u64) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.generate_theme(seed)).unwrap_or(JsValue::NULL)
+    }
+
+    // 应用主题：一次设置背景色、调色板、旋转和字号范围
+    #[wasm_bindgen]
+    pub fn apply_theme(&mut self, theme: JsValue) -> bool {
+        match serde_wasm_bindgen::from_value(theme) {
+            Ok(theme) => {
+                self.set_theme(theme);
+                true
+            }
+            Err(e) => {
+                log(&format!("解析主题失败: {}", e));
+                false
+            }
+        }
+    }
+}
+
+impl LayoutEngine {
+    // 构建 random_theme 返回的主题
+    fn generate_theme(&self, seed: u64) -> Theme {
+        let mut rng = Rng::new(seed);
+        let dark = rng.next_f64() < 0.5;
+        let background = Rgb::from_hsl(rng.next_f64() * 360.0, 0.15, if dark { 0.1 } else { 0.96 });
+        let background = Rgb::parse(&background.to_hex()).unwrap_or(background);
+
+        // 多次尝试中保留颜色间最小色差最大的一组
+        let mut best: Option<(f64, Vec<Rgb>)> = None;
+        for _ in 0..THEME_ATTEMPTS {
+            let palette = self.theme_palette(&mut rng, background, dark);
+            let mut min_distance = f64::INFINITY;
+            for (i, a) in palette.iter().enumerate() {
+                for b in &palette[i + 1..] {
+                    min_distance = min_distance.min(worst_case_distance(*a, *b));
+                }
+            }
+            if best.as_ref().is_none_or(|(d, _)| min_distance > *d) {
+                best = Some((min_distance, palette));
+            }
+            if min_distance >= self.options.palette_min_distance {
+                break;
+            }
+        }
+
+        Theme {
+            background: background.to_hex(),
+            palette: best
+                .map(|(_, palette)| palette.iter().map(|c| c.to_hex()).collect())
+                .unwrap_or_default(),
+            rotation: pick(&mut rng, &["none", "right_angles", "free"]).to_string(),
+            scale: pick(&mut rng, &["even", "balanced", "dramatic"]).to_string(),
+        }
+    }
+
+    // 把主题写入选项，角度按当前的 rotation_unit 换算
+    fn set_theme(&mut self, theme: Theme) {
+        self.options.background_color = Some(theme.background);
+        self.options.palette = theme.palette;
+
+        // rotation_range 使用配置的角度单位，rotation_snap 总是角度制
+        let (range_degrees, snap) = match theme.rotation.as_str() {
+            "right_angles" => (90.0, 45.0),
+            "free" => (45.0, 0.0),
+            _ => (0.0, 0.0),
+        };
+        self.options.rotation_range = if self.options.rotation_unit == "degrees" {
+            range_degrees
+        } else {
+            f64::to_radians(range_degrees)
+        };
+        self.options.rotation_snap = snap;
+
+        let (min_size, max_size) = match theme.scale.as_str() {
+            "even" => (20.0, 40.0),
+            "dramatic" => (8.0, 96.0),
+            _ => (12.0, 60.0),
+        };
+        self.options.min_size = min_size;
+        self.options.max_size = max_size;
+    }
+
+    // 以随机基础色相按等间隔取色，逐步调整亮度直到与背景的对比度达到阈值
+    fn theme_palette(&self, rng: &mut Rng, background: Rgb, dark: bool) -> Vec<Rgb> {
+        let base_hue = rng.next_f64() * 360.0;
+        let direction = if dark { 1.0 } else { -1.0 };
+        (0..THEME_PALETTE_SIZE)
+            .map(|k| {
+                let hue = base_hue
+                    + k as f64 * 360.0 / THEME_PALETTE_SIZE as f64
+                    + (rng.next_f64() - 0.5) * 20.0;
+                let saturation = 0.6 + rng.next_f64() * 0.25;
+                // 按十六进制舍入后的颜色计算，与校验时一致
+                let color_at = |lightness: f64| {
+                    let color = Rgb::from_hsl(hue, saturation, lightness);
+                    Rgb::parse(&color.to_hex()).unwrap_or(color)
+                };
+                let mut lightness = 0.5;
+                for _ in 0..LIGHTNESS_STEPS {
+                    if contrast_ratio(color_at(lightness), background)
+                        >= self.options.palette_min_contrast
+                    {
+                        break;
+                    }
+                    lightness = (lightness + direction * LIGHTNESS_STEP).clamp(0.0, 1.0);
+                }
+                color_at((lightness + direction * LIGHTNESS_SPREAD[k]).clamp(0.0, 1.0))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_cloud;
+
+    // 用 JSON 比较主题，避免为测试给 Theme 派生 PartialEq
+    fn theme_json(cloud: &WordCloud, seed: u64) -> serde_json::Value {
+        serde_json::to_value(cloud.generate_theme(seed)).unwrap()
+    }
+
+    #[test]
+    fn same_seed_yields_an_identical_theme() {
+        let cloud = test_cloud();
+        assert_eq!(theme_json(&cloud, 7), theme_json(&cloud, 7));
+        assert_eq!(theme_json(&cloud, 7), theme_json(&test_cloud(), 7));
+        assert_ne!(theme_json(&cloud, 7), theme_json(&cloud, 8));
+    }
+
+    #[test]
+    fn apply_theme_updates_the_reported_options() {
+        let mut cloud = test_cloud();
+        let theme = Theme {
+            background: "#101820".to_string(),
+            palette: vec!["#ff8800".to_string(), "#33ccff".to_string()],
+            rotation: "right_angles".to_string(),
+            scale: "dramatic".to_string(),
+        };
+        // 主题先保存再读回，再应用
+        let saved = serde_json::to_string(&theme).unwrap();
+        cloud.set_theme(serde_json::from_str(&saved).unwrap());
+
+        let options: serde_json::Value = serde_json::from_str(&cloud.get_options()).unwrap();
+        assert_eq!(options["background_color"], "#101820");
+        assert_eq!(
+            options["palette"],
+            serde_json::json!(["#ff8800", "#33ccff"])
+        );
+        assert_eq!(options["rotation_snap"], 45.0);
+        assert_eq!(options["min_size"], 8.0);
+        assert_eq!(options["max_size"], 96.0);
+        let range = options["rotation_range"].as_f64().unwrap();
+        if options["rotation_unit"] == "degrees" {
+            assert_eq!(range, 90.0);
+        } else {
+            assert!((range - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn generated_palettes_pass_the_contrast_validator() {
+        let cloud = test_cloud();
+        for seed in 0..32 {
+            let theme = cloud.generate_theme(seed);
+            assert_eq!(theme.palette.len(), THEME_PALETTE_SIZE);
+            let report = serde_json::to_value(
+                cloud.palette_report(theme.palette.clone(), theme.background.clone()),
+            )
+            .unwrap();
+            assert_eq!(report["invalid"], serde_json::json!([]), "seed {}", seed);
+            for color in report["colors"].as_array().unwrap() {
+                assert_eq!(color["flagged"], false, "seed {}: {}", seed, color);
+            }
+        }
+    }
+}
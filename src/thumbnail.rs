@@ -0,0 +1,288 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::env::log;
+use crate::render::escape_xml;
+use crate::{LayoutEngine, WordCloud};
+
+// 合并小方块时每个密度块的边长是最小高度阈值的倍数
+const BLOB_CELL_FACTOR: f64 = 4.0;
+// 圆角半径占方块短边的比例
+const CORNER_RATIO: f64 = 0.25;
+
+// 缩略图中的一个圆角矩形：中心坐标、尺寸、旋转角度和颜色，words 为合并的单词数量
+#[derive(Serialize, Deserialize)]
+struct ThumbnailBox {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    rotate: f64,
+    color: String,
+    radius: f64,
+    words: u32,
+}
+
+// 缩略图描述：按比例缩放后的画布尺寸和方块列表
+#[derive(Serialize, Deserialize)]
+struct ThumbnailSpec {
+    width: f64,
+    height: f64,
+    boxes: Vec<ThumbnailBox>,
+}
+
+// 合并中的密度块：包围盒 (min_x, min_y, max_x, max_y)、颜色（取第一个单词）和单词数量
+struct Blob {
+    bounds: (f64, f64, f64, f64),
+    color: String,
+    words: u32,
+}
+
+// 解析缩略图描述，失败时返回 None
+fn parse_spec(spec_json: &str) -> Option<ThumbnailSpec> {
+    serde_json::from_str(spec_json)
+        .map_err(|e| {
+            log(&format!("解析缩略图JSON失败: {}", e));
+        })
+        .ok()
+}
+
+fn rounded_box(x: f64, y: f64, width: f64, height: f64, rotate: f64, color: &str) -> ThumbnailBox {
+    ThumbnailBox {
+        x,
+        y,
+        width,
+        height,
+        rotate,
+        color: color.to_string(),
+        radius: width.min(height) * CORNER_RATIO,
+        words: 1,
+    }
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 把当前布局转换为不含文字的缩略图描述：每个单词变成按 scale 缩放的圆角矩形。
+    // 缩放后高度低于 thumbnail_min_height 的单词被丢弃，
+    // 开启 thumbnail_merge_small 时改为按区域合并成密度块
+    #[wasm_bindgen]
+    pub fn export_thumbnail_spec(&self, scale: f64) -> String {
+        let scale = if scale > 0.0 { scale } else { 1.0 };
+        let min_height = self.options.thumbnail_min_height;
+
+        let mut boxes = Vec::new();
+        // 按区域坐标归组的密度块
+        let mut blobs: BTreeMap<(i64, i64), Blob> = BTreeMap::new();
+        let cell = (min_height * BLOB_CELL_FACTOR).max(1.0);
+
+        for word in &self.placed {
+            let (width, height) = self.word_box(word);
+            let color = word.color.as_deref().unwrap_or("#000000");
+            if height * scale >= min_height {
+                // 坐标和角度与布局输出一致，按配置的 y 轴方向和旋转单位给出
+                let position = self.emit_position(word);
+                boxes.push(rounded_box(
+                    self.round_output(position.x * scale),
+                    self.round_output(position.y * scale),
+                    self.round_output(width * scale),
+                    self.round_output(height * scale),
+                    position.rotate,
+                    color,
+                ));
+                continue;
+            }
+            if !self.options.thumbnail_merge_small {
+                continue;
+            }
+
+            let (min_x, min_y, max_x, max_y) = self.word_obb(word).bounds();
+            let (min_x, min_y, max_x, max_y) =
+                (min_x * scale, min_y * scale, max_x * scale, max_y * scale);
+            let key = (
+                (word.x * scale / cell).floor() as i64,
+                (word.y * scale / cell).floor() as i64,
+            );
+            let blob = blobs.entry(key).or_insert(Blob {
+                bounds: (min_x, min_y, max_x, max_y),
+                color: color.to_string(),
+                words: 0,
+            });
+            let bounds = blob.bounds;
+            blob.bounds = (
+                bounds.0.min(min_x),
+                bounds.1.min(min_y),
+                bounds.2.max(max_x),
+                bounds.3.max(max_y),
+            );
+            blob.words += 1;
+        }
+
+        for blob in blobs.into_values() {
+            let (min_x, min_y, max_x, max_y) = blob.bounds;
+            boxes.push(ThumbnailBox {
+                words: blob.words,
+                ..rounded_box(
+                    self.round_output((min_x + max_x) / 2.0),
+                    self.round_output(self.flip_y((min_y + max_y) / 2.0 / scale) * scale),
+                    self.round_output(max_x - min_x),
+                    self.round_output(max_y - min_y),
+                    0.0,
+                    &blob.color,
+                )
+            });
+        }
+
+        let spec = ThumbnailSpec {
+            width: self.round_output(self.options.width as f64 * scale),
+            height: self.round_output(self.options.height as f64 * scale),
+            boxes,
+        };
+        serde_json::to_string(&spec).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    // 设置缩略图的最小方块高度（像素），以及是否把更小的方块合并为密度块
+    #[wasm_bindgen]
+    pub fn set_thumbnail_options(&mut self, min_height: f64, merge_small: bool) {
+        self.options.thumbnail_min_height = min_height.max(0.0);
+        self.options.thumbnail_merge_small = merge_small;
+    }
+
+    // 把缩略图描述导出为 SVG
+    #[wasm_bindgen]
+    pub fn thumbnail_to_svg(&self, spec_json: String) -> String {
+        let spec = match parse_spec(&spec_json) {
+            Some(spec) => spec,
+            None => return String::new(),
+        };
+
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+            spec.width, spec.height, spec.width, spec.height
+        );
+        for b in &spec.boxes {
+            let y = self.thumbnail_y(&spec, b.y);
+            svg.push_str(&format!(
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" fill="{}" transform="rotate({} {} {})"/>"#,
+                b.x - b.width / 2.0,
+                y - b.height / 2.0,
+                b.width,
+                b.height,
+                b.radius,
+                escape_xml(&b.color),
+                self.svg_rotation(self.rotation_to_internal(b.rotate)),
+                b.x,
+                y
+            ));
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    // 把缩略图描述绘制到 canvas 上
+    #[wasm_bindgen]
+    pub fn draw_thumbnail(&self, spec_json: String, ctx: &CanvasRenderingContext2d) {
+        let spec = match parse_spec(&spec_json) {
+            Some(spec) => spec,
+            None => return,
+        };
+
+        ctx.clear_rect(0.0, 0.0, spec.width, spec.height);
+        for b in &spec.boxes {
+            let (half_width, half_height) = (b.width / 2.0, b.height / 2.0);
+            let radius = b.radius.min(half_width).min(half_height);
+
+            ctx.save();
+            let _ = ctx.translate(b.x, self.thumbnail_y(&spec, b.y));
+            let _ = ctx.rotate(self.rotation_to_internal(b.rotate));
+            ctx.begin_path();
+            ctx.move_to(-half_width + radius, -half_height);
+            let _ = ctx.arc_to(half_width, -half_height, half_width, half_height, radius);
+            let _ = ctx.arc_to(half_width, half_height, -half_width, half_height, radius);
+            let _ = ctx.arc_to(-half_width, half_height, -half_width, -half_height, radius);
+            let _ = ctx.arc_to(-half_width, -half_height, half_width, -half_height, radius);
+            ctx.close_path();
+            ctx.set_fill_style_str(&b.color);
+            ctx.fill();
+            ctx.restore();
+        }
+    }
+}
+
+impl LayoutEngine {
+    // 缩略图中的 y 按配置的 y 轴方向给出，绘制时换算回画布坐标
+    fn thumbnail_y(&self, spec: &ThumbnailSpec, y: f64) -> f64 {
+        if self.y_up() {
+            spec.height - y
+        } else {
+            y
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_cloud, weighted_words};
+
+    const SCALE: f64 = 0.3;
+    const MIN_HEIGHT: f64 = 6.0;
+
+    fn thumbnail(merge_small: bool) -> (WordCloud, ThumbnailSpec) {
+        let mut cloud = test_cloud();
+        cloud.set_thumbnail_options(MIN_HEIGHT, merge_small);
+        cloud.generate_layout(weighted_words(40));
+        let spec = parse_spec(&cloud.export_thumbnail_spec(SCALE)).unwrap();
+        (cloud, spec)
+    }
+
+    #[test]
+    fn boxes_fit_the_scaled_bounds() {
+        for merge_small in [false, true] {
+            let (cloud, spec) = thumbnail(merge_small);
+            assert!(!spec.boxes.is_empty());
+            assert!(spec.boxes.len() <= cloud.placed.len());
+            assert_eq!(spec.width, cloud.options.width as f64 * SCALE);
+            assert_eq!(spec.height, cloud.options.height as f64 * SCALE);
+
+            for b in &spec.boxes {
+                // 旋转后的半宽半高
+                let (sin, cos) = cloud.rotation_to_internal(b.rotate).sin_cos();
+                let half_x = (b.width * cos.abs() + b.height * sin.abs()) / 2.0;
+                let half_y = (b.width * sin.abs() + b.height * cos.abs()) / 2.0;
+                // 输出坐标经过取整，允许少量误差
+                let tolerance = 0.5;
+                assert!(b.x - half_x >= -tolerance && b.x + half_x <= spec.width + tolerance);
+                assert!(b.y - half_y >= -tolerance && b.y + half_y <= spec.height + tolerance);
+            }
+        }
+    }
+
+    #[test]
+    fn min_height_threshold_drops_small_words() {
+        let (cloud, spec) = thumbnail(false);
+        let expected: Vec<(f64, f64)> = cloud
+            .placed
+            .iter()
+            .filter(|word| cloud.word_box(word).1 * SCALE >= MIN_HEIGHT)
+            .map(|word| (word.x, word.y))
+            .collect();
+        // 阈值需要真正过滤掉一部分单词，测试才有意义
+        assert!(!expected.is_empty() && expected.len() < cloud.placed.len());
+        assert_eq!(spec.boxes.len(), expected.len());
+        for (b, (x, y)) in spec.boxes.iter().zip(&expected) {
+            assert_eq!(b.words, 1);
+            assert!(b.height >= MIN_HEIGHT - 0.5);
+            assert!((b.x - x * SCALE).abs() <= 0.5 && (b.y - y * SCALE).abs() <= 0.5);
+        }
+    }
+
+    #[test]
+    fn merged_blobs_account_for_every_small_word() {
+        let (cloud, spec) = thumbnail(true);
+        let words: u32 = spec.boxes.iter().map(|b| b.words).sum();
+        assert_eq!(words as usize, cloud.placed.len());
+    }
+}
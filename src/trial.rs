@@ -0,0 +1,346 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::geometry::Obb;
+use crate::{LayoutEngine, WordCloud, WordItem, WordPosition};
+
+// 计算解决冲突的平移向量时最多推开的次数
+const RESOLVE_ITERATIONS: usize = 32;
+// 推开时额外留出的距离，避免恰好接触时的数值误差
+const RESOLVE_EPSILON: f64 = 0.01;
+
+// 平移向量（按配置的 y 轴方向）
+#[derive(Serialize)]
+struct Translation {
+    dx: f64,
+    dy: f64,
+}
+
+// 试放结果：是否合法、冲突的单词 key、是否超出画布或遮罩，
+// 以及使位置合法的平移向量（不考虑遮罩，无法解决时为空）
+#[derive(Serialize)]
+struct TrialResult {
+    legal: bool,
+    conflicts: Vec<String>,
+    out_of_bounds: bool,
+    outside_mask: bool,
+    resolution: Option<Translation>,
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 拖动预览：评估把单词 key 移到 (x, y)、旋转 rotate 时是否合法，结束后布局保持不变。
+    // 单词自身原来的位置不参与检测；单词不存在时返回 null
+    #[wasm_bindgen]
+    pub fn trial_place(&mut self, key: String, x: f64, y: f64, rotate: f64) -> JsValue {
+        match self.trial(&key, x, y, rotate) {
+            Some(result) => serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL),
+            None => JsValue::NULL,
+        }
+    }
+
+    // 把单词 key 移到 (x, y)、旋转 rotate；位置不合法时不做修改并返回 false
+    #[wasm_bindgen]
+    pub fn commit_place(&mut self, key: String, x: f64, y: f64, rotate: f64) -> bool {
+        let legal = self
+            .trial(&key, x, y, rotate)
+            .is_some_and(|result| result.legal);
+        let index = match self.placed.iter().position(|word| word.key() == key) {
+            Some(index) if legal => index,
+            _ => return false,
+        };
+
+        self.placed[index] = self.moved_word(index, x, y, rotate);
+        self.rebuild_grid();
+        self.touch_layout();
+        true
+    }
+}
+
+impl LayoutEngine {
+    fn trial(&mut self, key: &str, x: f64, y: f64, rotate: f64) -> Option<TrialResult> {
+        let index = self.placed.iter().position(|word| word.key() == key)?;
+        let moved = self.moved_word(index, x, y, rotate);
+        let obb = self.collision_obb(&moved);
+
+        let conflicts: Vec<String> = self
+            .conflicting(index, &obb)
+            .into_iter()
+            .map(|other| self.placed[other].key().to_string())
+            .collect();
+        let out_of_bounds = self.out_of_bounds(&obb).is_some();
+        let outside_mask = self.outside_mask(&obb);
+
+        // 遮罩无法用平移向量描述，只在有单词冲突或超出画布时计算
+        let candidates = if conflicts.is_empty() && !out_of_bounds {
+            Vec::new()
+        } else {
+            self.resolve_candidates(index, obb)
+        };
+
+        let item = WordItem {
+            text: moved.text.clone(),
+            key: moved.key.clone(),
+            color: moved.color.clone(),
+            ..WordItem::default()
+        };
+        let (free, resolution) = self.without_word(index, |cloud| {
+            // 与放置时相同的判定：网格、安全区、中心间距、精确包围盒和单词约束
+            let free = cloud.position_free(&item, &obb);
+            let resolution = candidates
+                .iter()
+                .filter_map(|&(direction, translation)| {
+                    cloud.settle(&item, obb, direction, translation)
+                })
+                .min_by(|a, b| a.0.hypot(a.1).total_cmp(&b.0.hypot(b.1)));
+            (free, resolution)
+        });
+        let legal = conflicts.is_empty() && !out_of_bounds && !outside_mask && free;
+        let resolution = resolution.map(|(dx, dy)| Translation {
+            dx: self.round_output(dx),
+            dy: self.round_output(self.flip_y(obb.cy + dy) - self.flip_y(obb.cy)),
+        });
+
+        Some(TrialResult {
+            legal,
+            conflicts,
+            out_of_bounds,
+            outside_mask,
+            resolution,
+        })
+    }
+
+    // 单词 index 移到 (x, y)、旋转 rotate（配置的单位和方向）后的位置，
+    // 有扩展包围盒时按原来的扩展量在新位置和角度上重新计算
+    fn moved_word(&self, index: usize, x: f64, y: f64, rotate: f64) -> WordPosition {
+        let word = &self.placed[index];
+        let (width, height) = self.word_box(word);
+        let footprint = word.footprint.as_ref().and_then(|footprint| {
+            let inflation = footprint.inflation(
+                word.x,
+                word.y,
+                width,
+                height,
+                self.collision_rotation(word.rotate),
+            );
+            let (x, y) = (x, self.flip_y(y));
+            let rotation = self.collision_rotation(self.rotation_to_internal(rotate));
+            self.footprint_for(x, y, width, height, rotation, &inflation)
+        });
+        WordPosition {
+            x,
+            y: self.flip_y(y),
+            rotate: self.rotation_to_internal(rotate),
+            footprint,
+            ..word.clone()
+        }
+    }
+
+    // 暂时移除单词 index，在此状态下执行 f，完成后恢复原状。网格只清除并补标该单词
+    // 覆盖的单元；布局版本不变，移除期间暂存空间索引和中心索引，f 中按需重建的索引随后丢弃
+    fn without_word<T>(&mut self, index: usize, f: impl FnOnce(&LayoutEngine) -> T) -> T {
+        self.unmark_placed_word(index);
+        let original = self.placed.remove(index);
+        let spatial_index = self.spatial_index.take();
+        let center_index = self.center_index.take();
+
+        let result = f(self);
+
+        self.spatial_index.replace(spatial_index);
+        self.center_index.replace(center_index);
+        self.placed.insert(index, original);
+        self.mark_placed_word(index);
+        result
+    }
+
+    // 与 obb 相交的其他已放置单词
+    fn conflicting(&self, index: usize, obb: &Obb) -> Vec<usize> {
+        (0..self.placed.len())
+            .filter(|&other| other != index)
+            .filter(|&other| self.collision_obb(&self.placed[other]).intersects(obb))
+            .collect()
+    }
+
+    // 超出画布时返回移回画布内所需的平移
+    fn out_of_bounds(&self, obb: &Obb) -> Option<(f64, f64)> {
+        let (min_x, min_y, max_x, max_y) = obb.bounds();
+        let (width, height) = (self.options.width as f64, self.options.height as f64);
+        let shift = |min: f64, max: f64, limit: f64| {
+            if min < 0.0 {
+                -min
+            } else if max > limit {
+                limit - max
+            } else {
+                0.0
+            }
+        };
+        let (dx, dy) = (shift(min_x, max_x, width), shift(min_y, max_y, height));
+        (dx != 0.0 || dy != 0.0).then_some((dx, dy))
+    }
+
+    // 包围盒覆盖的网格单元中有位于遮罩外的
+    fn outside_mask(&self, obb: &Obb) -> bool {
+        let mask = match &self.mask {
+            Some(mask) => mask,
+            None => return false,
+        };
+        let (width, height) = (self.options.width as f64, self.options.height as f64);
+        let cell = self.grid_size as f64;
+        let (min_x, min_y, max_x, max_y) = obb.bounds();
+        let point = |x: f64, y: f64| Obb::new(x, y, 0.0, 0.0, 0.0);
+        let mut y = (min_y / cell).floor() * cell + cell / 2.0;
+        while y < max_y {
+            let mut x = (min_x / cell).floor() * cell + cell / 2.0;
+            while x < max_x {
+                let inside_obb = obb.distance(&point(x, y)) == 0.0;
+                if inside_obb && !mask.contains(x, y, width, height) {
+                    return true;
+                }
+                x += cell;
+            }
+            y += cell;
+        }
+        false
+    }
+
+    // 先移回画布内，再沿若干候选方向（单词自身的四个边方向，以及与第一个冲突单词的最小平移方向）
+    // 推开冲突的单词，返回各方向及其平移。沿固定方向推开不会在两个相邻单词之间来回振荡
+    fn resolve_candidates(&self, index: usize, obb: Obb) -> Vec<((f64, f64), (f64, f64))> {
+        let (shift_x, shift_y) = self.out_of_bounds(&obb).unwrap_or((0.0, 0.0));
+        let start = Obb {
+            cx: obb.cx + shift_x,
+            cy: obb.cy + shift_y,
+            ..obb
+        };
+
+        let mut directions: Vec<(f64, f64)> = start
+            .axes()
+            .iter()
+            .flat_map(|&(x, y)| [(x, y), (-x, -y)])
+            .collect();
+        let nearest = self
+            .conflicting(index, &start)
+            .into_iter()
+            .find_map(|other| start.penetration(&self.collision_obb(&self.placed[other])));
+        if let Some((dx, dy)) = nearest {
+            let length = dx.hypot(dy);
+            if length > 0.0 {
+                directions.push((dx / length, dy / length));
+            }
+        }
+
+        directions
+            .into_iter()
+            .filter_map(|direction| {
+                self.sweep(index, start, direction)
+                    .map(|(dx, dy)| (direction, (dx + shift_x, dy + shift_y)))
+            })
+            .collect()
+    }
+
+    // 沿单位方向 direction 反复把 obb 推到刚好离开所有冲突的单词；
+    // 返回累计的平移，最终超出画布或推开次数用尽时返回 None
+    fn sweep(&self, index: usize, mut obb: Obb, direction: (f64, f64)) -> Option<(f64, f64)> {
+        let (start_x, start_y) = (obb.cx, obb.cy);
+        for _ in 0..RESOLVE_ITERATIONS {
+            let step = self
+                .conflicting(index, &obb)
+                .into_iter()
+                .map(|other| {
+                    obb.separation_along(&self.collision_obb(&self.placed[other]), direction)
+                })
+                .try_fold(0.0, |step: f64, distance| distance.map(|d| step.max(d)))?;
+            if step == 0.0 {
+                return self
+                    .out_of_bounds(&obb)
+                    .is_none()
+                    .then_some((obb.cx - start_x, obb.cy - start_y));
+            }
+            obb.cx += direction.0 * (step + RESOLVE_EPSILON);
+            obb.cy += direction.1 * (step + RESOLVE_EPSILON);
+        }
+        None
+    }
+
+    // 包围盒不再相交后，网格取整和间距限制可能仍使位置不可用：
+    // 从 translation 起沿 direction 每次前进一个网格，直到位置可用；超出画布或次数用尽时返回 None
+    fn settle(
+        &self,
+        item: &WordItem,
+        obb: Obb,
+        direction: (f64, f64),
+        translation: (f64, f64),
+    ) -> Option<(f64, f64)> {
+        let cell = self.grid_size as f64;
+        (0..RESOLVE_ITERATIONS).find_map(|step| {
+            let (dx, dy) = (
+                translation.0 + direction.0 * cell * step as f64,
+                translation.1 + direction.1 * cell * step as f64,
+            );
+            let candidate = Obb {
+                cx: obb.cx + dx,
+                cy: obb.cy + dy,
+                ..obb
+            };
+            (self.out_of_bounds(&candidate).is_none() && self.position_free(item, &candidate))
+                .then_some((dx, dy))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cloud, weighted_words};
+
+    #[test]
+    fn resolution_vector_makes_an_occupied_spot_legal() {
+        let mut cloud = test_cloud();
+        cloud.generate_layout(weighted_words(3));
+        let free = cloud.remaining_area();
+        let before: Vec<(f64, f64)> = cloud.placed.iter().map(|w| (w.x, w.y)).collect();
+
+        // 把 w2 拖到 w0 所在的位置：冲突的邻居是 w0
+        let target = (cloud.placed[0].x, cloud.placed[0].y);
+        let result = cloud.trial("w2", target.0, target.1, 0.0).unwrap();
+        assert!(!result.legal);
+        assert!(result.conflicts.contains(&"w0".to_string()));
+        assert!(!result.conflicts.contains(&"w2".to_string()));
+
+        // 按平移向量移动后重新试放即合法
+        let resolution = result.resolution.unwrap();
+        let (x, y) = (target.0 + resolution.dx, target.1 + resolution.dy);
+        let resolved = cloud.trial("w2", x, y, 0.0).unwrap();
+        assert!(resolved.legal);
+        assert!(resolved.conflicts.is_empty());
+
+        // 试放不修改布局
+        assert_eq!(cloud.remaining_area(), free);
+        let after: Vec<(f64, f64)> = cloud.placed.iter().map(|w| (w.x, w.y)).collect();
+        assert_eq!(before, after);
+
+        assert!(!cloud.commit_place("w2".to_string(), target.0, target.1, 0.0));
+        assert!(cloud.commit_place("w2".to_string(), x, y, 0.0));
+        let moved = cloud.placed.iter().find(|w| w.text == "w2").unwrap();
+        assert_eq!((moved.x, moved.y), (x, y));
+    }
+
+    #[test]
+    fn trial_keeps_the_layout_generation_and_cached_indices() {
+        let mut cloud = test_cloud();
+        cloud.set_min_word_center_distance(Some(8.0));
+        cloud.generate_layout(weighted_words(12));
+        let keys = cloud.words_in_rect_keys(0.0, 0.0, 400.0, 300.0);
+        let generation = cloud.generation;
+        let grid = cloud.grid.clone();
+
+        // 拖到自己原来的位置附近：自身的中心不参与间距检测
+        let (x, y) = (cloud.placed[2].x + 1.0, cloud.placed[2].y);
+        let key = cloud.placed[2].key().to_string();
+        assert!(cloud.trial(&key, x, y, 0.0).unwrap().legal);
+
+        assert_eq!(cloud.generation, generation);
+        assert_eq!(cloud.grid, grid);
+        assert!(cloud.spatial_index.borrow().is_some());
+        assert_eq!(cloud.words_in_rect_keys(0.0, 0.0, 400.0, 300.0), keys);
+    }
+}
@@ -0,0 +1,103 @@
+use wasm_bindgen::prelude::*;
+
+use crate::render::escape_xml;
+use crate::WordCloud;
+
+// 类别区域的填充不透明度
+const REGION_OPACITY: f64 = 0.15;
+
+type Point = (f64, f64);
+
+// 用半平面 {p | (p - origin) · normal <= 0} 裁剪凸多边形（Sutherland–Hodgman）
+fn clip_half_plane(polygon: &[Point], origin: Point, normal: Point) -> Vec<Point> {
+    let side = |p: Point| (p.0 - origin.0) * normal.0 + (p.1 - origin.1) * normal.1;
+    let mut clipped = Vec::new();
+    for (i, &current) in polygon.iter().enumerate() {
+        let next = polygon[(i + 1) % polygon.len()];
+        let (d_current, d_next) = (side(current), side(next));
+        if d_current <= 0.0 {
+            clipped.push(current);
+        }
+        if (d_current < 0.0 && d_next > 0.0) || (d_current > 0.0 && d_next < 0.0) {
+            let t = d_current / (d_current - d_next);
+            clipped.push((
+                current.0 + (next.0 - current.0) * t,
+                current.1 + (next.1 - current.1) * t,
+            ));
+        }
+    }
+    clipped
+}
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 按类别划分背景区域：以每个类别已放置单词中心的质心为站点计算 Voronoi 图，
+    // 每个单元用该类别颜色（类别中第一个单词的颜色）的半透明填充，返回 <path> 元素
+    #[wasm_bindgen]
+    pub fn get_category_voronoi_svg(&self) -> String {
+        // 类别 -> (坐标和, 单词数量, 颜色)，保持类别首次出现的顺序
+        let mut categories: Vec<(&str, Point, usize, &str)> = Vec::new();
+        for word in &self.placed {
+            let category = match &word.category {
+                Some(category) => category.as_str(),
+                None => continue,
+            };
+            let color = word.color.as_deref().unwrap_or("#000000");
+            match categories.iter_mut().find(|(name, ..)| *name == category) {
+                Some((_, sum, count, _)) => {
+                    sum.0 += word.x;
+                    sum.1 += word.y;
+                    *count += 1;
+                }
+                None => categories.push((category, (word.x, word.y), 1, color)),
+            }
+        }
+
+        let sites: Vec<Point> = categories
+            .iter()
+            .map(|(_, sum, count, _)| (sum.0 / *count as f64, sum.1 / *count as f64))
+            .collect();
+        let width = self.options.width as f64;
+        let height = self.options.height as f64;
+
+        let mut svg = String::new();
+        for (i, (category, _, _, color)) in categories.iter().enumerate() {
+            // 从整个画布出发，依次用与其他站点的垂直平分线裁剪
+            let mut cell = vec![(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)];
+            for (j, other) in sites.iter().enumerate() {
+                if i == j || cell.is_empty() {
+                    continue;
+                }
+                let site = sites[i];
+                let midpoint = ((site.0 + other.0) / 2.0, (site.1 + other.1) / 2.0);
+                let normal = (other.0 - site.0, other.1 - site.1);
+                if normal == (0.0, 0.0) {
+                    continue;
+                }
+                cell = clip_half_plane(&cell, midpoint, normal);
+            }
+            if cell.len() < 3 {
+                continue;
+            }
+
+            let mut path = String::new();
+            for (k, (x, y)) in cell.iter().enumerate() {
+                path.push_str(&format!(
+                    "{}{} {}",
+                    if k == 0 { "M" } else { "L" },
+                    self.round_output(*x),
+                    self.round_output(*y)
+                ));
+            }
+            path.push('Z');
+            svg.push_str(&format!(
+                r#"<path d="{}" fill="{}" fill-opacity="{}" data-category="{}"/>"#,
+                path,
+                escape_xml(color),
+                REGION_OPACITY,
+                escape_xml(category)
+            ));
+        }
+        svg
+    }
+}
@@ -0,0 +1,104 @@
+use std::f64::consts::FRAC_PI_6;
+
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::render::escape_xml;
+use crate::{LayoutEngine, WordCloud};
+
+// 水印文字的字号、颜色，以及平铺时横向和纵向的间距
+const WATERMARK_FONT_SIZE: f64 = 24.0;
+const WATERMARK_COLOR: &str = "#000000";
+const WATERMARK_GAP: f64 = 48.0;
+const WATERMARK_ROW_HEIGHT: f64 = 96.0;
+// 水印沿左下到右上的对角线方向倾斜 30°
+const WATERMARK_ANGLE: f64 = -FRAC_PI_6;
+
+#[wasm_bindgen]
+impl WordCloud {
+    // 设置平铺水印文字及其不透明度，传入 None 表示不加水印
+    #[wasm_bindgen]
+    pub fn set_watermark(&mut self, text: Option<String>, opacity: f64) {
+        self.options.watermark_text = text;
+        self.options.watermark_opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    // 设置水印文字后生成布局。水印只在导出时绘制在背景上，不占用网格，不影响单词的位置
+    #[wasm_bindgen]
+    pub fn generate_layout_with_canvas_watermark(
+        &mut self,
+        words_json: String,
+        watermark_text: String,
+    ) -> String {
+        self.options.watermark_text = Some(watermark_text);
+        self.generate_layout(words_json)
+    }
+}
+
+impl LayoutEngine {
+    // 单个水印平铺单元的宽和高
+    fn watermark_tile(&self, text: &str) -> (f64, f64) {
+        (
+            self.estimate_word_width(text, WATERMARK_FONT_SIZE) + WATERMARK_GAP,
+            WATERMARK_ROW_HEIGHT,
+        )
+    }
+
+    // 以倾斜的 <pattern> 平铺水印文字，并用覆盖整个画布的 <rect> 引用
+    pub(crate) fn svg_watermark(&self) -> Option<String> {
+        let text = self.options.watermark_text.as_deref()?;
+        let (tile_width, tile_height) = self.watermark_tile(text);
+        Some(format!(
+            r#"<defs><pattern id="watermark" width="{}" height="{}" patternUnits="userSpaceOnUse" patternTransform="rotate({})"><text x="{}" y="{}" font-family="{}" font-size="{}" fill="{}" fill-opacity="{}" text-anchor="middle" dominant-baseline="middle">{}</text></pattern></defs><rect width="{}" height="{}" fill="url(#watermark)"/>"#,
+            tile_width,
+            tile_height,
+            WATERMARK_ANGLE.to_degrees(),
+            tile_width / 2.0,
+            tile_height / 2.0,
+            escape_xml(&self.options.font_family),
+            WATERMARK_FONT_SIZE,
+            WATERMARK_COLOR,
+            self.options.watermark_opacity,
+            escape_xml(text),
+            self.options.width,
+            self.options.height
+        ))
+    }
+}
+
+impl WordCloud {
+    // 在 canvas 上逐个绘制水印，旋转后的平铺范围取画布对角线长度以覆盖四角
+    pub(crate) fn draw_watermark(&self, ctx: &CanvasRenderingContext2d) {
+        let text = match self.options.watermark_text.as_deref() {
+            Some(text) => text,
+            None => return,
+        };
+        let (tile_width, tile_height) = self.watermark_tile(text);
+        let width = self.options.width as f64;
+        let height = self.options.height as f64;
+        let extent = (width * width + height * height).sqrt() / 2.0;
+
+        ctx.save();
+        ctx.set_global_alpha(self.options.watermark_opacity);
+        ctx.set_fill_style_str(WATERMARK_COLOR);
+        ctx.set_font(&format!(
+            "{}px {}",
+            WATERMARK_FONT_SIZE, self.options.font_family
+        ));
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+        let _ = ctx.translate(width / 2.0, height / 2.0);
+        let _ = ctx.rotate(WATERMARK_ANGLE);
+
+        let mut y = -(extent / tile_height).ceil() * tile_height;
+        while y <= extent {
+            let mut x = -(extent / tile_width).ceil() * tile_width;
+            while x <= extent {
+                let _ = ctx.fill_text(text, x, y);
+                x += tile_width;
+            }
+            y += tile_height;
+        }
+        ctx.restore();
+    }
+}
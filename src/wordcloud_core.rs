@@ -0,0 +1,384 @@
+//! 面向 Rust 调用方的类型化接口：用构建器配置布局，输入和输出都是普通结构体而非 JSON 字符串。
+//! 内部直接驱动不依赖 wasm-bindgen 的 LayoutEngine，WordCloud 的 wasm 接口也委托给它，两者共用同一套实现
+//!
+//! ```
+//! use wordcloud::wordcloud_core::{LayoutBuilder, PlacementOrder, Spiral, Word};
+//!
+//! let mut layout = LayoutBuilder::new(400, 300)
+//!     .font_family("sans-serif")
+//!     .font_sizes(10.0, 40.0)
+//!     .spiral(Spiral::Rectangular)
+//!     .order(PlacementOrder::WeightDescending)
+//!     .seed(42)
+//!     .build();
+//!
+//! let words = [Word::new("rust", 10.0), Word::new("wasm", 6.0)];
+//! let result = layout.place(&words);
+//! assert_eq!(result.placed_count() + result.skipped().count(), words.len());
+//! for word in result.placed() {
+//!     assert!(word.x >= 0.0 && word.x <= 400.0);
+//!     assert!(word.size >= 10.0 && word.size <= 40.0);
+//! }
+//! ```
+
+use crate::{LayoutEngine, WordItem, DEFAULT_GRID_SIZE};
+
+// 螺旋类型
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Spiral {
+    #[default]
+    Archimedean,
+    Rectangular,
+}
+
+impl Spiral {
+    fn as_str(self) -> &'static str {
+        match self {
+            Spiral::Archimedean => "archimedean",
+            Spiral::Rectangular => "rectangular",
+        }
+    }
+}
+
+// 权重到字号的映射方式
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Scale {
+    #[default]
+    Linear,
+}
+
+// 单词的放置顺序
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlacementOrder {
+    // 按输入顺序放置
+    #[default]
+    Input,
+    // 按权重从高到低放置，权重相同时保持输入顺序
+    WeightDescending,
+}
+
+// 输入的单词；rotate 为弧度，None 时按 rotation_range 随机旋转
+#[derive(Clone, Debug, PartialEq)]
+pub struct Word {
+    pub text: String,
+    pub weight: f64,
+    pub color: Option<String>,
+    pub rotate: Option<f64>,
+}
+
+impl Word {
+    pub fn new(text: impl Into<String>, weight: f64) -> Word {
+        Word {
+            text: text.into(),
+            weight,
+            color: None,
+            rotate: None,
+        }
+    }
+}
+
+// 布局配置的构建器
+#[derive(Clone, Debug)]
+pub struct LayoutBuilder {
+    width: u32,
+    height: u32,
+    font_family: String,
+    font_weight: String,
+    min_size: f64,
+    max_size: f64,
+    rotation_range: f64,
+    grid_size: usize,
+    seed: Option<u64>,
+    spiral: Spiral,
+    scale: Scale,
+    order: PlacementOrder,
+}
+
+impl LayoutBuilder {
+    pub fn new(width: u32, height: u32) -> LayoutBuilder {
+        LayoutBuilder {
+            width,
+            height,
+            font_family: "sans-serif".to_string(),
+            font_weight: "normal".to_string(),
+            min_size: 10.0,
+            max_size: 60.0,
+            rotation_range: 0.0,
+            grid_size: DEFAULT_GRID_SIZE,
+            seed: None,
+            spiral: Spiral::default(),
+            scale: Scale::default(),
+            order: PlacementOrder::default(),
+        }
+    }
+
+    pub fn font_family(mut self, font_family: impl Into<String>) -> LayoutBuilder {
+        self.font_family = font_family.into();
+        self
+    }
+
+    pub fn font_weight(mut self, font_weight: impl Into<String>) -> LayoutBuilder {
+        self.font_weight = font_weight.into();
+        self
+    }
+
+    pub fn font_sizes(mut self, min_size: f64, max_size: f64) -> LayoutBuilder {
+        self.min_size = min_size;
+        self.max_size = max_size;
+        self
+    }
+
+    // 随机旋转的最大角度（弧度）
+    pub fn rotation_range(mut self, rotation_range: f64) -> LayoutBuilder {
+        self.rotation_range = rotation_range;
+        self
+    }
+
+    // 碰撞网格每个单元的像素大小，范围 [1, 32]
+    pub fn grid_size(mut self, grid_size: usize) -> LayoutBuilder {
+        self.grid_size = grid_size.max(1);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> LayoutBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn spiral(mut self, spiral: Spiral) -> LayoutBuilder {
+        self.spiral = spiral;
+        self
+    }
+
+    pub fn scale(mut self, scale: Scale) -> LayoutBuilder {
+        self.scale = scale;
+        self
+    }
+
+    pub fn order(mut self, order: PlacementOrder) -> LayoutBuilder {
+        self.order = order;
+        self
+    }
+
+    pub fn build(self) -> Layout {
+        let mut engine = LayoutEngine::new(
+            self.width,
+            self.height,
+            self.font_family,
+            self.font_weight,
+            self.min_size,
+            self.max_size,
+            self.grid_size,
+        );
+        engine.options.rotation_range = self.rotation_range;
+        engine.options.spiral = self.spiral.as_str().to_string();
+        engine.options.seed = self.seed;
+        // 目前只有线性映射，Scale 为以后扩展保留
+        let Scale::Linear = self.scale;
+        Layout {
+            engine,
+            order: self.order,
+        }
+    }
+}
+
+// 可重复使用的布局器，每次 place 都从空画布开始
+pub struct Layout {
+    engine: LayoutEngine,
+    order: PlacementOrder,
+}
+
+impl Layout {
+    pub fn place(&mut self, words: &[Word]) -> PlacementResult<'_> {
+        let mut items: Vec<WordItem> = words
+            .iter()
+            .map(|word| WordItem {
+                text: word.text.clone(),
+                weight: word.weight,
+                color: word.color.clone(),
+                rotate: word.rotate,
+                ..WordItem::default()
+            })
+            .collect();
+        if self.order == PlacementOrder::WeightDescending {
+            items.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        }
+
+        self.engine.reset_layout_state();
+        if let Some((items, context)) = self.engine.prepare_words(items) {
+            self.engine.place_all(items, &context);
+        }
+
+        PlacementResult {
+            engine: &self.engine,
+        }
+    }
+}
+
+// 一次布局的结果，借用 Layout 内部的数据，遍历时不复制单词
+pub struct PlacementResult<'a> {
+    engine: &'a LayoutEngine,
+}
+
+impl<'a> PlacementResult<'a> {
+    // 已放置的单词，按放置顺序。与 wasm 接口的输出相同，
+    // 坐标和角度按 y_axis、rotation_unit 和输出精度转换
+    pub fn placed(&self) -> impl Iterator<Item = PlacedWord<'a>> + 'a {
+        let engine = self.engine;
+        engine
+            .placed
+            .iter()
+            .filter(move |word| engine.tags_visible(&word.tags))
+            .map(move |word| PlacedWord {
+                text: &word.text,
+                weight: word.weight,
+                x: engine.round_output(word.x),
+                y: engine.round_output(engine.flip_y(word.y)),
+                rotate: engine.emitted_rotation(word.rotate),
+                size: engine.round_output(word.size.unwrap_or_default()),
+                color: word.color.as_deref(),
+            })
+    }
+
+    // 未能放置的单词，按尝试放置的顺序
+    pub fn skipped(&self) -> impl Iterator<Item = SkippedWord<'a>> + 'a {
+        self.engine.skipped.iter().map(|word| SkippedWord {
+            text: &word.text,
+            weight: word.weight,
+            size: word.size,
+            reason: &word.reason,
+        })
+    }
+
+    pub fn placed_count(&self) -> usize {
+        self.placed().count()
+    }
+}
+
+// 已放置的单词：中心坐标、旋转角度（弧度）和字号
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlacedWord<'a> {
+    pub text: &'a str,
+    pub weight: f64,
+    pub x: f64,
+    pub y: f64,
+    pub rotate: f64,
+    pub size: f64,
+    pub color: Option<&'a str>,
+}
+
+// 未能放置的单词：尝试时的字号和原因，原因与 wasm 接口 get_skipped 中的 reason 相同，
+// 如 "no_space"
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SkippedWord<'a> {
+    pub text: &'a str,
+    pub weight: f64,
+    pub size: f64,
+    pub reason: &'a str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn place_runs_natively_and_is_reproducible_with_a_seed() {
+        let words = [
+            Word::new("alpha", 10.0),
+            Word::new("beta", 6.0),
+            Word::new("gamma", 3.0),
+        ];
+        let mut layout = LayoutBuilder::new(400, 300).seed(7).build();
+        let first: Vec<(String, f64, f64)> = layout
+            .place(&words)
+            .placed()
+            .map(|word| (word.text.to_string(), word.x, word.y))
+            .collect();
+        assert_eq!(first.len(), 3);
+
+        let second: Vec<(String, f64, f64)> = layout
+            .place(&words)
+            .placed()
+            .map(|word| (word.text.to_string(), word.x, word.y))
+            .collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn place_emits_output_coordinates() {
+        let words = [Word::new("alpha", 10.0)];
+        let mut layout = LayoutBuilder::new(400, 300).seed(1).build();
+        let down: Vec<f64> = layout.place(&words).placed().map(|word| word.y).collect();
+
+        layout.engine.options.y_axis = "up".to_string();
+        let up: Vec<f64> = layout.place(&words).placed().map(|word| word.y).collect();
+        assert_eq!(up.len(), 1);
+        assert!((up[0] - (300.0 - down[0])).abs() < 1e-9);
+    }
+
+    #[test]
+    fn skipped_words_report_weight_size_and_reason() {
+        let words: Vec<Word> = (0..12)
+            .map(|i| Word::new(format!("word{}", i), (12 - i) as f64))
+            .collect();
+        let mut layout = LayoutBuilder::new(120, 60)
+            .font_sizes(20.0, 30.0)
+            .seed(3)
+            .build();
+        let result = layout.place(&words);
+        let skipped: Vec<SkippedWord> = result.skipped().collect();
+        assert!(!skipped.is_empty());
+        assert_eq!(result.placed_count() + skipped.len(), words.len());
+
+        for word in &skipped {
+            let input = words.iter().find(|input| input.text == word.text).unwrap();
+            assert_eq!(word.weight, input.weight);
+            assert!(word.size >= 20.0 && word.size <= 30.0);
+            assert_eq!(word.reason, "no_space");
+        }
+    }
+
+    // 带种子的参考输入在 wasm 接口上的输出，重构前后必须逐字节一致
+    const REFERENCE_LAYOUT: &str = concat!(
+        r#"[{"text":"w0","weight":5.0,"x":200.0,"y":150.0,"rotate":0.0,"size":40.0},"#,
+        r#"{"text":"w1","weight":4.0,"x":199.51237583497092,"y":107.60280407062623,"rotate":0.0,"size":32.5},"#,
+        r#"{"text":"w2","weight":3.0,"x":206.6114910213638,"y":188.53697168271592,"rotate":0.0,"size":25.0},"#,
+        r#"{"text":"w3","weight":2.0,"x":160.57048691162151,"y":159.67850699299305,"rotate":0.0,"size":17.5},"#,
+        r#"{"text":"w4","weight":1.0,"x":176.49016362657164,"y":182.23813880630593,"rotate":0.0,"size":10.0}]"#
+    );
+
+    #[test]
+    fn wasm_output_is_byte_identical_for_a_seeded_reference_input() {
+        let mut cloud = crate::test_cloud();
+        cloud.set_seed(42);
+        assert_eq!(
+            cloud.generate_layout(crate::weighted_words(5)),
+            REFERENCE_LAYOUT
+        );
+
+        // 类型化接口在相同配置下给出相同的结果
+        let words: Vec<Word> = (0..5)
+            .map(|i| Word::new(format!("w{}", i), (5 - i) as f64))
+            .collect();
+        let mut layout = LayoutBuilder::new(400, 300)
+            .font_family("sans-serif")
+            .font_weight("normal")
+            .font_sizes(10.0, 40.0)
+            .grid_size(4)
+            .seed(42)
+            .build();
+        let expected: Vec<crate::WordPosition> = serde_json::from_str(REFERENCE_LAYOUT).unwrap();
+        let result = layout.place(&words);
+        let placed: Vec<PlacedWord> = result.placed().collect();
+        assert_eq!(placed.len(), expected.len());
+        for (word, expected) in placed.iter().zip(&expected) {
+            assert_eq!(word.text, expected.text);
+            assert_eq!((word.x, word.y), (expected.x, expected.y));
+            assert_eq!(word.rotate, expected.rotate);
+            assert_eq!(Some(word.size), expected.size);
+        }
+    }
+}
@@ -18,6 +18,7 @@ export class WordCloudRenderer {
       fontWeight: options.fontWeight || 'normal',
       minSize: options.minSize || 10,
       maxSize: options.maxSize || 60,
+      gridSize: options.gridSize || 4, // pixels per collision grid cell, 1-32
       rotationRange: options.rotationRange || 0, // 0 for no rotation
       spiral: options.spiral || 'archimedean', // 'archimedean' or 'rectangular'
       colors: options.colors || ['#1f77b4', '#ff7f0e', '#2ca02c', '#d62728', '#9467bd']
@@ -41,12 +42,16 @@ export class WordCloudRenderer {
         this.options.fontFamily,
         this.options.fontWeight,
         this.options.minSize,
-        this.options.maxSize
+        this.options.maxSize,
+        this.options.gridSize
       );
       
       // Set additional options
+      this.wordcloudInstance.set_rotation_unit('degrees');
       this.wordcloudInstance.set_rotation_range(this.options.rotationRange);
       this.wordcloudInstance.set_spiral(this.options.spiral);
+      // Measure word widths with the real canvas instead of the width heuristic
+      this.wordcloudInstance.measure_with_context(this.ctx);
       
       this.wasmLoaded = true;
       console.log('WordCloud WASM module loaded successfully');
@@ -61,8 +66,65 @@ export class WordCloudRenderer {
       return;
     }
     
-    // Prepare words input
-    const wordsWithColor = words.map((word, index) => {
+    // Generate layout; words and positions cross the boundary as plain objects
+    const layout = this.wordcloudInstance.generate_layout_js(this.prepareWords(words));
+    
+    this.drawLayout(layout);
+    
+    return layout;
+  }
+  
+  // Layout from clipboard text, weighted by word frequency
+  async generateFromClipboard() {
+    if (!this.wasmLoaded || !this.wordcloudInstance) {
+      console.error('WASM module not loaded');
+      return;
+    }
+    
+    // The wasm side lays out a copy of the instance, so it is not borrowed while waiting
+    const layout = JSON.parse(await this.wordcloudInstance.generate_layout_from_clipboard());
+    this.drawLayout(layout);
+    
+    return layout;
+  }
+  
+  // Cooperative layout: places words during browser idle periods.
+  // This is the awaitable form of the stepped API; it lives here rather than in
+  // wasm so the instance is not borrowed while the browser is idle.
+  async generateCooperative(words) {
+    if (!this.wasmLoaded || !this.wordcloudInstance) {
+      console.error('WASM module not loaded');
+      return;
+    }
+    
+    const instance = this.wordcloudInstance;
+    instance.set_yield_strategy('deadline');
+    
+    if (instance.begin_layout(JSON.stringify(this.prepareWords(words)))) {
+      const requestIdle = window.requestIdleCallback
+        ? window.requestIdleCallback.bind(window)
+        : (callback => setTimeout(() => callback({ timeRemaining: () => 8 }), 0));
+      
+      await new Promise(resolve => {
+        const step = deadline => {
+          if (instance.step_layout(() => deadline.timeRemaining())) {
+            resolve();
+          } else {
+            requestIdle(step);
+          }
+        };
+        requestIdle(step);
+      });
+    }
+    
+    const layout = JSON.parse(instance.finish_layout());
+    this.drawLayout(layout);
+    
+    return layout;
+  }
+  
+  prepareWords(words) {
+    return words.map((word, index) => {
       if (typeof word === 'string') {
         return {
           text: word,
@@ -75,23 +137,36 @@ export class WordCloudRenderer {
           weight: word[1] || 1,
           color: word[2] || this.getColor(index)
         };
+      } else if (Array.isArray(word.parts)) {
+        // Compound word, e.g. { parts: [{ text, weight }], separator: ' & ' }
+        return {
+          ...word,
+          color: word.color || this.getColor(index)
+        };
       } else {
         return {
           text: word.text,
           weight: word.weight || 1,
           color: word.color || this.getColor(index),
-          rotate: word.rotate
+          rotate: word.rotate,
+          annotation: word.annotation,
+          annotation_direction: word.annotationDirection,
+          gradient_angle: word.gradientAngle,
+          weight_ci_low: word.weightCiLow,
+          weight_ci_high: word.weightCiHigh,
+          category: word.category,
+          tags: word.tags,
+          audio_url: word.audioUrl,
+          phonetic: word.phonetic,
+          trend: word.trend,
+          aria_label: word.ariaLabel,
+          timestamp: word.timestamp
         };
       }
     });
-    
-    // Convert to JSON for WASM
-    const wordsJson = JSON.stringify(wordsWithColor);
-    
-    // Generate layout
-    const layoutJson = this.wordcloudInstance.generate_layout(wordsJson);
-    const layout = JSON.parse(layoutJson);
-    
+  }
+  
+  drawLayout(layout) {
     // Clear canvas
     this.ctx.clearRect(0, 0, this.width, this.height);
     
@@ -99,8 +174,6 @@ export class WordCloudRenderer {
     layout.forEach(word => {
       this.drawWord(word);
     });
-    
-    return layout;
   }
   
   drawWord(word) {
@@ -108,6 +181,11 @@ export class WordCloudRenderer {
     
     this.ctx.save();
     
+    // Faded words from time decay
+    if (typeof word.opacity === 'number') {
+      this.ctx.globalAlpha = word.opacity;
+    }
+    
     // Set font
     this.ctx.font = `${this.options.fontWeight} ${size}px ${this.options.fontFamily}`;
     
@@ -121,7 +199,16 @@ export class WordCloudRenderer {
     // Draw text
     this.ctx.textAlign = 'center';
     this.ctx.textBaseline = 'middle';
-    this.ctx.fillText(text, 0, 0);
+    if (Array.isArray(word.parts)) {
+      // Compound word: each part has its own size and offset from the anchor
+      word.parts.forEach(part => {
+        this.ctx.font = `${this.options.fontWeight} ${part.size}px ${this.options.fontFamily}`;
+        this.ctx.fillStyle = part.color || color || '#000000';
+        this.ctx.fillText(part.text, part.dx, part.dy);
+      });
+    } else {
+      this.ctx.fillText(text, 0, 0);
+    }
     
     this.ctx.restore();
   }